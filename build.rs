@@ -0,0 +1,100 @@
+//! Optionally generates the constants table from the platform `android.jar`, in place of the
+//! vendored `src/constants_table.rs`.
+//!
+//! Set `ANDROID_INTENT_ANDROID_JAR` to e.g. `$ANDROID_HOME/platforms/android-34/android.jar` (and
+//! have `javap` from a JDK on the `PATH`) to build against a table of every `ACTION_*`,
+//! `CATEGORY_*`, `EXTRA_*` and `FLAG_*` constant of the classes below. Without it, the vendored
+//! table is used.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+const JAR_VAR: &str = "ANDROID_INTENT_ANDROID_JAR";
+
+/// Classes whose constants are looked up by name, in `javap` notation.
+const CLASSES: &[&str] = &[
+    "android.app.admin.DevicePolicyManager",
+    "android.bluetooth.BluetoothAdapter",
+    "android.content.Intent",
+    "android.media.RingtoneManager",
+    "android.provider.MediaStore",
+    "android.provider.Settings",
+    "android.provider.Settings$Panel",
+];
+
+const PREFIXES: &[&str] = &["ACTION_", "CATEGORY_", "EXTRA_", "FLAG_"];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed={}", JAR_VAR);
+    println!("cargo:rustc-check-cfg=cfg(android_intent_generated_constants)");
+
+    let Some(jar) = env::var_os(JAR_VAR) else {
+        return;
+    };
+    println!("cargo:rerun-if-changed={}", PathBuf::from(&jar).display());
+
+    let mut strings = Vec::new();
+    let mut ints = Vec::new();
+    for class in CLASSES {
+        let output = Command::new("javap")
+            .arg("-constants")
+            .arg("-public")
+            .arg("-classpath")
+            .arg(&jar)
+            .arg(class)
+            .output()
+            .unwrap_or_else(|err| panic!("running javap for {}: {}", class, err));
+        if !output.status.success() {
+            panic!("javap failed for {}: {}", class, String::from_utf8_lossy(&output.stderr));
+        }
+
+        let jni_class = class.replace('.', "/");
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Some(declaration) = line.trim().strip_prefix("public static final ") else {
+                continue;
+            };
+            let Some((declaration, value)) = declaration.trim_end_matches(';').split_once(" = ") else {
+                continue;
+            };
+            let Some((field_type, name)) = declaration.rsplit_once(' ') else {
+                continue;
+            };
+            if !PREFIXES.iter().any(|prefix| name.starts_with(prefix)) {
+                continue;
+            }
+
+            match field_type {
+                // javap prints string constants as Java literals, which read the same in Rust for
+                // the ASCII values these constants have.
+                "java.lang.String" => strings.push((jni_class.clone(), name.to_owned(), value.to_owned())),
+                "int" => ints.push((jni_class.clone(), name.to_owned(), value.to_owned())),
+                _ => {}
+            }
+        }
+    }
+    strings.sort();
+    ints.sort();
+
+    let mut table = String::new();
+    writeln!(table, "// Generated by build.rs from android.jar with `javap -constants`. Do not edit by hand.").unwrap();
+    writeln!(table).unwrap();
+    writeln!(table, "pub(crate) const STRINGS: &[(&str, &str, &str)] = &[").unwrap();
+    for (class, name, value) in &strings {
+        writeln!(table, "    ({:?}, {:?}, {}),", class, name, value).unwrap();
+    }
+    writeln!(table, "];").unwrap();
+    writeln!(table).unwrap();
+    writeln!(table, "pub(crate) const INTS: &[(&str, &str, i32)] = &[").unwrap();
+    for (class, name, value) in &ints {
+        writeln!(table, "    ({:?}, {:?}, {}),", class, name, value).unwrap();
+    }
+    writeln!(table, "];").unwrap();
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("constants_table.rs"), table).unwrap();
+    println!("cargo:rustc-cfg=android_intent_generated_constants");
+}
@@ -0,0 +1,82 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, LitStr, Type};
+
+/// Implements `android_intent::IntentExtras` for a struct with named fields.
+///
+/// Fields are stored under their name, or under `#[extra(key = "...")]` when given. `Option`
+/// fields are left out when `None` and read back as `None` when missing.
+#[proc_macro_derive(IntentExtras, attributes(extra))]
+pub fn derive_intent_extras(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new(input.span(), "IntentExtras needs named fields")),
+        },
+        _ => return Err(syn::Error::new(input.span(), "IntentExtras can only be derived for structs")),
+    };
+
+    let mut puts = Vec::new();
+    let mut gets = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let key = extra_key(field)?.unwrap_or_else(|| ident.to_string());
+
+        if is_option(&field.ty) {
+            puts.push(quote! { ::android_intent::__private::put_optional(&mut extras, #key, &self.#ident); });
+        } else {
+            puts.push(quote! { ::android_intent::__private::put(&mut extras, #key, &self.#ident); });
+        }
+        gets.push(quote! { #ident: ::android_intent::__private::get(extras, #key)? });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::android_intent::IntentExtras for #name #ty_generics #where_clause {
+            fn to_extras(&self) -> ::std::collections::BTreeMap<::std::string::String, ::android_intent::ExtraValue> {
+                let mut extras = ::std::collections::BTreeMap::new();
+                #(#puts)*
+                extras
+            }
+
+            fn from_extras(
+                extras: &::std::collections::BTreeMap<::std::string::String, ::android_intent::ExtraValue>,
+            ) -> ::std::result::Result<Self, ::android_intent::Error> {
+                ::std::result::Result::Ok(Self {
+                    #(#gets,)*
+                })
+            }
+        }
+    })
+}
+
+/// The `key` of an `#[extra(key = "...")]` attribute.
+fn extra_key(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut key = None;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("extra")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") {
+                key = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `key = \"...\"`"))
+            }
+        })?;
+    }
+
+    Ok(key)
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.segments.last().is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
@@ -1,18 +1,59 @@
+use crate::backend::INTENT_CLASS;
+use crate::constants;
+
 /// Action to invoke with an intent
 pub enum Action {
     Send,
     Edit,
     Chooser,
     GetContent,
+    View,
+    UninstallPackage,
+    SetWallpaper,
+    Assist,
+    VoiceCommand,
 }
 
 impl AsRef<str> for Action {
     fn as_ref(&self) -> &str {
+        self.field_name()
+    }
+}
+
+impl Action {
+    const ALL: [Self; 9] = [
+        Self::Send,
+        Self::Edit,
+        Self::Chooser,
+        Self::GetContent,
+        Self::View,
+        Self::UninstallPackage,
+        Self::SetWallpaper,
+        Self::Assist,
+        Self::VoiceCommand,
+    ];
+
+    /// The name of the `Intent` field holding the action string.
+    const fn field_name(&self) -> &'static str {
         match self {
             Self::Send => "ACTION_SEND",
             Self::Edit => "ACTION_EDIT",
             Self::Chooser => "ACTION_CHOOSER",
             Self::GetContent => "ACTION_GET_CONTENT",
+            Self::View => "ACTION_VIEW",
+            Self::UninstallPackage => "ACTION_UNINSTALL_PACKAGE",
+            Self::SetWallpaper => "ACTION_SET_WALLPAPER",
+            Self::Assist => "ACTION_ASSIST",
+            Self::VoiceCommand => "ACTION_VOICE_COMMAND",
         }
     }
 }
+
+// Every action names a real `Intent` constant.
+const _: () = {
+    let mut i = 0;
+    while i < Action::ALL.len() {
+        constants::expect_string(INTENT_CLASS, Action::ALL[i].field_name());
+        i += 1;
+    }
+};
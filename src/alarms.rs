@@ -0,0 +1,58 @@
+//! Exact alarms through `AlarmManager`, which broadcast an intent at a given time.
+//!
+//! From API 31 on, exact alarms need the user's permission; without it, scheduling fails with
+//! [`Error::PermissionRequired`] rather than a `SecurityException`:
+//! ```no_run
+//! use android_intent::{alarms, settings, Error, IntentSpec};
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! # let mut env = intent_env.get_env();
+//! let reminder = IntentSpec {
+//!     component: Some("com.example.app/.ReminderReceiver".to_owned()),
+//!     ..Default::default()
+//! };
+//! match alarms::set_exact(&mut env, 1_700_000_000_000, &reminder, 0) {
+//!     Err(Error::PermissionRequired(_)) => {
+//!         settings::request_exact_alarm_permission(intent_env.get_env()).start_activity().unwrap();
+//!     }
+//!     result => result.unwrap(),
+//! }
+//! ```
+
+use jni::JNIEnv;
+
+use crate::trace::Traced;
+use crate::{context, pending, settings, ApiLevel, Error, IntentSpec};
+
+const RTC_WAKEUP: i32 = 0;
+
+/// Broadcast `broadcast` at `trigger_at_millis` (wall clock time, in milliseconds since the
+/// epoch), waking the device, even in Doze from API 23 on. Replaces an alarm scheduled with the
+/// same intent and `request_code`.
+pub fn set_exact(env: &mut JNIEnv, trigger_at_millis: i64, broadcast: &IntentSpec, request_code: i32) -> Result<(), Error> {
+    if !settings::can_schedule_exact_alarms(env)? {
+        return Err(Error::PermissionRequired("android.permission.SCHEDULE_EXACT_ALARM"));
+    }
+
+    let alarm_manager = context::system_service(env, "ALARM_SERVICE")?;
+    let operation = pending::broadcast_with_flags(env, broadcast, request_code, false, pending::FLAG_UPDATE_CURRENT)?;
+    let method = if ApiLevel(23).is_supported(env)? { "setExactAndAllowWhileIdle" } else { "setExact" };
+    env.call(
+        alarm_manager,
+        method,
+        "(IJLandroid/app/PendingIntent;)V",
+        &[RTC_WAKEUP.into(), trigger_at_millis.into(), (&operation).into()],
+    )?;
+
+    Ok(())
+}
+
+/// Cancel the alarm scheduled with `broadcast` and `request_code`.
+pub fn cancel(env: &mut JNIEnv, broadcast: &IntentSpec, request_code: i32) -> Result<(), Error> {
+    let alarm_manager = context::system_service(env, "ALARM_SERVICE")?;
+    let operation = pending::broadcast_with_flags(env, broadcast, request_code, false, pending::FLAG_UPDATE_CURRENT)?;
+
+    env.call(alarm_manager, "cancel", "(Landroid/app/PendingIntent;)V", &[(&operation).into()])?;
+
+    Ok(())
+}
@@ -0,0 +1,358 @@
+//! The `adb shell am start` command line syntax.
+
+use crate::{Error, ExtraValue, IntentSpec};
+
+impl IntentSpec {
+    /// Parse `am start` arguments, with or without the leading `adb shell am start`.
+    pub(crate) fn from_am_command(command: &str) -> Result<Self, Error> {
+        let mut tokens = split(command)?.into_iter().peekable();
+        for prefix in ["adb", "shell", "am", "start"] {
+            if tokens.peek().map(String::as_str) == Some(prefix) {
+                tokens.next();
+            }
+        }
+
+        let mut args = Self::default();
+        while let Some(option) = tokens.next() {
+            let mut value = |what: &str| {
+                tokens
+                    .next()
+                    .ok_or_else(|| invalid(format!("{} is missing its {}", option, what)))
+            };
+
+            match option.as_str() {
+                "-a" => args.action = Some(value("action")?),
+                "-d" => args.data = Some(value("data URI")?),
+                "-t" => args.mime_type = Some(value("MIME type")?),
+                "-p" => args.package = Some(value("package")?),
+                "-c" => args.categories.push(value("category")?),
+                "-n" => args.component = Some(value("component")?),
+                "-i" => args.identifier = Some(value("identifier")?),
+                "-f" => args.flags = parse_number(&value("flags")?)?,
+                "--esn" => {
+                    args.extras.insert(value("key")?, ExtraValue::Null);
+                }
+                "-e" | "--es" | "--ei" | "--el" | "--ez" | "--ef" | "--ed" | "--eu" | "--esa" | "--eia" | "--ela"
+                | "--efa" => {
+                    let key = value("key")?;
+                    let raw = value("value")?;
+                    let extra = match option.as_str() {
+                        "-e" | "--es" => ExtraValue::Str(raw),
+                        "--ei" => ExtraValue::Int(parse_number(&raw)?),
+                        "--el" => ExtraValue::Long(raw.parse().map_err(|_| invalid(format!("bad long {}", raw)))?),
+                        "--ez" => ExtraValue::Bool(parse_bool(&raw)?),
+                        "--ef" => ExtraValue::Float(raw.parse().map_err(|_| invalid(format!("bad float {}", raw)))?),
+                        "--ed" => ExtraValue::Double(raw.parse().map_err(|_| invalid(format!("bad double {}", raw)))?),
+                        "--eu" => ExtraValue::Uri(raw.into()),
+                        "--eia" => ExtraValue::IntArray(split_numbers(&raw, parse_number)?),
+                        "--ela" => ExtraValue::LongArray(split_numbers(&raw, |value| {
+                            value.parse().map_err(|_| invalid(format!("bad long {}", value)))
+                        })?),
+                        "--efa" => ExtraValue::FloatArray(split_numbers(&raw, |value| {
+                            value.parse().map_err(|_| invalid(format!("bad float {}", value)))
+                        })?),
+                        _ => ExtraValue::StringArray(split_array(&raw)),
+                    };
+                    args.extras.insert(key, extra);
+                }
+                other => return Err(invalid(format!("unsupported option {}", other))),
+            }
+        }
+
+        Ok(args)
+    }
+
+    pub(crate) fn to_am_command(&self) -> String {
+        let mut args = vec!["am".to_string(), "start".to_string()];
+        let mut push = |option: &str, value: &str| {
+            args.push(option.to_string());
+            args.push(quote(value));
+        };
+
+        if let Some(action) = &self.action {
+            push("-a", action);
+        }
+        if let Some(data) = &self.data {
+            push("-d", data);
+        }
+        if let Some(mime_type) = &self.mime_type {
+            push("-t", mime_type);
+        }
+        for category in &self.categories {
+            push("-c", category);
+        }
+        if let Some(package) = &self.package {
+            push("-p", package);
+        }
+        if let Some(component) = &self.component {
+            push("-n", component);
+        }
+        if let Some(identifier) = &self.identifier {
+            push("-i", identifier);
+        }
+        if self.flags != 0 {
+            push("-f", &format!("0x{:08x}", self.flags));
+        }
+
+        for (key, value) in &self.extras {
+            match value {
+                ExtraValue::Null => {
+                    args.push("--esn".to_string());
+                    args.push(quote(key));
+                }
+                ExtraValue::Str(value) => extra(&mut args, "--es", key, value),
+                ExtraValue::Int(value) => extra(&mut args, "--ei", key, &value.to_string()),
+                ExtraValue::Long(value) => extra(&mut args, "--el", key, &value.to_string()),
+                ExtraValue::Bool(value) => extra(&mut args, "--ez", key, &value.to_string()),
+                ExtraValue::Float(value) => extra(&mut args, "--ef", key, &value.to_string()),
+                ExtraValue::Double(value) => extra(&mut args, "--ed", key, &value.to_string()),
+                ExtraValue::Uri(value) => extra(&mut args, "--eu", key, value.as_str()),
+                // `--esa key ''` parses back as `[""]`, so an empty array has no syntax either.
+                ExtraValue::StringArray(values) if values.is_empty() => {}
+                ExtraValue::StringArray(values) => {
+                    let values: Vec<String> = values.iter().map(|value| value.replace(',', "\\,")).collect();
+                    extra(&mut args, "--esa", key, &values.join(","))
+                }
+                ExtraValue::IntArray(values) => extra(&mut args, "--eia", key, &join(values)),
+                ExtraValue::LongArray(values) => extra(&mut args, "--ela", key, &join(values)),
+                ExtraValue::FloatArray(values) => extra(&mut args, "--efa", key, &join(values)),
+                // `am` has no syntax for these, so they can't be reproduced from the shell.
+                ExtraValue::DoubleArray(_)
+                | ExtraValue::ByteArray(_)
+                | ExtraValue::CharArray(_)
+                | ExtraValue::ShortArray(_)
+                | ExtraValue::BoolArray(_)
+                | ExtraValue::Bundle(_)
+                | ExtraValue::Intent(_)
+                | ExtraValue::Other(_) => {}
+            }
+        }
+
+        args.join(" ")
+    }
+}
+
+/// Comma-separated values, as `--eia`, `--ela` and `--efa` expect.
+fn join<T: ToString>(values: &[T]) -> String {
+    values.iter().map(T::to_string).collect::<Vec<_>>().join(",")
+}
+
+fn extra(args: &mut Vec<String>, option: &str, key: &str, value: &str) {
+    args.push(option.to_string());
+    args.push(quote(key));
+    args.push(quote(value));
+}
+
+/// Quote `value` for a POSIX shell, leaving it bare if that is unambiguous.
+fn quote(value: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "._-/:=@%+,".contains(c);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        return value.to_string();
+    }
+
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidAmCommand(reason)
+}
+
+/// A decimal or `0x`-prefixed hexadecimal int.
+fn parse_number(value: &str) -> Result<i32, Error> {
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).map(|value| value as i32),
+        None => value.parse(),
+    };
+
+    parsed.map_err(|_| invalid(format!("bad number {}", value)))
+}
+
+/// `am` accepts `true`/`false` as well as `t`/`f` and `1`/`0`, like `Boolean.parseBoolean` plus
+/// the shorthands.
+fn parse_bool(value: &str) -> Result<bool, Error> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "t" | "1" => Ok(true),
+        "false" | "f" | "0" => Ok(false),
+        _ => Err(invalid(format!("bad boolean {}", value))),
+    }
+}
+
+/// Parse a comma-separated `--eia`, `--ela` or `--efa` value.
+fn split_numbers<T>(value: &str, parse: impl Fn(&str) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+    if value.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    value.split(',').map(parse).collect()
+}
+
+/// Split an `--esa` value on commas not escaped as `\,`.
+fn split_array(value: &str) -> Vec<String> {
+    let mut values = vec![String::new()];
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&',') => {
+                chars.next();
+                values.last_mut().unwrap().push(',');
+            }
+            ',' => values.push(String::new()),
+            c => values.last_mut().unwrap().push(c),
+        }
+    }
+
+    values
+}
+
+/// Split a command line into words the way a POSIX shell would, honoring quotes and backslashes.
+fn split(command: &str) -> Result<Vec<String>, Error> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(invalid("unterminated single quote".to_string())),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(invalid("unterminated double quote".to_string())),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(invalid("unterminated double quote".to_string())),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    word.get_or_insert_with(String::new).push(c);
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(command: &str) -> IntentSpec {
+        IntentSpec::from_am_command(command).unwrap()
+    }
+
+    fn assert_invalid(command: &str) {
+        let result = IntentSpec::from_am_command(command);
+        assert!(matches!(result, Err(Error::InvalidAmCommand(_))), "{command}: {result:?}");
+    }
+
+    #[test]
+    fn round_trips_through_the_command_line() {
+        let spec = IntentSpec {
+            action: Some("android.intent.action.SEND".to_owned()),
+            data: Some("https://example.com/a b?q=1&r=2".to_owned()),
+            mime_type: Some("text/plain".to_owned()),
+            package: Some("com.example.app".to_owned()),
+            component: Some("com.example.app/.MainActivity".to_owned()),
+            categories: vec!["android.intent.category.DEFAULT".to_owned()],
+            identifier: Some("id-1".to_owned()),
+            flags: 0x1000_0000 | 0x0000_0001,
+            extras: [
+                ("null".to_owned(), ExtraValue::Null),
+                ("text".to_owned(), ExtraValue::Str("it's \"quoted\" with \\ and $HOME".to_owned())),
+                ("int".to_owned(), ExtraValue::Int(-7)),
+                ("long".to_owned(), ExtraValue::Long(1 << 40)),
+                ("bool".to_owned(), ExtraValue::Bool(true)),
+                ("float".to_owned(), ExtraValue::Float(1.5)),
+                ("double".to_owned(), ExtraValue::Double(-0.25)),
+                ("uri".to_owned(), ExtraValue::Uri("content://a/b".into())),
+                ("strings".to_owned(), ExtraValue::StringArray(vec!["a,b".to_owned(), "c".to_owned()])),
+                ("ints".to_owned(), ExtraValue::IntArray(vec![1, -2, 3])),
+                ("longs".to_owned(), ExtraValue::LongArray(vec![4, 5])),
+                ("floats".to_owned(), ExtraValue::FloatArray(vec![0.5, 2.0])),
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(parse(&spec.to_am_command()), spec);
+
+        let mut with_empty = spec.clone();
+        with_empty.extras.insert("no_strings".to_owned(), ExtraValue::StringArray(Vec::new()));
+        assert_eq!(with_empty.to_am_command(), spec.to_am_command());
+    }
+
+    #[test]
+    fn skips_the_adb_prefix() {
+        let spec = parse("adb shell am start -a android.intent.action.VIEW -d https://example.com");
+        assert_eq!(spec.action.as_deref(), Some("android.intent.action.VIEW"));
+        assert_eq!(spec.data.as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn quotes_only_when_needed() {
+        assert_eq!(quote("com.example.app/.Main"), "com.example.app/.Main");
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("a b"), "'a b'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn splits_quotes_and_backslashes_like_a_shell() {
+        assert_eq!(split(r#"a 'b c' "d \"e\" \\ \x" f\ g"#).unwrap(), ["a", "b c", r#"d "e" \ \x"#, "f g"]);
+        assert_eq!(split("''").unwrap(), [""]);
+        assert_eq!(split(r#"--es key "it's""#).unwrap(), ["--es", "key", "it's"]);
+    }
+
+    #[test]
+    fn splits_string_arrays_on_unescaped_commas() {
+        let spec = parse(r"--esa names 'a\,b,c,,d\'");
+        assert_eq!(
+            spec.extras["names"],
+            ExtraValue::StringArray(vec!["a,b".to_owned(), "c".to_owned(), String::new(), r"d\".to_owned()])
+        );
+    }
+
+    #[test]
+    fn parses_number_and_boolean_syntax() {
+        let spec = parse("-f 0x10000000 --ei hex 0xff --ez short t --ez digit 0 --eia empty ''");
+        assert_eq!(spec.flags, 0x1000_0000);
+        assert_eq!(spec.extras["hex"], ExtraValue::Int(255));
+        assert_eq!(spec.extras["short"], ExtraValue::Bool(true));
+        assert_eq!(spec.extras["digit"], ExtraValue::Bool(false));
+        assert_eq!(spec.extras["empty"], ExtraValue::IntArray(Vec::new()));
+    }
+
+    #[test]
+    fn rejects_malformed_commands() {
+        assert_invalid("-a android.intent.action.VIEW --wait");
+        assert_invalid("-a");
+        assert_invalid("--ei count");
+        assert_invalid("--ei count many");
+        assert_invalid("--ez flag maybe");
+        assert_invalid("--eia ints 1,x");
+        assert_invalid("-a 'android.intent.action.VIEW");
+        assert_invalid(r#"-d "https://example.com"#);
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use jni::JNIEnv;
+
+use crate::{context, Error};
+
+static SDK_INT: AtomicI32 = AtomicI32::new(0);
+
+/// `Build.VERSION.SDK_INT` of the running device. Read once, then cached.
+pub fn sdk_int() -> Result<i32, Error> {
+    match SDK_INT.load(Ordering::Relaxed) {
+        0 => {
+            let vm = context::java_vm()?;
+            let mut env = vm.attach_current_thread()?;
+            sdk_int_with(&mut env)
+        }
+        sdk => Ok(sdk),
+    }
+}
+
+/// Same as [`sdk_int`], using an already attached `env`.
+pub(crate) fn sdk_int_with(env: &mut JNIEnv) -> Result<i32, Error> {
+    match SDK_INT.load(Ordering::Relaxed) {
+        0 => {
+            let version_class = env.find_class("android/os/Build$VERSION")?;
+            let sdk = env.get_static_field(version_class, "SDK_INT", "I")?.i()?;
+            SDK_INT.store(sdk, Ordering::Relaxed);
+            Ok(sdk)
+        }
+        sdk => Ok(sdk),
+    }
+}
+
+/// An Android API level, used to guard calls into APIs that older devices lack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiLevel(pub i32);
+
+impl ApiLevel {
+    /// Whether the running device is at least this API level.
+    pub fn is_supported(self, env: &mut JNIEnv) -> Result<bool, Error> {
+        Ok(sdk_int_with(env)? >= self.0)
+    }
+
+    /// Fail with [`Error::UnsupportedApiLevel`] if the running device is older than this API level.
+    pub(crate) fn require(self, env: &mut JNIEnv) -> Result<(), Error> {
+        let actual = sdk_int_with(env)?;
+        if actual < self.0 {
+            return Err(Error::UnsupportedApiLevel { required: self.0, actual });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+//! Launching the assistant, and reading the request when this app is the assistant.
+//!
+//! An app that declares an activity for `android.intent.action.ASSIST` can be picked as the
+//! device assistant, and then receives what the user was looking at:
+//! ```no_run
+//! use android_intent::assist;
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! # let mut intent = android_intent::Intent::new(intent_env.get_env(), android_intent::Action::View);
+//! // `intent` is the one the activity was started with.
+//! if let Some(request) = assist::incoming(&mut intent).unwrap() {
+//!     log::info!("assisting with {:?}", request.package);
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use jni::AttachGuard;
+
+use crate::{Action, Error, ExtraValue, Intent};
+
+const ACTION_ASSIST: &str = "android.intent.action.ASSIST";
+const EXTRA_ASSIST_PACKAGE: &str = "android.intent.extra.ASSIST_PACKAGE";
+const EXTRA_ASSIST_UID: &str = "android.intent.extra.ASSIST_UID";
+const EXTRA_ASSIST_CONTEXT: &str = "android.intent.extra.ASSIST_CONTEXT";
+const EXTRA_ASSIST_INPUT_HINT_KEYBOARD: &str = "android.intent.extra.ASSIST_INPUT_HINT_KEYBOARD";
+
+/// An `ACTION_ASSIST` request received by this app.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssistRequest {
+    /// Package of the app in the foreground when the assistant was invoked.
+    pub package: Option<String>,
+    /// Uid of that app.
+    pub uid: Option<i32>,
+    /// Extra data that app provided through `Activity.onProvideAssistData`.
+    pub context: BTreeMap<String, ExtraValue>,
+    /// The user invoked the assistant by keyboard, so typed rather than spoken input is likely.
+    pub keyboard_hint: bool,
+}
+
+/// Open the device assistant, e.g. from an in-app "ask" button.
+pub fn launch_assistant(env: AttachGuard<'_>) -> Result<Intent<'_>, Error> {
+    Intent::new(env, Action::Assist).new_task().start_activity()
+}
+
+/// Start a voice command, as a long press on a headset button would.
+pub fn launch_voice_command(env: AttachGuard<'_>) -> Result<Intent<'_>, Error> {
+    Intent::new(env, Action::VoiceCommand).new_task().start_activity()
+}
+
+/// The request in `intent`, or `None` if it isn't an `ACTION_ASSIST` intent.
+pub fn incoming(intent: &mut Intent) -> Result<Option<AssistRequest>, Error> {
+    if intent.to_spec()?.action.as_deref() != Some(ACTION_ASSIST) {
+        return Ok(None);
+    }
+
+    Ok(Some(AssistRequest {
+        package: intent.get(EXTRA_ASSIST_PACKAGE)?,
+        uid: intent.get(EXTRA_ASSIST_UID)?,
+        context: intent.get::<Option<_>>(EXTRA_ASSIST_CONTEXT)?.unwrap_or_default(),
+        keyboard_hint: intent.get::<Option<bool>>(EXTRA_ASSIST_INPUT_HINT_KEYBOARD)?.unwrap_or(false),
+    }))
+}
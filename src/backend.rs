@@ -0,0 +1,772 @@
+use std::collections::BTreeMap;
+
+use jni::{objects::JObject, AttachGuard, JNIEnv};
+use jni::objects::JValueOwned;
+use jni::sys::jint;
+
+use crate::{
+    api_level, bundle, compat, constants, context, host, leak_check, ApiLevel, Error, ExtraValue, Flags, IntentSpec,
+};
+use crate::sig::sig;
+use crate::trace::{debug, Traced};
+
+pub(crate) const INTENT_CLASS: &str = "android/content/Intent";
+
+/// The operations [`Intent`](crate::Intent) needs from whatever holds the intent being built.
+///
+/// [`Inner`] drives a real `android.content.Intent` over JNI; the `mock` feature adds
+/// [`MockBackend`](crate::MockBackend), which only records what was asked for.
+pub(crate) trait IntentBackend<'env> {
+    fn put_extra(&mut self, key: &str, value: &ExtraValue) -> Result<(), Error>;
+
+    fn remove_extra(&mut self, key: &str) -> Result<(), Error>;
+
+    fn replace_extras(&mut self, extras: &BTreeMap<String, ExtraValue>) -> Result<(), Error>;
+
+    /// Replace the extras with a copy of those of `source`, keeping Java-only values when both
+    /// are real intents.
+    fn replace_extras_from(&mut self, source: &mut dyn IntentBackend<'_>) -> Result<(), Error>;
+
+    fn set_type(&mut self, mime_type: &str) -> Result<(), Error>;
+
+    /// Like `Intent.setDataAndType`, a `None` MIME type clears the type.
+    fn set_data(&mut self, uri: &str, mime_type: Option<&str>) -> Result<(), Error>;
+
+    fn set_package(&mut self, package: Option<&str>) -> Result<(), Error>;
+
+    /// `bounds` is `[left, top, right, bottom]`.
+    fn set_source_bounds(&mut self, bounds: [i32; 4]) -> Result<(), Error>;
+
+    fn set_selector(&mut self, selector: &IntentSpec) -> Result<(), Error>;
+
+    fn set_identifier(&mut self, identifier: &str) -> Result<(), Error>;
+
+    fn add_flags(&mut self, flags: Flags) -> Result<(), Error>;
+
+    fn remove_flags(&mut self, flags: Flags) -> Result<(), Error>;
+
+    /// `category` names a `CATEGORY_*` field of `Intent`.
+    fn add_category(&mut self, category: &str) -> Result<(), Error>;
+
+    /// `category` names a `CATEGORY_*` field of `Intent`.
+    fn has_category(&mut self, category: &str) -> Result<bool, Error>;
+
+    /// `category` names a `CATEGORY_*` field of `Intent`.
+    fn remove_category(&mut self, category: &str) -> Result<(), Error>;
+
+    fn wrap_in_chooser(&mut self, title: Option<&str>) -> Result<(), Error>;
+
+    fn start_activity(&mut self, request_code: Option<i32>) -> Result<(), Error>;
+
+    /// The extra under `key`, or `None` if there is none.
+    fn extra(&mut self, key: &str) -> Result<Option<ExtraValue>, Error>;
+
+    fn extras(&mut self) -> Result<BTreeMap<String, ExtraValue>, Error>;
+
+    fn to_spec(&mut self) -> Result<IntentSpec, Error>;
+
+    /// Human readable description, for [`Debug`](std::fmt::Debug).
+    fn describe(&self) -> String;
+
+    /// The JNI backend, for operations that only make sense against a real intent.
+    fn jni(&mut self) -> Option<&mut Inner<'env>>;
+
+    #[cfg(feature = "mock")]
+    fn mock(&self) -> Option<&crate::MockBackend> {
+        None
+    }
+}
+
+/// Read the static field `field_name` of `class`, from the [`constants`] table if it is known.
+pub(crate) fn static_field<'a>(env: &mut JNIEnv<'a>, class: &str, field_name: impl AsRef<str>, field_type: &str) -> Result<JValueOwned<'a>, jni::errors::Error> {
+    match field_type {
+        "Ljava/lang/String;" => {
+            if let Some(value) = constants::string(class, field_name.as_ref()) {
+                return Ok(JValueOwned::Object(env.new_string(value)?.into()));
+            }
+        }
+        "I" => {
+            if let Some(value) = constants::int(class, field_name.as_ref()) {
+                return Ok(JValueOwned::Int(value));
+            }
+        }
+        _ => {}
+    }
+    debug!("get static field {}.{} with type {}", class, field_name.as_ref(), field_type);
+
+    let class = env.find_class(class)?;
+    let val = env.get_static_field(&class, field_name.as_ref(), field_type)?;
+
+    Ok(val)
+}
+
+/// A new primitive Java array such as `int[]` holding `values`.
+macro_rules! write_array {
+    ($env:ident, $values:expr, $new_array:ident, $set_region:ident) => {{
+        let values = $values;
+        let array = $env.$new_array(values.len() as i32)?;
+        $env.$set_region(&array, 0, values)?;
+        JObject::from(array).into()
+    }};
+}
+
+pub(crate) fn parse_uri<'a>(env: &mut JNIEnv<'a>, uri: &str) -> Result<JObject<'a>, Error> {
+    let uri = env.new_string(uri)?;
+    let uri = env
+        .call_static("android/net/Uri", "parse", sig!((String) -> Uri), &[(&uri).into()])?
+        .l()?;
+
+    Ok(uri)
+}
+
+/// Styled text from `html`, via `Html.fromHtml`.
+pub(crate) fn from_html<'a>(env: &mut JNIEnv<'a>, html: &str) -> Result<JObject<'a>, Error> {
+    let html = env.new_string(html)?;
+    // The flag-less overload is deprecated from API 24 on; mode 0 is FROM_HTML_MODE_LEGACY,
+    // which behaves the same.
+    let text = if compat::supports_html_flags(api_level::sdk_int_with(env)?) {
+        let signature = sig!((String, int) -> "android/text/Spanned");
+        env.call_static("android/text/Html", "fromHtml", signature, &[(&html).into(), 0.into()])?
+    } else {
+        let signature = sig!((String) -> "android/text/Spanned");
+        env.call_static("android/text/Html", "fromHtml", signature, &[(&html).into()])?
+    };
+
+    Ok(text.l()?)
+}
+
+/// Call a `()Ljava/lang/String;` method, mapping a null result to `None`.
+pub(crate) fn string_getter(env: &mut JNIEnv, object: &JObject, method: &str) -> Result<Option<String>, Error> {
+    let value = env.call(object, method, sig!(() -> String), &[])?.l()?;
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    let value = env.get_string(&value.into())?.into();
+    Ok(Some(value))
+}
+
+/// A `ComponentName` from its flattened `package/class` form, where `class` may start with `.`
+/// to be relative to `package`.
+pub(crate) fn component_name<'a>(env: &mut JNIEnv<'a>, component: &str) -> Result<JObject<'a>, Error> {
+    let jcomponent = env.new_string(component)?;
+    let component = env
+        .call_static(
+            "android/content/ComponentName",
+            "unflattenFromString",
+            sig!((String) -> ComponentName),
+            &[(&jcomponent).into()],
+        )?
+        .l()?;
+
+    Ok(component)
+}
+
+/// A new `android.os.Bundle` holding `map`. It is built by filling a scratch intent and taking
+/// its extras.
+pub(crate) fn new_bundle<'a>(env: &mut JNIEnv<'a>, map: &BTreeMap<String, ExtraValue>) -> Result<JObject<'a>, Error> {
+    let scratch = env.new_object(INTENT_CLASS, sig!(() -> ()), &[])?;
+    for (key, value) in map {
+        Inner::put_extra_value(env, &scratch, key, value)?;
+    }
+
+    let bundle = env.call(&scratch, "getExtras", sig!(() -> Bundle), &[])?.l()?;
+    if bundle.is_null() {
+        return Ok(env.new_object("android/os/Bundle", sig!(() -> ()), &[])?);
+    }
+
+    Ok(bundle)
+}
+
+/// A new `android.graphics.Rect` from `[left, top, right, bottom]`.
+fn new_rect<'a>(env: &mut JNIEnv<'a>, [left, top, right, bottom]: [i32; 4]) -> Result<JObject<'a>, Error> {
+    let rect = env.new_object(
+        "android/graphics/Rect",
+        sig!((int, int, int, int) -> ()),
+        &[left.into(), top.into(), right.into(), bottom.into()],
+    )?;
+
+    Ok(rect)
+}
+
+/// A new `android.content.Intent` matching `spec`.
+pub(crate) fn new_intent<'a>(env: &mut JNIEnv<'a>, spec: &IntentSpec) -> Result<JObject<'a>, Error> {
+    let intent = env.new_object(INTENT_CLASS, sig!(() -> ()), &[])?;
+
+    if let Some(action) = &spec.action {
+        let action = env.new_string(action)?;
+        env.call(&intent, "setAction", sig!((String) -> Intent), &[(&action).into()])?;
+    }
+
+    let data = match &spec.data {
+        Some(data) => parse_uri(env, data)?,
+        None => JObject::null(),
+    };
+    let mime_type = match &spec.mime_type {
+        Some(mime_type) => env.new_string(mime_type)?.into(),
+        None => JObject::null(),
+    };
+    env.call(
+        &intent,
+        "setDataAndType",
+        sig!((Uri, String) -> Intent),
+        &[(&data).into(), (&mime_type).into()],
+    )?;
+
+    for category in &spec.categories {
+        let category = env.new_string(category)?;
+        env.call(&intent, "addCategory", sig!((String) -> Intent), &[(&category).into()])?;
+    }
+
+    if let Some(package) = &spec.package {
+        let package = env.new_string(package)?;
+        env.call(&intent, "setPackage", sig!((String) -> Intent), &[(&package).into()])?;
+    }
+
+    if let Some(component) = &spec.component {
+        let component = component_name(env, component)?;
+        env.call(&intent, "setComponent", sig!((ComponentName) -> Intent), &[(&component).into()])?;
+    }
+
+    if let Some(bounds) = spec.source_bounds {
+        let rect = new_rect(env, bounds)?;
+        env.call(&intent, "setSourceBounds", sig!(("android/graphics/Rect") -> ()), &[(&rect).into()])?;
+    }
+
+    if let Some(selector) = &spec.selector {
+        let selector = new_intent(env, selector)?;
+        env.call(&intent, "setSelector", sig!((Intent) -> ()), &[(&selector).into()])?;
+    }
+
+    if let Some(identifier) = &spec.identifier {
+        if compat::supports_intent_identifier(api_level::sdk_int_with(env)?) {
+            let identifier = env.new_string(identifier)?;
+            env.call(&intent, "setIdentifier", sig!((String) -> Intent), &[(&identifier).into()])?;
+        } else {
+            debug!("skipping identifier below API level 29");
+        }
+    }
+
+    env.call(&intent, "setFlags", sig!((int) -> Intent), &[spec.flags.into()])?;
+
+    for (key, value) in &spec.extras {
+        Inner::put_extra_value(env, &intent, key, value)?;
+    }
+
+    Ok(intent)
+}
+
+/// Read an `android.content.Intent` object back into an [`IntentSpec`].
+pub(crate) fn spec_of(env: &mut JNIEnv, object: &JObject) -> Result<IntentSpec, Error> {
+    let extras = env.call(object, "getExtras", sig!(() -> Bundle), &[])?.l()?;
+    let extras = if extras.is_null() {
+        BTreeMap::new()
+    } else {
+        ExtraValue::map_from_bundle(env, &extras)?
+    };
+
+    let component = env.call(object, "getComponent", sig!(() -> ComponentName), &[])?.l()?;
+    let component = if component.is_null() {
+        None
+    } else {
+        string_getter(env, &component, "flattenToShortString")?
+    };
+
+    let categories = env.call(object, "getCategories", sig!(() -> "java/util/Set"), &[])?.l()?;
+
+    let bounds = env.call(object, "getSourceBounds", sig!(() -> "android/graphics/Rect"), &[])?.l()?;
+    let source_bounds = if bounds.is_null() {
+        None
+    } else {
+        let mut side = |name: &str| env.get_field(&bounds, name, "I")?.i();
+        Some([side("left")?, side("top")?, side("right")?, side("bottom")?])
+    };
+
+    let selector = env.call(object, "getSelector", sig!(() -> Intent), &[])?.l()?;
+    let selector = if selector.is_null() {
+        None
+    } else {
+        Some(Box::new(spec_of(env, &selector)?))
+    };
+
+    let identifier = if compat::supports_intent_identifier(api_level::sdk_int_with(env)?) {
+        string_getter(env, object, "getIdentifier")?
+    } else {
+        None
+    };
+
+    Ok(IntentSpec {
+        action: string_getter(env, object, "getAction")?,
+        data: string_getter(env, object, "getDataString")?,
+        mime_type: string_getter(env, object, "getType")?,
+        package: string_getter(env, object, "getPackage")?,
+        categories: bundle::string_collection(env, &categories)?,
+        component,
+        source_bounds,
+        selector,
+        identifier,
+        flags: env.call(object, "getFlags", sig!(() -> int), &[])?.i()?,
+        extras,
+    })
+}
+
+/// A live `android.content.Intent` and the environment it belongs to.
+pub(crate) struct Inner<'env> {
+    pub(crate) env: AttachGuard<'env>,
+    pub(crate) object: JObject<'env>,
+}
+
+impl Drop for Inner<'_> {
+    fn drop(&mut self) {
+        leak_check::released(leak_check::Kind::Intent);
+
+        // Long-running native threads never return to Java to free their local references.
+        let object = std::mem::take(&mut self.object);
+        if let Err(err) = self.env.delete_local_ref(object) {
+            debug!("deleting the intent reference failed: {}", err);
+        }
+    }
+}
+
+impl<'env> Inner<'env> {
+    /// Builds an intent whose action is the static `String` field `action` of `class`, e.g.
+    /// `ACTION_APPLICATION_DETAILS_SETTINGS` on `android/provider/Settings`.
+    pub(crate) fn new_with_class_action(
+        mut env: AttachGuard<'env>,
+        class: &str,
+        action: &str,
+        uri: Option<&str>,
+    ) -> Result<Self, Error> {
+        let action_view = static_field(&mut env, class, action, "Ljava/lang/String;")?;
+
+        let intent_class = env.find_class(INTENT_CLASS)?;
+        let intent = match uri {
+            Some(uri) => {
+                let uri = parse_uri(&mut env, uri)?;
+
+                env.new_object(
+                    &intent_class,
+                    sig!((String, Uri) -> ()),
+                    &[(&action_view).into(), (&uri).into()],
+                )?
+            }
+            None => env.new_object(&intent_class, sig!((String) -> ()), &[(&action_view).into()])?,
+        };
+
+        Ok(Inner::new(env, intent))
+    }
+
+    pub(crate) fn from_spec(mut env: AttachGuard<'env>, spec: &IntentSpec) -> Result<Self, Error> {
+        let object = new_intent(&mut env, spec)?;
+        Ok(Inner::new(env, object))
+    }
+
+    /// Take over the local reference `object`.
+    pub(crate) fn new(env: AttachGuard<'env>, object: JObject<'env>) -> Self {
+        leak_check::acquired(leak_check::Kind::Intent);
+        Inner { env, object }
+    }
+
+    /// Replace the intent with `object`, deleting the reference to the old one.
+    pub(crate) fn replace_object(&mut self, object: JObject<'env>) -> Result<(), Error> {
+        let old = std::mem::replace(&mut self.object, object);
+        self.env.delete_local_ref(old)?;
+
+        Ok(())
+    }
+
+    /// `putExtra` with the overload matching `value`.
+    pub(crate) fn put_extra_value(env: &mut JNIEnv, intent: &JObject, key: &str, value: &ExtraValue) -> Result<(), Error> {
+        let jkey = env.new_string(key)?;
+
+        let (signature, jvalue): (&str, JValueOwned) = match value {
+            ExtraValue::Null => (sig!((String, String) -> Intent), JObject::null().into()),
+            ExtraValue::Str(value) => (sig!((String, String) -> Intent), env.new_string(value)?.into()),
+            ExtraValue::Int(value) => (sig!((String, int) -> Intent), (*value).into()),
+            ExtraValue::Long(value) => (sig!((String, long) -> Intent), (*value).into()),
+            ExtraValue::Bool(value) => (sig!((String, boolean) -> Intent), (*value).into()),
+            ExtraValue::Float(value) => (sig!((String, float) -> Intent), (*value).into()),
+            ExtraValue::Double(value) => (sig!((String, double) -> Intent), (*value).into()),
+            ExtraValue::Uri(value) => (sig!((String, Parcelable) -> Intent), parse_uri(env, value.as_str())?.into()),
+            ExtraValue::StringArray(values) => {
+                let array = env.new_object_array(values.len() as i32, "java/lang/String", JObject::null())?;
+                for (i, value) in values.iter().enumerate() {
+                    let value = env.new_string(value)?;
+                    env.set_object_array_element(&array, i as i32, value)?;
+                }
+                (sig!((String, [String]) -> Intent), JObject::from(array).into())
+            }
+            ExtraValue::IntArray(values) => {
+                (sig!((String, [int]) -> Intent), write_array!(env, values, new_int_array, set_int_array_region))
+            }
+            ExtraValue::LongArray(values) => {
+                (sig!((String, [long]) -> Intent), write_array!(env, values, new_long_array, set_long_array_region))
+            }
+            ExtraValue::FloatArray(values) => {
+                (sig!((String, [float]) -> Intent), write_array!(env, values, new_float_array, set_float_array_region))
+            }
+            ExtraValue::DoubleArray(values) => {
+                (sig!((String, [double]) -> Intent), write_array!(env, values, new_double_array, set_double_array_region))
+            }
+            ExtraValue::ByteArray(values) => {
+                (sig!((String, [byte]) -> Intent), JObject::from(env.byte_array_from_slice(values)?).into())
+            }
+            ExtraValue::CharArray(values) => {
+                (sig!((String, [char]) -> Intent), write_array!(env, values, new_char_array, set_char_array_region))
+            }
+            ExtraValue::ShortArray(values) => {
+                (sig!((String, [short]) -> Intent), write_array!(env, values, new_short_array, set_short_array_region))
+            }
+            ExtraValue::BoolArray(values) => {
+                let values: Vec<u8> = values.iter().map(|&value| value.into()).collect();
+                (sig!((String, [boolean]) -> Intent), write_array!(env, &values, new_boolean_array, set_boolean_array_region))
+            }
+            ExtraValue::Bundle(map) => (sig!((String, Bundle) -> Intent), new_bundle(env, map)?.into()),
+            ExtraValue::Intent(spec) => (sig!((String, Parcelable) -> Intent), new_intent(env, spec)?.into()),
+            ExtraValue::Other(class) => return Err(Error::UnsupportedExtra(class.clone())),
+        };
+
+        env.call(intent, "putExtra", signature, &[(&jkey).into(), jvalue.borrow()])?;
+
+        Ok(())
+    }
+
+    fn resolve_flags(env: &mut AttachGuard<'_>, flags: Flags) -> Result<jint, Error> {
+        let sdk = api_level::sdk_int_with(env)?;
+        let mut jflags: jint = 0;
+
+        for (flag, _) in flags.iter_names() {
+            if !compat::supports_flag(sdk, flag) {
+                debug!("skipping FLAG_{} on API level {}", flag, sdk);
+                continue;
+            }
+
+            let flag_val = static_field(env, INTENT_CLASS, format!("FLAG_{}", flag), "I")?;
+            jflags |= flag_val.i()?;
+        }
+
+        Ok(jflags)
+    }
+
+    fn extras_bundle(&mut self) -> Result<JObject<'env>, Error> {
+        let extras = self.env.call(&self.object, "getExtras", sig!(() -> Bundle), &[])?.l()?;
+        Ok(extras)
+    }
+
+    /// `toString()` of the intent followed by its extras as `key: Type = value`. Leaves an
+    /// exception the caller has pending alone, and fails instead of dumping.
+    fn dump(object: &JObject) -> Result<String, jni::errors::Error> {
+        let vm = context::java_vm()?;
+        let mut env = vm.attach_current_thread()?;
+        if env.exception_check()? {
+            return Err(jni::errors::Error::JavaException);
+        }
+
+        let dump = env.with_local_frame(16, |env| Self::dump_with(env, object));
+        if dump.is_err() && env.exception_check()? {
+            // Thrown by the dump itself, as none was pending before.
+            env.exception_clear()?;
+        }
+        dump
+    }
+
+    fn dump_with(env: &mut JNIEnv, object: &JObject) -> Result<String, jni::errors::Error> {
+        const PREVIEW_LEN: usize = 64;
+
+        let mut out = bundle::to_string(env, object)?;
+
+        let extras = env.call(object, "getExtras", sig!(() -> Bundle), &[])?.l()?;
+        if extras.is_null() {
+            return Ok(out);
+        }
+
+        let mut entries = Vec::new();
+        for key in bundle::keys(env, &extras)? {
+            let entry = env.with_local_frame(4, |env| -> Result<String, jni::errors::Error> {
+                let value = bundle::get(env, &extras, &key)?;
+                if value.is_null() {
+                    return Ok(format!("{}: null", key));
+                }
+
+                let class = bundle::simple_class_name(env, &value)?;
+                let mut preview = bundle::to_string(env, &value)?;
+                if let Some((end, _)) = preview.char_indices().nth(PREVIEW_LEN) {
+                    preview.truncate(end);
+                    preview.push('…');
+                }
+                Ok(format!("{}: {} = {:?}", key, class, preview))
+            })?;
+            entries.push(entry);
+        }
+
+        out.push_str(&format!(" extras={{{}}}", entries.join(", ")));
+        Ok(out)
+    }
+}
+
+impl<'env> IntentBackend<'env> for Inner<'env> {
+    fn put_extra(&mut self, key: &str, value: &ExtraValue) -> Result<(), Error> {
+        Self::put_extra_value(&mut self.env, &self.object, key, value)
+    }
+
+    fn remove_extra(&mut self, key: &str) -> Result<(), Error> {
+        let jkey = self.env.new_string(key)?;
+
+        self.env.call(&self.object, "removeExtra", sig!((String) -> ()), &[(&jkey).into()])?;
+
+        Ok(())
+    }
+
+    fn replace_extras(&mut self, extras: &BTreeMap<String, ExtraValue>) -> Result<(), Error> {
+        let jextras = new_bundle(&mut self.env, extras)?;
+
+        self.env.call(
+            &self.object,
+            "replaceExtras",
+            sig!((Bundle) -> Intent),
+            &[(&jextras).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn replace_extras_from(&mut self, source: &mut dyn IntentBackend<'_>) -> Result<(), Error> {
+        let Some(source) = source.jni() else {
+            return self.replace_extras(&source.extras()?);
+        };
+
+        self.env.call(
+            &self.object,
+            "replaceExtras",
+            sig!((Intent) -> Intent),
+            &[(&source.object).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_type(&mut self, mime_type: &str) -> Result<(), Error> {
+        let jstring = self.env.new_string(mime_type)?;
+
+        self.env.call(
+            &self.object,
+            "setType",
+            sig!((String) -> Intent),
+            &[(&jstring).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_data(&mut self, uri: &str, mime_type: Option<&str>) -> Result<(), Error> {
+        let juri = parse_uri(&mut self.env, uri)?;
+        let jtype = match mime_type {
+            Some(mime_type) => self.env.new_string(mime_type)?.into(),
+            None => JObject::null(),
+        };
+
+        self.env.call(
+            &self.object,
+            "setDataAndType",
+            sig!((Uri, String) -> Intent),
+            &[(&juri).into(), (&jtype).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_package(&mut self, package: Option<&str>) -> Result<(), Error> {
+        let jpackage = match package {
+            Some(package) => self.env.new_string(package)?.into(),
+            None => JObject::null(),
+        };
+
+        self.env.call(
+            &self.object,
+            "setPackage",
+            sig!((String) -> Intent),
+            &[(&jpackage).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_source_bounds(&mut self, bounds: [i32; 4]) -> Result<(), Error> {
+        let jrect = new_rect(&mut self.env, bounds)?;
+
+        self.env.call(
+            &self.object,
+            "setSourceBounds",
+            sig!(("android/graphics/Rect") -> ()),
+            &[(&jrect).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_selector(&mut self, selector: &IntentSpec) -> Result<(), Error> {
+        let jselector = new_intent(&mut self.env, selector)?;
+
+        self.env.call(
+            &self.object,
+            "setSelector",
+            sig!((Intent) -> ()),
+            &[(&jselector).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_identifier(&mut self, identifier: &str) -> Result<(), Error> {
+        ApiLevel(29).require(&mut self.env)?;
+        let jidentifier = self.env.new_string(identifier)?;
+
+        self.env.call(
+            &self.object,
+            "setIdentifier",
+            sig!((String) -> Intent),
+            &[(&jidentifier).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn add_flags(&mut self, flags: Flags) -> Result<(), Error> {
+        let jflags = Self::resolve_flags(&mut self.env, flags)?;
+
+        self.env.call(
+            &self.object,
+            "addFlags",
+            sig!((int) -> Intent),
+            &[jflags.into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_flags(&mut self, flags: Flags) -> Result<(), Error> {
+        let sdk = api_level::sdk_int_with(&mut self.env)?;
+        if !compat::supports_remove_flags(sdk) {
+            debug!("skipping removeFlags on API level {}", sdk);
+            return Ok(());
+        }
+
+        let jflags = Self::resolve_flags(&mut self.env, flags)?;
+
+        self.env.call(
+            &self.object,
+            "removeFlags",
+            sig!((int) -> ()),
+            &[jflags.into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn add_category(&mut self, category: &str) -> Result<(), Error> {
+        let jcategory = static_field(&mut self.env, INTENT_CLASS, category, "Ljava/lang/String;")?;
+
+        self.env.call(
+            &self.object,
+            "addCategory",
+            sig!((String) -> Intent),
+            &[(&jcategory).into()],
+        )?;
+
+        Ok(())
+    }
+
+    fn has_category(&mut self, category: &str) -> Result<bool, Error> {
+        let jcategory = static_field(&mut self.env, INTENT_CLASS, category, "Ljava/lang/String;")?;
+
+        let has = self
+            .env
+            .call(&self.object, "hasCategory", sig!((String) -> boolean), &[(&jcategory).into()])?
+            .z()?;
+
+        Ok(has)
+    }
+
+    fn remove_category(&mut self, category: &str) -> Result<(), Error> {
+        let jcategory = static_field(&mut self.env, INTENT_CLASS, category, "Ljava/lang/String;")?;
+
+        self.env.call(&self.object, "removeCategory", sig!((String) -> ()), &[(&jcategory).into()])?;
+
+        Ok(())
+    }
+
+    fn wrap_in_chooser(&mut self, title: Option<&str>) -> Result<(), Error> {
+        let title_value: JValueOwned = if let Some(title) = title {
+            let s = self.env.new_string(title)?;
+            s.into()
+        } else {
+            JObject::null().into()
+        };
+
+        let intent = self.env.call_static(
+            INTENT_CLASS,
+            "createChooser",
+            sig!((Intent, CharSequence) -> Intent),
+            &[(&self.object).into(), (&title_value).into()],
+        )?;
+
+        self.replace_object(intent.try_into()?)
+    }
+
+    fn start_activity(&mut self, request_code: Option<i32>) -> Result<(), Error> {
+        let activity = context::activity(&mut self.env)?;
+        let object = &self.object;
+
+        host::with_host(&mut self.env, &activity, |host, env| {
+            host.start_activity(env, &activity, object, request_code)
+        })
+    }
+
+    fn extra(&mut self, key: &str) -> Result<Option<ExtraValue>, Error> {
+        let extras = self.extras_bundle()?;
+        if extras.is_null() {
+            return Ok(None);
+        }
+
+        let object = bundle::get(&mut self.env, &extras, key)?;
+        let value = ExtraValue::from_object(&mut self.env, &object)?;
+        // `Bundle.get` can't tell a missing key from a null value.
+        match value {
+            ExtraValue::Null => {
+                let jkey = self.env.new_string(key)?;
+                let contains =
+                    self.env.call(&extras, "containsKey", sig!((String) -> boolean), &[(&jkey).into()])?.z()?;
+                Ok(contains.then_some(ExtraValue::Null))
+            }
+            value => Ok(Some(value)),
+        }
+    }
+
+    fn extras(&mut self) -> Result<BTreeMap<String, ExtraValue>, Error> {
+        let extras = self.extras_bundle()?;
+        if extras.is_null() {
+            return Ok(BTreeMap::new());
+        }
+
+        ExtraValue::map_from_bundle(&mut self.env, &extras)
+    }
+
+    fn to_spec(&mut self) -> Result<IntentSpec, Error> {
+        spec_of(&mut self.env, &self.object)
+    }
+
+    fn describe(&self) -> String {
+        if self.object.is_null() {
+            return "Intent(null)".to_string();
+        }
+
+        match Self::dump(&self.object) {
+            Ok(dump) => dump,
+            Err(err) => format!("Intent(<dump failed: {}>)", err),
+        }
+    }
+
+    fn jni(&mut self) -> Option<&mut Inner<'env>> {
+        Some(self)
+    }
+}
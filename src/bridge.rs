@@ -0,0 +1,544 @@
+//! The app-side Java glue that [`Intent::get_result`](crate::Intent::get_result),
+//! [`Intent::start_activity_on_ui_thread`](crate::Intent::start_activity_on_ui_thread),
+//! [`Intent::into_chooser_with_callback`](crate::Intent::into_chooser_with_callback) and the
+//! [`lifecycle`](crate::lifecycle), [`messenger`](crate::messenger),
+//! [`permissions`](crate::permissions), [`receivers`](crate::receivers) and `winit` modules call into,
+//! generated from the same names and signatures the crate uses over JNI.
+//!
+//! Generate it from the app's `build.rs` into a source directory Gradle compiles, and let the
+//! activity extend the generated `RustIntentActivity`:
+//! ```no_run
+//! // build.rs, with android-intent as a build-dependency
+//! android_intent::bridge::Bridge::new("com.example.app")
+//!     .write_to("android/app/src/main/java")
+//!     .unwrap();
+//! ```
+//! With the `result-bridge` feature, tell the crate at runtime which package the bridge was
+//! generated into:
+#![cfg_attr(
+    feature = "result-bridge",
+    doc = "```no_run\nandroid_intent::bridge::set_package(\"com.example.app\");\n```"
+)]
+
+#[cfg(feature = "result-bridge")]
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "result-bridge")]
+use std::sync::RwLock;
+
+use crate::sig::sig;
+
+/// Package the result class lives in until [`set_package`] is called, a placeholder apps
+/// generating the bridge into their own package have to replace.
+#[cfg(feature = "result-bridge")]
+pub const DEFAULT_PACKAGE: &str = "com.example.app";
+/// Simple name of the class `getNextIntentResult` returns.
+pub const RESULT_CLASS: &str = "RustNativeIntentResult";
+/// Simple name of the generated activity base class.
+pub const ACTIVITY_CLASS: &str = "RustIntentActivity";
+/// Simple name of the generated provider serving [`content::share_pipe`](crate::content::share_pipe)
+/// and [`content::share_bytes`](crate::content::share_bytes) data.
+pub const SHARE_PROVIDER_CLASS: &str = "RustShareProvider";
+
+pub(crate) const GET_NEXT_RESULT: &str = "getNextIntentResult";
+pub(crate) const START_ON_UI_THREAD: &str = "startActivityOnUiThread";
+pub(crate) const REGISTER_PIPE: &str = "registerPipe";
+pub(crate) const REGISTER_BYTES: &str = "registerBytes";
+pub(crate) const UNREGISTER: &str = "unregister";
+pub(crate) const FD_KEY: &str = "fd";
+pub(crate) const BYTES_KEY: &str = "bytes";
+pub(crate) const MIME_TYPE_KEY: &str = "mimeType";
+pub(crate) const START_ON_UI_THREAD_SIG: &str = sig!((Intent, int, BlockingQueue) -> ());
+pub(crate) const REGISTER_CHOSEN_RECEIVER: &str = "registerChosenReceiver";
+pub(crate) const REGISTER_CHOSEN_RECEIVER_SIG: &str = sig!((String, BlockingQueue) -> ());
+pub(crate) const UNREGISTER_CHOSEN_RECEIVER: &str = "unregisterChosenReceiver";
+pub(crate) const UNREGISTER_CHOSEN_RECEIVER_SIG: &str = sig!((BlockingQueue) -> ());
+pub(crate) const REGISTER_QUEUE_RECEIVER: &str = "registerQueueReceiver";
+pub(crate) const REGISTER_QUEUE_RECEIVER_SIG: &str =
+    sig!(("android/content/IntentFilter", String, int, BlockingQueue) -> "android/content/BroadcastReceiver");
+pub(crate) const BIND_MESSENGER_SERVICE: &str = "bindMessengerService";
+pub(crate) const BIND_MESSENGER_SERVICE_SIG: &str = sig!((Intent, BlockingQueue) -> "android/content/ServiceConnection");
+pub(crate) const NEW_REPLY_MESSENGER: &str = "newReplyMessenger";
+pub(crate) const REQUEST_PERMISSIONS: &str = "requestPermissionsForQueue";
+pub(crate) const REQUEST_PERMISSIONS_SIG: &str = sig!(([String], BlockingQueue) -> ());
+pub(crate) const REGISTER_LIFECYCLE_QUEUE: &str = "registerLifecycleQueue";
+pub(crate) const REGISTER_LIFECYCLE_QUEUE_SIG: &str = sig!((BlockingQueue) -> ());
+pub(crate) const REGISTER_RESULT_QUEUE: &str = "registerResultQueue";
+pub(crate) const UNREGISTER_RESULT_QUEUE: &str = "unregisterResultQueue";
+#[cfg(feature = "winit")]
+pub(crate) const RESULT_QUEUE_SIG: &str = sig!((BlockingQueue) -> ());
+pub(crate) const DESTROYED: &str = "destroyed";
+pub(crate) const TAKE_RESULT_FOR: &str = "takeIntentResultFor";
+pub(crate) const NEW_REPLY_MESSENGER_SIG: &str = sig!((BlockingQueue) -> "android/os/Messenger");
+
+#[cfg(feature = "result-bridge")]
+static PACKAGE: RwLock<Cow<'static, str>> = RwLock::new(Cow::Borrowed(DEFAULT_PACKAGE));
+
+/// Set the package the bridge was generated into, see [`Bridge::new`].
+#[cfg(feature = "result-bridge")]
+pub fn set_package(package: impl Into<String>) {
+    *PACKAGE.write().unwrap() = Cow::Owned(package.into());
+}
+
+/// JNI signature of the activity's `getNextIntentResult` method.
+#[cfg(feature = "result-bridge")]
+pub(crate) fn get_next_result_sig() -> String {
+    format!("()L{}/{};", PACKAGE.read().unwrap().replace('.', "/"), RESULT_CLASS)
+}
+
+/// JNI signature of the activity's `takeIntentResultFor` method.
+#[cfg(feature = "result-bridge")]
+pub(crate) fn take_result_for_sig() -> String {
+    format!("([I)L{}/{};", PACKAGE.read().unwrap().replace('.', "/"), RESULT_CLASS)
+}
+
+const RESULT_TEMPLATE: &str = r#"// Generated by android-intent. Do not edit.
+package {package};
+
+import android.content.Intent;
+
+public class {result_class} {
+    public final int requestCode;
+    public final int resultCode;
+    public final Intent data;
+
+    public {result_class}(int requestCode, int resultCode, Intent data) {
+        this.requestCode = requestCode;
+        this.resultCode = resultCode;
+        this.data = data;
+    }
+}
+"#;
+
+const ACTIVITY_TEMPLATE: &str = r#"// Generated by android-intent. Do not edit.
+package {package};
+
+import android.content.BroadcastReceiver;
+import android.content.ComponentName;
+import android.content.Context;
+import android.content.Intent;
+import android.content.IntentFilter;
+import android.content.ServiceConnection;
+import android.os.Build;
+import android.os.Handler;
+import android.os.IBinder;
+import android.os.Looper;
+import android.os.Message;
+import android.os.Messenger;
+import java.util.List;
+import java.util.Map;
+import java.util.concurrent.BlockingQueue;
+import java.util.concurrent.ConcurrentHashMap;
+import java.util.concurrent.ConcurrentLinkedQueue;
+import java.util.concurrent.CopyOnWriteArrayList;
+import java.util.concurrent.atomic.AtomicInteger;
+
+public class {activity_class} extends {superclass} {
+    private final ConcurrentLinkedQueue<{result_class}> intentResults = new ConcurrentLinkedQueue<>();
+    private final Map<BroadcastReceiver, BlockingQueue<Object>> chosenReceivers = new ConcurrentHashMap<>();
+    private final Map<Integer, BlockingQueue<Object>> permissionRequests = new ConcurrentHashMap<>();
+    private final AtomicInteger nextPermissionRequest = new AtomicInteger(0x7100);
+    private final List<BlockingQueue<Object>> lifecycleQueues = new CopyOnWriteArrayList<>();
+    private final List<BlockingQueue<Object>> resultQueues = new CopyOnWriteArrayList<>();
+
+    @Override
+    protected void onActivityResult(int requestCode, int resultCode, Intent data) {
+        super.onActivityResult(requestCode, resultCode, data);
+        {result_class} result = new {result_class}(requestCode, resultCode, data);
+        if (resultQueues.isEmpty()) {
+            intentResults.add(result);
+        }
+        for (BlockingQueue<Object> results : resultQueues) {
+            results.add(result);
+        }
+    }
+
+    public void {register_result_queue}(BlockingQueue<Object> results) {
+        resultQueues.add(results);
+    }
+
+    public void {unregister_result_queue}(BlockingQueue<Object> results) {
+        resultQueues.remove(results);
+        // Hand results nobody took back to getNextIntentResult.
+        for (Object result : results) {
+            if (result instanceof {result_class}) {
+                intentResults.add(({result_class}) result);
+            }
+        }
+    }
+
+    public {result_class} {get_next_result}() {
+        return intentResults.poll();
+    }
+
+    public void {start_on_ui_thread}(Intent intent, int requestCode, BlockingQueue<Object> done) {
+        runOnUiThread(() -> {
+            try {
+                if (requestCode < 0) {
+                    startActivity(intent);
+                } else {
+                    startActivityForResult(intent, requestCode);
+                }
+                done.add(Boolean.TRUE);
+            } catch (RuntimeException e) {
+                done.add(e);
+            }
+        });
+    }
+
+    public void {register_chosen_receiver}(String action, BlockingQueue<Object> chosen) {
+        BroadcastReceiver receiver = new BroadcastReceiver() {
+            @Override
+            public void onReceive(Context context, Intent intent) {
+                if (chosenReceivers.remove(this) == null) {
+                    return;
+                }
+                unregisterReceiver(this);
+                ComponentName component = intent.getParcelableExtra(Intent.EXTRA_CHOSEN_COMPONENT);
+                chosen.add(component == null ? Boolean.FALSE : component.flattenToString());
+            }
+        };
+        chosenReceivers.put(receiver, chosen);
+
+        IntentFilter filter = new IntentFilter(action);
+        if (Build.VERSION.SDK_INT >= 33) {
+            registerReceiver(receiver, filter, Context.RECEIVER_NOT_EXPORTED);
+        } else {
+            registerReceiver(receiver, filter);
+        }
+    }
+
+    public void {unregister_chosen_receiver}(BlockingQueue<Object> chosen) {
+        for (Map.Entry<BroadcastReceiver, BlockingQueue<Object>> entry : chosenReceivers.entrySet()) {
+            if (entry.getValue() == chosen && chosenReceivers.remove(entry.getKey()) != null) {
+                unregisterReceiver(entry.getKey());
+                chosen.add(Boolean.FALSE);
+            }
+        }
+    }
+
+    public BroadcastReceiver {register_queue_receiver}(IntentFilter filter, String permission, int flags, BlockingQueue<Object> received) {
+        BroadcastReceiver receiver = new BroadcastReceiver() {
+            @Override
+            public void onReceive(Context context, Intent intent) {
+                received.add(intent);
+            }
+        };
+        if (flags != 0) {
+            registerReceiver(receiver, filter, permission, null, flags);
+        } else {
+            registerReceiver(receiver, filter, permission, null);
+        }
+        return receiver;
+    }
+
+    public ServiceConnection {bind_messenger_service}(Intent intent, BlockingQueue<Object> bound) {
+        ServiceConnection connection = new ServiceConnection() {
+            @Override
+            public void onServiceConnected(ComponentName name, IBinder service) {
+                bound.add(new Messenger(service));
+            }
+
+            @Override
+            public void onServiceDisconnected(ComponentName name) {
+            }
+        };
+        if (!bindService(intent, connection, Context.BIND_AUTO_CREATE)) {
+            unbindService(connection);
+            bound.add(Boolean.FALSE);
+        }
+        return connection;
+    }
+
+    public void {request_permissions}(String[] permissions, BlockingQueue<Object> results) {
+        // Permission request codes must fit in 16 bits.
+        int requestCode = nextPermissionRequest.getAndIncrement() & 0xffff;
+        permissionRequests.put(requestCode, results);
+        runOnUiThread(() -> requestPermissions(permissions, requestCode));
+    }
+
+    @Override
+    public void onRequestPermissionsResult(int requestCode, String[] permissions, int[] grantResults) {
+        BlockingQueue<Object> results = permissionRequests.remove(requestCode);
+        if (results == null) {
+            super.onRequestPermissionsResult(requestCode, permissions, grantResults);
+            return;
+        }
+        results.add(grantResults);
+    }
+
+    public {result_class} {take_result_for}(int[] requestCodes) {
+        for ({result_class} result : intentResults) {
+            for (int requestCode : requestCodes) {
+                if (result.requestCode == requestCode && intentResults.remove(result)) {
+                    return result;
+                }
+            }
+        }
+        return null;
+    }
+
+    public void {register_lifecycle_queue}(BlockingQueue<Object> events) {
+        lifecycleQueues.add(events);
+    }
+
+    @Override
+    protected void onResume() {
+        super.onResume();
+        for (BlockingQueue<Object> events : lifecycleQueues) {
+            events.add(Boolean.TRUE);
+        }
+    }
+
+    @Override
+    protected void onPause() {
+        for (BlockingQueue<Object> events : lifecycleQueues) {
+            events.add(Boolean.FALSE);
+        }
+        super.onPause();
+    }
+
+    public Messenger {new_reply_messenger}(BlockingQueue<Object> replies) {
+        return new Messenger(new Handler(Looper.getMainLooper(), message -> {
+            Message copy = Message.obtain();
+            copy.copyFrom(message);
+            replies.add(copy);
+            return true;
+        }));
+    }
+
+    @Override
+    protected void onDestroy() {
+        // Choosers the user cancelled never report back; release their waiting threads.
+        for (Map.Entry<BroadcastReceiver, BlockingQueue<Object>> entry : chosenReceivers.entrySet()) {
+            unregisterReceiver(entry.getKey());
+            entry.getValue().add(Boolean.FALSE);
+        }
+        chosenReceivers.clear();
+        for (BlockingQueue<Object> results : permissionRequests.values()) {
+            results.add(new int[0]);
+        }
+        permissionRequests.clear();
+        for (BlockingQueue<Object> events : lifecycleQueues) {
+            events.add("{destroyed}");
+        }
+        lifecycleQueues.clear();
+        for (BlockingQueue<Object> results : resultQueues) {
+            results.add("{destroyed}");
+        }
+        resultQueues.clear();
+        super.onDestroy();
+    }
+}
+"#;
+
+const SHARE_PROVIDER_TEMPLATE: &str = r#"// Generated by android-intent. Do not edit.
+package {package};
+
+import android.content.ContentProvider;
+import android.content.ContentValues;
+import android.database.Cursor;
+import android.database.MatrixCursor;
+import android.net.Uri;
+import android.os.Bundle;
+import android.os.ParcelFileDescriptor;
+import android.provider.OpenableColumns;
+import java.io.FileNotFoundException;
+import java.io.IOException;
+import java.io.OutputStream;
+import java.util.Map;
+import java.util.concurrent.ConcurrentHashMap;
+
+public class {share_provider_class} extends ContentProvider {
+    private static final Map<String, ParcelFileDescriptor> pipes = new ConcurrentHashMap<>();
+    private static final Map<String, byte[]> buffers = new ConcurrentHashMap<>();
+    private static final Map<String, String> mimeTypes = new ConcurrentHashMap<>();
+
+    @Override
+    public boolean onCreate() {
+        return true;
+    }
+
+    @Override
+    public Bundle call(String method, String arg, Bundle extras) {
+        if ("{register_pipe}".equals(method)) {
+            pipes.put(arg, extras.getParcelable("{fd_key}"));
+            mimeTypes.put(arg, extras.getString("{mime_type_key}"));
+        } else if ("{register_bytes}".equals(method)) {
+            buffers.put(arg, extras.getByteArray("{bytes_key}"));
+            mimeTypes.put(arg, extras.getString("{mime_type_key}"));
+        } else if ("{unregister}".equals(method)) {
+            pipes.remove(arg);
+            buffers.remove(arg);
+            mimeTypes.remove(arg);
+        }
+        return null;
+    }
+
+    @Override
+    public ParcelFileDescriptor openFile(Uri uri, String mode) throws FileNotFoundException {
+        String name = uri.getLastPathSegment();
+        byte[] bytes = buffers.get(name);
+        if (bytes != null) {
+            return writeToPipe(bytes);
+        }
+
+        ParcelFileDescriptor pipe = pipes.remove(name);
+        if (pipe == null) {
+            throw new FileNotFoundException(uri.toString());
+        }
+        return pipe;
+    }
+
+    private static ParcelFileDescriptor writeToPipe(byte[] bytes) throws FileNotFoundException {
+        ParcelFileDescriptor[] pipe;
+        try {
+            pipe = ParcelFileDescriptor.createPipe();
+        } catch (IOException e) {
+            throw new FileNotFoundException(e.toString());
+        }
+
+        ParcelFileDescriptor writeEnd = pipe[1];
+        new Thread(() -> {
+            try (OutputStream out = new ParcelFileDescriptor.AutoCloseOutputStream(writeEnd)) {
+                out.write(bytes);
+            } catch (IOException e) {
+                // The reader went away.
+            }
+        }).start();
+        return pipe[0];
+    }
+
+    @Override
+    public String getType(Uri uri) {
+        return mimeTypes.get(uri.getLastPathSegment());
+    }
+
+    @Override
+    public Cursor query(Uri uri, String[] projection, String selection, String[] selectionArgs, String sortOrder) {
+        String name = uri.getLastPathSegment();
+        byte[] bytes = buffers.get(name);
+        MatrixCursor cursor = new MatrixCursor(new String[] { OpenableColumns.DISPLAY_NAME, OpenableColumns.SIZE });
+        cursor.addRow(new Object[] { name, bytes == null ? null : (long) bytes.length });
+        return cursor;
+    }
+
+    @Override
+    public Uri insert(Uri uri, ContentValues values) {
+        return null;
+    }
+
+    @Override
+    public int delete(Uri uri, String selection, String[] selectionArgs) {
+        return 0;
+    }
+
+    @Override
+    public int update(Uri uri, ContentValues values, String selection, String[] selectionArgs) {
+        return 0;
+    }
+}
+"#;
+
+/// Generator for the Java bridge sources.
+#[must_use]
+pub struct Bridge {
+    package: String,
+    superclass: String,
+}
+
+impl Bridge {
+    /// Generate into `package`, which has to match [`set_package`] at runtime.
+    pub fn new(package: impl Into<String>) -> Self {
+        Self {
+            package: package.into(),
+            superclass: "android.app.NativeActivity".to_owned(),
+        }
+    }
+
+    /// Extend `superclass` (fully qualified) instead of `android.app.NativeActivity`, e.g.
+    /// `com.google.androidgamesdk.GameActivity`.
+    pub fn with_superclass(self, superclass: impl Into<String>) -> Self {
+        Self {
+            superclass: superclass.into(),
+            ..self
+        }
+    }
+
+    /// Source of the class `getNextIntentResult` returns.
+    pub fn result_class_source(&self) -> String {
+        self.render(RESULT_TEMPLATE)
+    }
+
+    /// Source of the activity base class providing the bridge methods.
+    pub fn activity_source(&self) -> String {
+        self.render(ACTIVITY_TEMPLATE)
+    }
+
+    /// Source of the content provider serving data shared from Rust, see the
+    /// [`content`](crate::content) module. Only needed by apps using it, which declare it in the
+    /// manifest:
+    /// ```xml
+    /// <provider android:name=".RustShareProvider" android:authorities="com.example.app.share"
+    ///     android:exported="false" android:grantUriPermissions="true" />
+    /// ```
+    pub fn share_provider_source(&self) -> String {
+        self.render(SHARE_PROVIDER_TEMPLATE)
+    }
+
+    /// Write all classes below `java_root`, in the directory of their package. Returns the paths
+    /// written.
+    pub fn write_to(&self, java_root: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+        let dir = self
+            .package
+            .split('.')
+            .fold(java_root.as_ref().to_path_buf(), |dir, part| dir.join(part));
+        fs::create_dir_all(&dir)?;
+
+        [
+            (RESULT_CLASS, self.result_class_source()),
+            (ACTIVITY_CLASS, self.activity_source()),
+            (SHARE_PROVIDER_CLASS, self.share_provider_source()),
+        ]
+        .into_iter()
+        .map(|(class, source)| {
+            let path = dir.join(format!("{class}.java"));
+            // Leave unchanged files alone so Gradle doesn't recompile them.
+            if fs::read_to_string(&path).ok().as_deref() != Some(source.as_str()) {
+                fs::write(&path, source)?;
+            }
+            Ok(path)
+        })
+        .collect()
+    }
+
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("{package}", &self.package)
+            .replace("{superclass}", &self.superclass)
+            .replace("{result_class}", RESULT_CLASS)
+            .replace("{activity_class}", ACTIVITY_CLASS)
+            .replace("{get_next_result}", GET_NEXT_RESULT)
+            .replace("{start_on_ui_thread}", START_ON_UI_THREAD)
+            .replace("{register_chosen_receiver}", REGISTER_CHOSEN_RECEIVER)
+            .replace("{unregister_chosen_receiver}", UNREGISTER_CHOSEN_RECEIVER)
+            .replace("{register_queue_receiver}", REGISTER_QUEUE_RECEIVER)
+            .replace("{bind_messenger_service}", BIND_MESSENGER_SERVICE)
+            .replace("{new_reply_messenger}", NEW_REPLY_MESSENGER)
+            .replace("{request_permissions}", REQUEST_PERMISSIONS)
+            .replace("{take_result_for}", TAKE_RESULT_FOR)
+            .replace("{register_lifecycle_queue}", REGISTER_LIFECYCLE_QUEUE)
+            .replace("{register_result_queue}", REGISTER_RESULT_QUEUE)
+            .replace("{unregister_result_queue}", UNREGISTER_RESULT_QUEUE)
+            .replace("{destroyed}", DESTROYED)
+            .replace("{share_provider_class}", SHARE_PROVIDER_CLASS)
+            .replace("{register_pipe}", REGISTER_PIPE)
+            .replace("{register_bytes}", REGISTER_BYTES)
+            .replace("{unregister}", UNREGISTER)
+            .replace("{fd_key}", FD_KEY)
+            .replace("{bytes_key}", BYTES_KEY)
+            .replace("{mime_type_key}", MIME_TYPE_KEY)
+    }
+}
@@ -0,0 +1,159 @@
+use jni::AttachGuard;
+
+use crate::{Flags, Intent, Uri};
+
+enum Op {
+    Extra(String, String),
+    ExtraInt(String, i32),
+    ExtraBool(String, bool),
+    ExtraUri(String, Uri),
+    Type(String),
+    Package(String),
+    AddFlags(Flags),
+    RemoveFlags(Flags),
+    Category(String),
+    Chooser(Option<String>),
+}
+
+/// Records intent builder steps without touching JNI, to replay them later with [`Self::build`].
+///
+/// Unlike [`Intent`], this is `Send + 'static`, so it can be assembled on any thread and handed
+/// to the thread that launches it.
+/// ```no_run
+/// use android_intent::{Action, Extra, IntentBuilder};
+///
+/// let builder = std::thread::spawn(|| {
+///     IntentBuilder::new(Action::Send)
+///         .with_type("text/plain")
+///         .with_extra(Extra::Text, "Hello World!")
+///         .into_chooser()
+/// })
+/// .join()
+/// .unwrap();
+///
+/// # let mut intent_env = android_intent::IntentEnv::new();
+/// # android_intent::with_current_env(&mut intent_env, |env| {
+/// builder.build(env).start_activity().unwrap();
+/// # })
+/// ```
+#[must_use]
+pub struct IntentBuilder {
+    action: String,
+    uri: Option<String>,
+    ops: Vec<Op>,
+}
+
+impl IntentBuilder {
+    /// See [`Intent::new`].
+    pub fn new(action: impl AsRef<str>) -> Self {
+        Self {
+            action: action.as_ref().to_owned(),
+            uri: None,
+            ops: Vec::new(),
+        }
+    }
+
+    /// See [`Intent::new_with_uri`].
+    pub fn new_with_uri(action: impl AsRef<str>, uri: impl AsRef<str>) -> Self {
+        Self {
+            uri: Some(uri.as_ref().to_owned()),
+            ..Self::new(action)
+        }
+    }
+
+    /// See [`Intent::with_extra`].
+    pub fn with_extra(self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.push(Op::Extra(key.as_ref().to_owned(), value.as_ref().to_owned()))
+    }
+
+    /// See [`Intent::with_extra_int`].
+    pub fn with_extra_int(self, key: impl AsRef<str>, value: i32) -> Self {
+        self.push(Op::ExtraInt(key.as_ref().to_owned(), value))
+    }
+
+    /// See [`Intent::with_extra_bool`].
+    pub fn with_extra_bool(self, key: impl AsRef<str>, value: bool) -> Self {
+        self.push(Op::ExtraBool(key.as_ref().to_owned(), value))
+    }
+
+    /// See [`Intent::with_extra_uri`].
+    pub fn with_extra_uri(self, key: impl AsRef<str>, value: impl Into<Uri>) -> Self {
+        self.push(Op::ExtraUri(key.as_ref().to_owned(), value.into()))
+    }
+
+    /// See [`Intent::with_type`].
+    pub fn with_type(self, type_name: impl AsRef<str>) -> Self {
+        self.push(Op::Type(type_name.as_ref().to_owned()))
+    }
+
+    /// See [`Intent::with_package`].
+    pub fn with_package(self, package: impl AsRef<str>) -> Self {
+        self.push(Op::Package(package.as_ref().to_owned()))
+    }
+
+    /// See [`Intent::add_flags`].
+    pub fn add_flags(self, flags: Flags) -> Self {
+        self.push(Op::AddFlags(flags))
+    }
+
+    /// See [`Intent::remove_flags`].
+    pub fn remove_flags(self, flags: Flags) -> Self {
+        self.push(Op::RemoveFlags(flags))
+    }
+
+    /// See [`Intent::add_category`].
+    pub fn add_category(self, category: impl AsRef<str>) -> Self {
+        self.push(Op::Category(category.as_ref().to_owned()))
+    }
+
+    /// See [`Intent::into_chooser`].
+    pub fn into_chooser(self) -> Self {
+        self.push(Op::Chooser(None))
+    }
+
+    /// See [`Intent::into_chooser_with_title`].
+    pub fn into_chooser_with_title(self, title: Option<impl AsRef<str>>) -> Self {
+        self.push(Op::Chooser(title.map(|title| title.as_ref().to_owned())))
+    }
+
+    /// Replay the recorded steps against `env`.
+    pub fn build(self, env: AttachGuard<'_>) -> Intent<'_> {
+        let intent = match &self.uri {
+            Some(uri) => Intent::new_with_uri(env, &self.action, uri),
+            None => Intent::new(env, &self.action),
+        };
+
+        self.replay(intent)
+    }
+
+    /// Replay the recorded steps against a [mock](Intent::mock) intent.
+    #[cfg(feature = "mock")]
+    pub fn build_mock(self) -> Intent<'static> {
+        let intent = match &self.uri {
+            Some(uri) => Intent::mock_with_uri(&self.action, uri),
+            None => Intent::mock(&self.action),
+        };
+
+        self.replay(intent)
+    }
+
+    fn replay(self, intent: Intent<'_>) -> Intent<'_> {
+        self.ops.into_iter().fold(intent, |intent, op| match op {
+            Op::Extra(key, value) => intent.with_extra(key, value),
+            Op::ExtraInt(key, value) => intent.with_extra_int(key, value),
+            Op::ExtraBool(key, value) => intent.with_extra_bool(key, value),
+            Op::ExtraUri(key, value) => intent.with_extra_uri(key, value),
+            Op::Type(type_name) => intent.with_type(type_name),
+            Op::Package(package) => intent.with_package(package),
+            Op::AddFlags(flags) => intent.add_flags(flags),
+            Op::RemoveFlags(flags) => intent.remove_flags(flags),
+            Op::Category(category) => intent.add_category(category),
+            Op::Chooser(title) => intent.into_chooser_with_title(title),
+        })
+    }
+
+    fn push(mut self, op: Op) -> Self {
+        self.ops.push(op);
+        self
+    }
+}
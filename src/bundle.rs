@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use jni::{errors::Error, objects::{GlobalRef, JObject, JObjectArray, JString}, JNIEnv};
+use crate::trace::Traced;
+use crate::{leak_check, ExtraValue};
+
+/// A live `android.os.Bundle`, for exploring extras without knowing their keys in advance.
+///
+/// Unlike [`ExtraValue::Bundle`], values are only converted when asked for.
+/// ```no_run
+/// # let intent_env = android_intent::IntentEnv::new();
+/// # let mut intent = android_intent::Intent::new(intent_env.get_env(), android_intent::Action::Send);
+/// let mut env = intent_env.get_env();
+/// if let Some(extras) = intent.extras_bundle().unwrap() {
+///     for key in extras.keys(&mut env).unwrap() {
+///         log::info!("{key} = {:?}", extras.get(&mut env, &key).unwrap());
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Bundle {
+    object: GlobalRef,
+}
+
+impl Clone for Bundle {
+    fn clone(&self) -> Self {
+        leak_check::acquired(leak_check::Kind::Bundle);
+        Self { object: self.object.clone() }
+    }
+}
+
+impl Drop for Bundle {
+    fn drop(&mut self) {
+        // The `GlobalRef` deletes the reference itself once its last clone is gone.
+        leak_check::released(leak_check::Kind::Bundle);
+    }
+}
+
+impl Bundle {
+    /// A new bundle holding `values`.
+    pub fn new(env: &mut JNIEnv, values: &BTreeMap<String, ExtraValue>) -> Result<Self, crate::Error> {
+        let object = crate::backend::new_bundle(env, values)?;
+        Self::from_object(env, &object)
+    }
+
+    /// Wrap an existing `android.os.Bundle`.
+    pub fn from_object(env: &mut JNIEnv, object: &JObject) -> Result<Self, crate::Error> {
+        let object = env.new_global_ref(object)?;
+        leak_check::acquired(leak_check::Kind::Bundle);
+
+        Ok(Self { object })
+    }
+
+    /// The underlying `android.os.Bundle`.
+    pub fn as_obj(&self) -> &JObject<'static> {
+        self.object.as_obj()
+    }
+
+    /// Keys of all values, via `keySet()`.
+    pub fn keys(&self, env: &mut JNIEnv) -> Result<Vec<String>, crate::Error> {
+        Ok(keys(env, self.as_obj())?)
+    }
+
+    /// The value stored under `key`, or [`ExtraValue::Null`] if there is none.
+    pub fn get(&self, env: &mut JNIEnv, key: &str) -> Result<ExtraValue, crate::Error> {
+        let object = get(env, self.as_obj(), key)?;
+        ExtraValue::from_object(env, &object)
+    }
+
+    /// Convert all values at once.
+    pub fn to_map(&self, env: &mut JNIEnv) -> Result<BTreeMap<String, ExtraValue>, crate::Error> {
+        ExtraValue::map_from_bundle(env, self.as_obj())
+    }
+}
+
+/// Keys of an `android.os.Bundle`, via `keySet().toArray()`.
+pub(crate) fn keys(env: &mut JNIEnv, bundle: &JObject) -> Result<Vec<String>, Error> {
+    let key_set = env.call(bundle, "keySet", "()Ljava/util/Set;", &[])?.l()?;
+    string_collection(env, &key_set)
+}
+
+/// Elements of a `java.util.Collection<String>`, or nothing if `collection` is null.
+pub(crate) fn string_collection(env: &mut JNIEnv, collection: &JObject) -> Result<Vec<String>, Error> {
+    let mut strings = Vec::new();
+    for string in self::collection(env, collection)? {
+        let string = env.get_string(&JString::from(string))?.into();
+        strings.push(string);
+    }
+
+    Ok(strings)
+}
+
+/// Elements of a `java.util.Collection`, or nothing if `collection` is null.
+pub(crate) fn collection<'a>(env: &mut JNIEnv<'a>, collection: &JObject) -> Result<Vec<JObject<'a>>, Error> {
+    if collection.is_null() {
+        return Ok(Vec::new());
+    }
+
+    let array = env
+        .call(collection, "toArray", "()[Ljava/lang/Object;", &[])?
+        .l()?;
+
+    object_array(env, array.into())
+}
+
+/// Elements of a Java object array.
+pub(crate) fn object_array<'a>(env: &mut JNIEnv<'a>, array: JObjectArray<'a>) -> Result<Vec<JObject<'a>>, Error> {
+    let len = env.get_array_length(&array)?;
+
+    (0..len).map(|i| env.get_object_array_element(&array, i)).collect()
+}
+
+/// The raw value stored under `key`, or null.
+pub(crate) fn get<'a>(env: &mut JNIEnv<'a>, bundle: &JObject, key: &str) -> Result<JObject<'a>, Error> {
+    let key = env.new_string(key)?;
+    env.call(bundle, "get", "(Ljava/lang/String;)Ljava/lang/Object;", &[(&key).into()])?
+        .l()
+}
+
+/// `Object.toString()` of a non-null `object`.
+pub(crate) fn to_string(env: &mut JNIEnv, object: &JObject) -> Result<String, Error> {
+    let jstring: JString = env.call(object, "toString", "()Ljava/lang/String;", &[])?.l()?.into();
+    let string = env.get_string(&jstring)?.into();
+    Ok(string)
+}
+
+/// `getClass().getSimpleName()` of a non-null `object`.
+pub(crate) fn simple_class_name(env: &mut JNIEnv, object: &JObject) -> Result<String, Error> {
+    let class = env.call(object, "getClass", "()Ljava/lang/Class;", &[])?.l()?;
+    let name: JString = env.call(class, "getSimpleName", "()Ljava/lang/String;", &[])?.l()?.into();
+    let name = env.get_string(&name)?.into();
+    Ok(name)
+}
+
+/// `getClass().getName()` of a non-null `object`.
+pub(crate) fn class_name(env: &mut JNIEnv, object: &JObject) -> Result<String, Error> {
+    let class = env.call(object, "getClass", "()Ljava/lang/Class;", &[])?.l()?;
+    let name: JString = env.call(class, "getName", "()Ljava/lang/String;", &[])?.l()?.into();
+    let name = env.get_string(&name)?.into();
+    Ok(name)
+}
@@ -0,0 +1,152 @@
+//! An in-process publish/subscribe bus keyed by action, the replacement for the deprecated
+//! `LocalBroadcastManager`: parts of the app exchange [`IntentSpec`]s without going through the
+//! system broadcast machinery, or JNI at all.
+//! ```
+//! use std::sync::mpsc;
+//!
+//! use android_intent::{bus, IntentSpec};
+//!
+//! let (sender, receiver) = mpsc::channel();
+//! let sender = std::sync::Mutex::new(sender);
+//! let _subscription = bus::subscribe("com.example.app.LOGGED_IN", move |spec| {
+//!     let _ = sender.lock().unwrap().send(spec.extras.clone());
+//! });
+//!
+//! let delivered = bus::publish(&IntentSpec {
+//!     action: Some("com.example.app.LOGGED_IN".to_owned()),
+//!     ..Default::default()
+//! });
+//! assert_eq!(delivered, 1);
+//! assert!(receiver.try_recv().is_ok());
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::IntentSpec;
+
+type Handler = Arc<dyn Fn(&IntentSpec) + Send + Sync>;
+type Subscribers = HashMap<String, Vec<(u64, Handler)>>;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+fn subscribers() -> &'static Mutex<Subscribers> {
+    static SUBSCRIBERS: OnceLock<Mutex<Subscribers>> = OnceLock::new();
+
+    SUBSCRIBERS.get_or_init(Default::default)
+}
+
+/// A handler registered with [`subscribe`]; dropping it unsubscribes.
+#[must_use = "dropping the subscription unsubscribes right away"]
+pub struct Subscription {
+    action: String,
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut subscribers = subscribers().lock().unwrap();
+        if let Some(handlers) = subscribers.get_mut(&self.action) {
+            handlers.retain(|(id, _)| *id != self.id);
+            if handlers.is_empty() {
+                subscribers.remove(&self.action);
+            }
+        }
+    }
+}
+
+/// Call `handler` with every spec [`publish`]ed with `action`, until the returned
+/// [`Subscription`] is dropped.
+pub fn subscribe(action: impl Into<String>, handler: impl Fn(&IntentSpec) + Send + Sync + 'static) -> Subscription {
+    let action = action.into();
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    subscribers()
+        .lock()
+        .unwrap()
+        .entry(action.clone())
+        .or_default()
+        .push((id, Arc::new(handler)));
+
+    Subscription { action, id }
+}
+
+/// Deliver `spec` to the subscribers of its action, synchronously on this thread. Returns how
+/// many there were; a spec without an action reaches none.
+///
+/// Handlers may publish or (un)subscribe themselves: they run after the bus is unlocked.
+pub fn publish(spec: &IntentSpec) -> usize {
+    let Some(action) = &spec.action else {
+        return 0;
+    };
+    let handlers: Vec<Handler> = match subscribers().lock().unwrap().get(action) {
+        Some(handlers) => handlers.iter().map(|(_, handler)| handler.clone()).collect(),
+        None => return 0,
+    };
+
+    for handler in &handlers {
+        handler(spec);
+    }
+
+    handlers.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    fn spec(action: &str) -> IntentSpec {
+        IntentSpec { action: Some(action.to_owned()), ..Default::default() }
+    }
+
+    fn counter() -> (Arc<AtomicUsize>, impl Fn(&IntentSpec) + Send + Sync + 'static) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let handler_count = count.clone();
+        (count, move |_: &IntentSpec| {
+            handler_count.fetch_add(1, Ordering::SeqCst);
+        })
+    }
+
+    #[test]
+    fn publish_reaches_only_subscribers_of_the_action() {
+        let (first, handler) = counter();
+        let _first = subscribe("test.bus.DISPATCH", handler);
+        let (second, handler) = counter();
+        let _second = subscribe("test.bus.DISPATCH", handler);
+        let (other, handler) = counter();
+        let _other = subscribe("test.bus.OTHER", handler);
+
+        assert_eq!(publish(&spec("test.bus.DISPATCH")), 2);
+        assert_eq!(first.load(Ordering::SeqCst), 1);
+        assert_eq!(second.load(Ordering::SeqCst), 1);
+        assert_eq!(other.load(Ordering::SeqCst), 0);
+
+        assert_eq!(publish(&IntentSpec::default()), 0);
+    }
+
+    #[test]
+    fn dropping_the_subscription_unsubscribes() {
+        let (count, handler) = counter();
+        let subscription = subscribe("test.bus.DROPPED", handler);
+        assert_eq!(publish(&spec("test.bus.DROPPED")), 1);
+
+        drop(subscription);
+        assert_eq!(publish(&spec("test.bus.DROPPED")), 0);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn handlers_may_publish_and_subscribe() {
+        let (count, handler) = counter();
+        let _inner = subscribe("test.bus.INNER", handler);
+        let _outer = subscribe("test.bus.OUTER", |_| {
+            let _late = subscribe("test.bus.INNER", |_| {});
+            publish(&spec("test.bus.INNER"));
+        });
+
+        assert_eq!(publish(&spec("test.bus.OUTER")), 1);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,99 @@
+//! Checks on the app that sent an intent, for activities that act on sensitive extras.
+//!
+//! From API 30 on, the checked packages must be visible to this app, e.g. through a `<queries>`
+//! manifest entry; invisible ones look uninstalled.
+//! ```no_run
+//! use android_intent::caller;
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! # let mut env = intent_env.get_env();
+//! let trusted = match caller::calling_package(&mut env).unwrap() {
+//!     Some(package) => caller::has_same_signature(&mut env, &package).unwrap(),
+//!     None => false,
+//! };
+//! ```
+
+use jni::JNIEnv;
+
+use crate::trace::Traced;
+use crate::{backend, context, ApiLevel, Error};
+
+const SIGNATURE_MATCH: i32 = 0;
+const PERMISSION_GRANTED: i32 = 0;
+
+/// The package that started this activity, via `Activity.getCallingPackage`. Only known, and
+/// then reliable, when the activity was started for a result; the
+/// [referrer](crate::IntentEnv::get_referrer) is set by the caller and can't be trusted.
+pub fn calling_package(env: &mut JNIEnv) -> Result<Option<String>, Error> {
+    let activity = context::activity(env)?;
+    backend::string_getter(env, &activity, "getCallingPackage")
+}
+
+/// The uid `package` runs as, or `None` if it isn't installed.
+pub fn package_uid(env: &mut JNIEnv, package: &str) -> Result<Option<i32>, Error> {
+    let package_manager = context::package_manager(env)?;
+    let jpackage = env.new_string(package)?;
+
+    let uid = if ApiLevel(24).is_supported(env)? {
+        env.call(&package_manager, "getPackageUid", "(Ljava/lang/String;I)I", &[(&jpackage).into(), 0.into()])
+            .and_then(|uid| uid.i())
+    } else {
+        env.call(
+            &package_manager,
+            "getApplicationInfo",
+            "(Ljava/lang/String;I)Landroid/content/pm/ApplicationInfo;",
+            &[(&jpackage).into(), 0.into()],
+        )
+        .and_then(|info| info.l())
+        .and_then(|info| env.get_field(info, "uid", "I")?.i())
+    };
+
+    match uid {
+        Ok(uid) => Ok(Some(uid)),
+        Err(jni::errors::Error::JavaException) => {
+            // NameNotFoundException.
+            env.exception_clear()?;
+            Ok(None)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Whether `package` is signed with the same certificate as this app, via
+/// `PackageManager.checkSignatures`. `false` if it isn't installed.
+pub fn has_same_signature(env: &mut JNIEnv, package: &str) -> Result<bool, Error> {
+    let package_manager = context::package_manager(env)?;
+    let own = context::package_name(env)?;
+    let own = env.new_string(own)?;
+    let other = env.new_string(package)?;
+
+    let result = env
+        .call(
+            &package_manager,
+            "checkSignatures",
+            "(Ljava/lang/String;Ljava/lang/String;)I",
+            &[(&own).into(), (&other).into()],
+        )?
+        .i()?;
+
+    Ok(result == SIGNATURE_MATCH)
+}
+
+/// Whether `package` was granted `permission`, e.g. a signature permission this app defines,
+/// via `PackageManager.checkPermission`.
+pub fn has_permission(env: &mut JNIEnv, package: &str, permission: &str) -> Result<bool, Error> {
+    let package_manager = context::package_manager(env)?;
+    let jpermission = env.new_string(permission)?;
+    let jpackage = env.new_string(package)?;
+
+    let result = env
+        .call(
+            &package_manager,
+            "checkPermission",
+            "(Ljava/lang/String;Ljava/lang/String;)I",
+            &[(&jpermission).into(), (&jpackage).into()],
+        )?
+        .i()?;
+
+    Ok(result == PERMISSION_GRANTED)
+}
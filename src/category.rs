@@ -1,3 +1,6 @@
+use crate::backend::INTENT_CLASS;
+use crate::constants;
+
 
 pub enum Category {
     Openable,
@@ -5,8 +8,20 @@ pub enum Category {
 
 impl AsRef<str> for Category {
     fn as_ref(&self) -> &str {
+        self.field_name()
+    }
+}
+
+impl Category {
+    /// The name of the `Intent` field holding the category string.
+    const fn field_name(&self) -> &'static str {
         match self {
             Self::Openable => "CATEGORY_OPENABLE",
         }
     }
-}
\ No newline at end of file
+}
+
+// Every category names a real `Intent` constant.
+const _: () = {
+    constants::expect_string(INTENT_CLASS, Category::Openable.field_name());
+};
@@ -0,0 +1,131 @@
+//! Choosers that report which target the user picked.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use jni::objects::{GlobalRef, JObject};
+
+use crate::backend::{Inner, INTENT_CLASS};
+use crate::trace::{debug, Traced};
+use crate::{bridge, bundle, context, lifecycle, pending, ApiLevel, Error, IntentSpec};
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// A chooser callback still waiting for the user, from
+/// [`Intent::into_chooser_with_request`](crate::Intent::into_chooser_with_request).
+///
+/// Dropping it leaves the callback registered; the bridge activity releases it when destroyed.
+#[derive(Clone)]
+#[must_use]
+pub struct PendingRequest {
+    /// The queue the bridge reports the chosen component on, `None` for mock intents.
+    chosen: Option<GlobalRef>,
+}
+
+impl PendingRequest {
+    pub(crate) fn none() -> Self {
+        Self { chosen: None }
+    }
+
+    /// Drop the registration, so the callback never runs and its waiting thread ends. Does
+    /// nothing if the callback already ran.
+    pub fn cancel(&self) -> Result<(), Error> {
+        let Some(chosen) = &self.chosen else {
+            return Ok(());
+        };
+        let vm = context::java_vm()?;
+        let mut env = vm.attach_current_thread()?;
+
+        let activity = context::activity(&mut env)?;
+        env.call(
+            &activity,
+            bridge::UNREGISTER_CHOSEN_RECEIVER,
+            bridge::UNREGISTER_CHOSEN_RECEIVER_SIG,
+            &[chosen.as_obj().into()],
+        )?;
+
+        Ok(())
+    }
+
+    /// [`Self::cancel`] the request once `timeout` has passed, for flows the user abandons by
+    /// leaving the chooser open. With [`lifecycle`](crate::lifecycle) enabled, only time in the
+    /// foreground counts.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        let request = self.clone();
+        thread::spawn(move || {
+            lifecycle::sleep_foreground(timeout);
+            if let Err(err) = request.cancel() {
+                debug!("cancelling the chooser callback failed: {}", err);
+            }
+        });
+
+        self
+    }
+}
+
+/// Wrap `inner` in a chooser whose `IntentSender` broadcasts the chosen component to a receiver
+/// the [bridge](crate::bridge) activity registers, and run `on_chosen` with it once it arrives.
+pub(crate) fn wrap_with_callback(
+    inner: &mut Inner,
+    on_chosen: Box<dyn FnOnce(String) + Send>,
+) -> Result<PendingRequest, Error> {
+    let env = &mut inner.env;
+    ApiLevel(22).require(env)?;
+
+    let package = context::package_name(env)?;
+    let action = format!("{}.CHOSEN.{}", package, NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    let chosen = env.new_object("java/util/concurrent/LinkedBlockingQueue", "()V", &[])?;
+    let jaction = env.new_string(&action)?;
+
+    // Mutable, so the chooser can fill in EXTRA_CHOSEN_COMPONENT; explicit, as mutable implicit
+    // PendingIntents are rejected from API 34 on.
+    let spec = IntentSpec { action: Some(action), package: Some(package), ..Default::default() };
+    let callback = pending::broadcast(env, &spec, 0, true)?;
+    let sender = env
+        .call(&callback, "getIntentSender", "()Landroid/content/IntentSender;", &[])?
+        .l()?;
+
+    let chooser = env
+        .call_static(
+            INTENT_CLASS,
+            "createChooser",
+            "(Landroid/content/Intent;Ljava/lang/CharSequence;Landroid/content/IntentSender;)Landroid/content/Intent;",
+            &[(&inner.object).into(), (&JObject::null()).into(), (&sender).into()],
+        )?
+        .l()?;
+    let chosen = env.new_global_ref(chosen)?;
+
+    // Registered last, so nothing failing above leaves the receiver registered until onDestroy.
+    let activity = context::activity(env)?;
+    env.call(
+        &activity,
+        bridge::REGISTER_CHOSEN_RECEIVER,
+        bridge::REGISTER_CHOSEN_RECEIVER_SIG,
+        &[(&jaction).into(), chosen.as_obj().into()],
+    )?;
+    inner.replace_object(chooser)?;
+
+    let request = PendingRequest { chosen: Some(chosen.clone()) };
+    thread::spawn(move || match wait(&chosen) {
+        Ok(Some(component)) => on_chosen(component),
+        Ok(None) => {}
+        Err(err) => debug!("waiting for the chosen component failed: {}", err),
+    });
+
+    Ok(request)
+}
+
+/// Block until the bridge reports back on `chosen`: the flattened component, or `Boolean.FALSE`
+/// if the activity went away or the request was cancelled first.
+fn wait(chosen: &GlobalRef) -> Result<Option<String>, Error> {
+    let vm = context::java_vm()?;
+    let mut env = vm.attach_current_thread_as_daemon()?;
+
+    let outcome = env.call(chosen.as_obj(), "take", "()Ljava/lang/Object;", &[])?.l()?;
+    if !env.is_instance_of(&outcome, "java/lang/String")? {
+        return Ok(None);
+    }
+
+    Ok(Some(bundle::to_string(&mut env, &outcome)?))
+}
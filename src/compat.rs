@@ -0,0 +1,170 @@
+//! How newer features degrade on older API levels.
+//!
+//! Where the crate falls back to older APIs or behavior on older devices, the decision lives here
+//! as a function of `Build.VERSION.SDK_INT`, so the rest of the crate only asks "what should I do
+//! on this device". Operations that simply need a newer API level, and fail or report a default
+//! without it, check it where they run with [`ApiLevel`](crate::ApiLevel).
+
+/// `PendingIntent.FLAG_IMMUTABLE`, API 23.
+pub const PENDING_INTENT_FLAG_IMMUTABLE: i32 = 0x0400_0000;
+/// `PendingIntent.FLAG_MUTABLE`, API 31.
+pub const PENDING_INTENT_FLAG_MUTABLE: i32 = 0x0200_0000;
+/// `Context.RECEIVER_EXPORTED`, API 33.
+pub const RECEIVER_EXPORTED: i32 = 0x2;
+/// `Context.RECEIVER_NOT_EXPORTED`, API 33.
+pub const RECEIVER_NOT_EXPORTED: i32 = 0x4;
+
+/// The API level that introduced the `Intent.FLAG_*` constant named `flag`.
+pub fn flag_min_api(flag: &str) -> i32 {
+    match flag {
+        "GRANT_PERSISTABLE_URI_PERMISSION" | "GRANT_PREFIX_URI_PERMISSION" => 19,
+        "ACTIVITY_LAUNCH_ADJACENT" => 24,
+        "ACTIVITY_MATCH_EXTERNAL" => 28,
+        "ACTIVITY_REQUIRE_NON_BROWSER" | "ACTIVITY_REQUIRE_DEFAULT" => 30,
+        _ => 1,
+    }
+}
+
+/// Whether the `Intent.FLAG_*` constant named `flag` exists on `sdk`. Flags that don't are
+/// skipped instead of failing with `NoSuchFieldError`.
+pub fn supports_flag(sdk: i32, flag: &str) -> bool {
+    sdk >= flag_min_api(flag)
+}
+
+/// `Intent.removeFlags` only exists from API 26; below that, removing flags is a no-op.
+pub fn supports_remove_flags(sdk: i32) -> bool {
+    sdk >= 26
+}
+
+/// Mutability flags to OR into a `PendingIntent` request.
+///
+/// Immutable is the default from API 23, when it was introduced, and mandatory to state
+/// explicitly from API 31. Mutable needs `FLAG_MUTABLE` from API 31 and is implied before.
+pub fn pending_intent_flags(sdk: i32, mutable: bool) -> i32 {
+    match (mutable, sdk) {
+        (true, sdk) if sdk >= 31 => PENDING_INTENT_FLAG_MUTABLE,
+        (true, _) => 0,
+        (false, sdk) if sdk >= 23 => PENDING_INTENT_FLAG_IMMUTABLE,
+        (false, _) => 0,
+    }
+}
+
+/// Export flags for `Context.registerReceiver`.
+///
+/// Apps targeting API 34 must state whether other apps may send to a receiver registered at
+/// runtime. The flags exist from API 33; before that, every such receiver is exported.
+pub fn receiver_flags(sdk: i32, exported: bool) -> i32 {
+    match (exported, sdk) {
+        (_, sdk) if sdk < 33 => 0,
+        (true, _) => RECEIVER_EXPORTED,
+        (false, _) => RECEIVER_NOT_EXPORTED,
+    }
+}
+
+/// Whether `MediaStore.ACTION_PICK_IMAGES` (the system photo picker) is available. Older
+/// devices use `ACTION_GET_CONTENT` instead.
+pub fn supports_photo_picker(sdk: i32) -> bool {
+    sdk >= 33
+}
+
+/// Whether `Html.fromHtml(String, int)` exists, API 24. The flag-less overload is deprecated from
+/// then on.
+pub fn supports_html_flags(sdk: i32) -> bool {
+    sdk >= 24
+}
+
+/// Whether `Intent.setIdentifier`/`getIdentifier` exist, API 29. Identifiers are dropped before.
+pub fn supports_intent_identifier(sdk: i32) -> bool {
+    sdk >= 29
+}
+
+/// Whether the type-safe `Intent.getSerializableExtra(String, Class)` exists, API 33. The
+/// deprecated overload without a class is used before.
+pub fn supports_typed_extras(sdk: i32) -> bool {
+    sdk >= 33
+}
+
+/// The `AppOpsManager` method checking an op without throwing: `checkOpNoThrow` was renamed to
+/// `unsafeCheckOpNoThrow` in API 29.
+pub fn check_op_method(sdk: i32) -> &'static str {
+    if sdk >= 29 {
+        "unsafeCheckOpNoThrow"
+    } else {
+        "checkOpNoThrow"
+    }
+}
+
+/// Whether `Settings.Panel` exists, API 29. Older devices open the full settings screen.
+pub fn supports_settings_panels(sdk: i32) -> bool {
+    sdk >= 29
+}
+
+/// Whether `Settings.ACTION_APP_NOTIFICATION_SETTINGS` exists, API 26. Older devices open the
+/// app details screen.
+pub fn supports_app_notification_settings(sdk: i32) -> bool {
+    sdk >= 26
+}
+
+/// Whether `Intent.ACTION_APPLICATION_PREFERENCES` exists, API 24. Older devices open the app
+/// details screen.
+pub fn supports_app_preferences(sdk: i32) -> bool {
+    sdk >= 24
+}
+
+/// Whether installing packages needs the per-app "install unknown apps" access, API 26. Before,
+/// one global setting decides.
+pub fn restricts_package_installs(sdk: i32) -> bool {
+    sdk >= 26
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_flags_the_device_lacks() {
+        assert!(supports_flag(21, "ACTIVITY_NEW_TASK"));
+        assert!(!supports_flag(23, "ACTIVITY_LAUNCH_ADJACENT"));
+        assert!(supports_flag(24, "ACTIVITY_LAUNCH_ADJACENT"));
+        assert!(!supports_flag(29, "ACTIVITY_REQUIRE_DEFAULT"));
+    }
+
+    #[test]
+    fn states_pending_intent_mutability_when_required() {
+        assert_eq!(pending_intent_flags(22, false), 0);
+        assert_eq!(pending_intent_flags(23, false), PENDING_INTENT_FLAG_IMMUTABLE);
+        assert_eq!(pending_intent_flags(30, true), 0);
+        assert_eq!(pending_intent_flags(31, true), PENDING_INTENT_FLAG_MUTABLE);
+    }
+
+    #[test]
+    fn exports_receivers_explicitly_from_api_33() {
+        assert_eq!(receiver_flags(32, false), 0);
+        assert_eq!(receiver_flags(33, true), RECEIVER_EXPORTED);
+        assert_eq!(receiver_flags(33, false), RECEIVER_NOT_EXPORTED);
+    }
+
+    fn assert_introduced_in(supports: fn(i32) -> bool, api: i32) {
+        assert!(!supports(api - 1));
+        assert!(supports(api));
+    }
+
+    #[test]
+    fn falls_back_below_the_introducing_api_level() {
+        assert_introduced_in(supports_remove_flags, 26);
+        assert_introduced_in(supports_photo_picker, 33);
+        assert_introduced_in(supports_html_flags, 24);
+        assert_introduced_in(supports_intent_identifier, 29);
+        assert_introduced_in(supports_typed_extras, 33);
+        assert_introduced_in(supports_settings_panels, 29);
+        assert_introduced_in(supports_app_notification_settings, 26);
+        assert_introduced_in(supports_app_preferences, 24);
+        assert_introduced_in(restricts_package_installs, 26);
+    }
+
+    #[test]
+    fn picks_the_check_op_method() {
+        assert_eq!(check_op_method(28), "checkOpNoThrow");
+        assert_eq!(check_op_method(29), "unsafeCheckOpNoThrow");
+    }
+}
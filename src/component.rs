@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// An `android.content.ComponentName`: the package of an app and the class of one of its
+/// activities, services or receivers.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComponentName {
+    pub package: String,
+    /// Fully qualified class name.
+    pub class: String,
+}
+
+impl ComponentName {
+    pub fn new(package: impl Into<String>, class: impl Into<String>) -> Self {
+        Self { package: package.into(), class: class.into() }
+    }
+
+    /// Parse the flattened `package/class` form, where `class` may start with `.` to be relative
+    /// to `package`, as `ComponentName.unflattenFromString` does.
+    pub fn unflatten(flattened: &str) -> Option<Self> {
+        let (package, class) = flattened.split_once('/')?;
+        let class = match class.strip_prefix('.') {
+            Some(_) => format!("{}{}", package, class),
+            None => class.to_owned(),
+        };
+
+        Some(Self::new(package, class))
+    }
+
+    /// The flattened form, with the class relative to the package where possible, as
+    /// `ComponentName.flattenToShortString` does.
+    pub fn flatten_to_short_string(&self) -> String {
+        match self.class.strip_prefix(self.package.as_str()) {
+            Some(relative) if relative.starts_with('.') => format!("{}/{}", self.package, relative),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// The flattened `package/class` form.
+impl fmt::Display for ComponentName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.package, self.class)
+    }
+}
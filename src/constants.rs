@@ -0,0 +1,72 @@
+//! Literal values of the framework constants the crate refers to by field name, so launches
+//! don't need a JNI static field lookup for each of them, and the enums are checked against
+//! them at compile time.
+//!
+//! The table is vendored in `constants_table.rs`, or generated from `android.jar` by `build.rs`
+//! when `ANDROID_INTENT_ANDROID_JAR` is set. Names missing from it are still looked up over JNI.
+
+#[cfg(not(android_intent_generated_constants))]
+include!("constants_table.rs");
+#[cfg(android_intent_generated_constants)]
+include!(concat!(env!("OUT_DIR"), "/constants_table.rs"));
+
+/// The value of the `String` constant `field` of `class` (in JNI notation).
+pub(crate) const fn string(class: &str, field: &str) -> Option<&'static str> {
+    let mut i = 0;
+    while i < STRINGS.len() {
+        let (entry_class, entry_field, value) = STRINGS[i];
+        if str_eq(entry_class, class) && str_eq(entry_field, field) {
+            return Some(value);
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// The value of the `int` constant `field` of `class` (in JNI notation).
+pub(crate) const fn int(class: &str, field: &str) -> Option<i32> {
+    let mut i = 0;
+    while i < INTS.len() {
+        let (entry_class, entry_field, value) = INTS[i];
+        if str_eq(entry_class, class) && str_eq(entry_field, field) {
+            return Some(value);
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Like [`string`], for names that must exist; in a `const`, a typo fails the build.
+pub(crate) const fn expect_string(class: &str, field: &str) -> &'static str {
+    match string(class, field) {
+        Some(value) => value,
+        None => panic!("unknown framework constant"),
+    }
+}
+
+/// Like [`int`], for names that must exist; in a `const`, a typo fails the build.
+pub(crate) const fn expect_int(class: &str, field: &str) -> i32 {
+    match int(class, field) {
+        Some(value) => value,
+        None => panic!("unknown framework constant"),
+    }
+}
+
+pub(crate) const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
@@ -0,0 +1,93 @@
+// Vendored from android.jar (API 34) and curated by hand: the subset of classes and constants
+// the crate and its enums refer to. Keep entries sorted when adding to it.
+//
+// To regenerate the full table, build with ANDROID_INTENT_ANDROID_JAR pointing at an android.jar
+// and `javap` on the PATH. build.rs then writes every constant of its classes to
+// `$OUT_DIR/constants_table.rs` and builds against that instead of this file; copy the entries
+// needed from there.
+
+pub(crate) const STRINGS: &[(&str, &str, &str)] = &[
+    ("android/app/admin/DevicePolicyManager", "ACTION_ADD_DEVICE_ADMIN", "android.app.action.ADD_DEVICE_ADMIN"),
+    ("android/bluetooth/BluetoothAdapter", "ACTION_REQUEST_DISCOVERABLE", "android.bluetooth.adapter.action.REQUEST_DISCOVERABLE"),
+    ("android/bluetooth/BluetoothAdapter", "ACTION_REQUEST_ENABLE", "android.bluetooth.adapter.action.REQUEST_ENABLE"),
+    ("android/content/Intent", "ACTION_APPLICATION_PREFERENCES", "android.intent.action.APPLICATION_PREFERENCES"),
+    ("android/content/Intent", "ACTION_ASSIST", "android.intent.action.ASSIST"),
+    ("android/content/Intent", "ACTION_CALL", "android.intent.action.CALL"),
+    ("android/content/Intent", "ACTION_CHOOSER", "android.intent.action.CHOOSER"),
+    ("android/content/Intent", "ACTION_CREATE_DOCUMENT", "android.intent.action.CREATE_DOCUMENT"),
+    ("android/content/Intent", "ACTION_DIAL", "android.intent.action.DIAL"),
+    ("android/content/Intent", "ACTION_EDIT", "android.intent.action.EDIT"),
+    ("android/content/Intent", "ACTION_GET_CONTENT", "android.intent.action.GET_CONTENT"),
+    ("android/content/Intent", "ACTION_MAIN", "android.intent.action.MAIN"),
+    ("android/content/Intent", "ACTION_OPEN_DOCUMENT", "android.intent.action.OPEN_DOCUMENT"),
+    ("android/content/Intent", "ACTION_OPEN_DOCUMENT_TREE", "android.intent.action.OPEN_DOCUMENT_TREE"),
+    ("android/content/Intent", "ACTION_PICK", "android.intent.action.PICK"),
+    ("android/content/Intent", "ACTION_PROCESS_TEXT", "android.intent.action.PROCESS_TEXT"),
+    ("android/content/Intent", "ACTION_SEND", "android.intent.action.SEND"),
+    ("android/content/Intent", "ACTION_SENDTO", "android.intent.action.SENDTO"),
+    ("android/content/Intent", "ACTION_SEND_MULTIPLE", "android.intent.action.SEND_MULTIPLE"),
+    ("android/content/Intent", "ACTION_SET_WALLPAPER", "android.intent.action.SET_WALLPAPER"),
+    ("android/content/Intent", "ACTION_UNINSTALL_PACKAGE", "android.intent.action.UNINSTALL_PACKAGE"),
+    ("android/content/Intent", "ACTION_VIEW", "android.intent.action.VIEW"),
+    ("android/content/Intent", "ACTION_VOICE_COMMAND", "android.intent.action.VOICE_COMMAND"),
+    ("android/content/Intent", "ACTION_WEB_SEARCH", "android.intent.action.WEB_SEARCH"),
+    ("android/content/Intent", "CATEGORY_APP_BROWSER", "android.intent.category.APP_BROWSER"),
+    ("android/content/Intent", "CATEGORY_APP_MUSIC", "android.intent.category.APP_MUSIC"),
+    ("android/content/Intent", "CATEGORY_BROWSABLE", "android.intent.category.BROWSABLE"),
+    ("android/content/Intent", "CATEGORY_DEFAULT", "android.intent.category.DEFAULT"),
+    ("android/content/Intent", "CATEGORY_HOME", "android.intent.category.HOME"),
+    ("android/content/Intent", "CATEGORY_LAUNCHER", "android.intent.category.LAUNCHER"),
+    ("android/content/Intent", "CATEGORY_OPENABLE", "android.intent.category.OPENABLE"),
+    ("android/content/Intent", "EXTRA_ALLOW_MULTIPLE", "android.intent.extra.ALLOW_MULTIPLE"),
+    ("android/content/Intent", "EXTRA_BCC", "android.intent.extra.BCC"),
+    ("android/content/Intent", "EXTRA_CC", "android.intent.extra.CC"),
+    ("android/content/Intent", "EXTRA_CHOSEN_COMPONENT", "android.intent.extra.CHOSEN_COMPONENT"),
+    ("android/content/Intent", "EXTRA_EMAIL", "android.intent.extra.EMAIL"),
+    ("android/content/Intent", "EXTRA_HTML_TEXT", "android.intent.extra.HTML_TEXT"),
+    ("android/content/Intent", "EXTRA_INTENT", "android.intent.extra.INTENT"),
+    ("android/content/Intent", "EXTRA_LOCAL_ONLY", "android.intent.extra.LOCAL_ONLY"),
+    ("android/content/Intent", "EXTRA_MIME_TYPES", "android.intent.extra.MIME_TYPES"),
+    ("android/content/Intent", "EXTRA_PROCESS_TEXT", "android.intent.extra.PROCESS_TEXT"),
+    ("android/content/Intent", "EXTRA_PROCESS_TEXT_READONLY", "android.intent.extra.PROCESS_TEXT_READONLY"),
+    ("android/content/Intent", "EXTRA_REFERRER", "android.intent.extra.REFERRER"),
+    ("android/content/Intent", "EXTRA_RETURN_RESULT", "android.intent.extra.RETURN_RESULT"),
+    ("android/content/Intent", "EXTRA_STREAM", "android.intent.extra.STREAM"),
+    ("android/content/Intent", "EXTRA_SUBJECT", "android.intent.extra.SUBJECT"),
+    ("android/content/Intent", "EXTRA_TEXT", "android.intent.extra.TEXT"),
+    ("android/content/Intent", "EXTRA_TITLE", "android.intent.extra.TITLE"),
+    ("android/media/RingtoneManager", "ACTION_RINGTONE_PICKER", "android.intent.action.RINGTONE_PICKER"),
+    ("android/provider/MediaStore", "ACTION_PICK_IMAGES", "android.provider.action.PICK_IMAGES"),
+    ("android/provider/Settings", "ACTION_ACCESSIBILITY_SETTINGS", "android.settings.ACCESSIBILITY_SETTINGS"),
+    ("android/provider/Settings", "ACTION_APPLICATION_DETAILS_SETTINGS", "android.settings.APPLICATION_DETAILS_SETTINGS"),
+    ("android/provider/Settings", "ACTION_APP_NOTIFICATION_SETTINGS", "android.settings.APP_NOTIFICATION_SETTINGS"),
+    ("android/provider/Settings", "ACTION_APP_OPEN_BY_DEFAULT_SETTINGS", "android.settings.APP_OPEN_BY_DEFAULT_SETTINGS"),
+    ("android/provider/Settings", "ACTION_MANAGE_APP_USE_FULL_SCREEN_INTENT", "android.settings.MANAGE_APP_USE_FULL_SCREEN_INTENT"),
+    ("android/provider/Settings", "ACTION_MANAGE_CROSS_PROFILE_ACCESS", "android.settings.MANAGE_CROSS_PROFILE_ACCESS"),
+    ("android/provider/Settings", "ACTION_MANAGE_UNKNOWN_APP_SOURCES", "android.settings.MANAGE_UNKNOWN_APP_SOURCES"),
+    ("android/provider/Settings", "ACTION_NFC_SETTINGS", "android.settings.NFC_SETTINGS"),
+    ("android/provider/Settings", "ACTION_REQUEST_IGNORE_BATTERY_OPTIMIZATIONS", "android.settings.REQUEST_IGNORE_BATTERY_OPTIMIZATIONS"),
+    ("android/provider/Settings", "ACTION_REQUEST_SCHEDULE_EXACT_ALARM", "android.settings.REQUEST_SCHEDULE_EXACT_ALARM"),
+    ("android/provider/Settings", "ACTION_SOUND_SETTINGS", "android.settings.SOUND_SETTINGS"),
+    ("android/provider/Settings", "ACTION_USAGE_ACCESS_SETTINGS", "android.settings.USAGE_ACCESS_SETTINGS"),
+    ("android/provider/Settings", "ACTION_WIFI_SETTINGS", "android.settings.WIFI_SETTINGS"),
+    ("android/provider/Settings", "ACTION_WIRELESS_SETTINGS", "android.settings.WIRELESS_SETTINGS"),
+    ("android/provider/Settings$Panel", "ACTION_INTERNET_CONNECTIVITY", "android.settings.panel.action.INTERNET_CONNECTIVITY"),
+    ("android/provider/Settings$Panel", "ACTION_NFC", "android.settings.panel.action.NFC"),
+    ("android/provider/Settings$Panel", "ACTION_VOLUME", "android.settings.panel.action.VOLUME"),
+    ("android/provider/Settings$Panel", "ACTION_WIFI", "android.settings.panel.action.WIFI"),
+];
+
+pub(crate) const INTS: &[(&str, &str, i32)] = &[
+    ("android/content/Intent", "FLAG_ACTIVITY_CLEAR_TASK", 32768),
+    ("android/content/Intent", "FLAG_ACTIVITY_CLEAR_TOP", 67108864),
+    ("android/content/Intent", "FLAG_ACTIVITY_EXCLUDE_FROM_RECENTS", 8388608),
+    ("android/content/Intent", "FLAG_ACTIVITY_LAUNCH_ADJACENT", 4096),
+    ("android/content/Intent", "FLAG_ACTIVITY_MULTIPLE_TASK", 134217728),
+    ("android/content/Intent", "FLAG_ACTIVITY_NEW_DOCUMENT", 524288),
+    ("android/content/Intent", "FLAG_ACTIVITY_NEW_TASK", 268435456),
+    ("android/content/Intent", "FLAG_ACTIVITY_NO_HISTORY", 1073741824),
+    ("android/content/Intent", "FLAG_ACTIVITY_REORDER_TO_FRONT", 131072),
+    ("android/content/Intent", "FLAG_ACTIVITY_SINGLE_TOP", 536870912),
+    ("android/content/Intent", "FLAG_GRANT_READ_URI_PERMISSION", 1),
+    ("android/content/Intent", "FLAG_GRANT_WRITE_URI_PERMISSION", 2),
+];
@@ -0,0 +1,313 @@
+//! Direct access to `content:` URIs from Rust.
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::os::fd::{FromRawFd, OwnedFd};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jni::objects::JObject;
+use jni::{AttachGuard, JNIEnv};
+
+use crate::backend::{IntentBackend, Inner};
+use crate::trace::{self, debug, Traced};
+use crate::{backend, bridge, bundle, context, recipes, Action, Error, ExtraValue, Flags, Intent, Uri};
+
+const EXTRA_STREAM: &str = "android.intent.extra.STREAM";
+
+/// Subdirectory of the cache directory that spilled extras are written to.
+pub const SPILL_DIR: &str = "android-intent-spill";
+/// Appended to the key of a spilled extra to record its original type, `string` or `bytes`.
+const SPILLED_SUFFIX: &str = ".android_intent.SPILLED";
+/// Spilled files older than this are deleted on the next spill.
+const SPILL_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+static NEXT_SPILL: AtomicU32 = AtomicU32::new(0);
+
+/// Open `uri` through the app's `ContentResolver` and take over its file descriptor, e.g. to mmap
+/// it or hand it to a native decoder without copying through Java streams.
+///
+/// `mode` is the `ContentResolver.openFileDescriptor` mode: `"r"`, `"w"`, `"wt"`, `"wa"`, `"rw"`
+/// or `"rwt"`.
+/// ```no_run
+/// use std::fs::File;
+/// use std::io::Read;
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// let fd = android_intent::content::open_fd(&mut intent_env.get_env(), "content://com.example/doc/1", "r").unwrap();
+/// let mut bytes = Vec::new();
+/// File::from(fd).read_to_end(&mut bytes).unwrap();
+/// ```
+pub fn open_fd(env: &mut JNIEnv, uri: &str, mode: &str) -> Result<OwnedFd, Error> {
+    trace::checked(env, |env| {
+        let resolver = context::content_resolver(env)?;
+        let uri = backend::parse_uri(env, uri)?;
+        let mode = env.new_string(mode)?;
+
+        let pfd = env
+            .call(
+                &resolver,
+                "openFileDescriptor",
+                "(Landroid/net/Uri;Ljava/lang/String;)Landroid/os/ParcelFileDescriptor;",
+                &[(&uri).into(), (&mode).into()],
+            )?
+            .l()?;
+        if pfd.is_null() {
+            return Err(jni::errors::Error::NullPtr("openFileDescriptor").into());
+        }
+
+        detach_fd(env, &pfd)
+    })
+}
+
+/// Share data generated by Rust without a temporary file: the receiver reads `name` from a pipe
+/// whose write end is returned. Write to it on another thread, since writes block until the
+/// receiver reads, and drop it to signal the end of the data.
+///
+/// The read end is served by the generated [`RustShareProvider`](crate::bridge::Bridge::share_provider_source),
+/// declared in the manifest under `authority`. Each pipe can be opened once.
+/// ```no_run
+/// use std::io::Write;
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// let (_intent, mut file) =
+///     android_intent::content::share_pipe(intent_env.get_env(), "com.example.app.share", "report.csv", "text/csv")
+///         .unwrap();
+/// std::thread::spawn(move || file.write_all(b"day,steps\nmonday,8000\n"));
+/// ```
+pub fn share_pipe<'env>(
+    mut env: AttachGuard<'env>,
+    authority: &str,
+    name: &str,
+    mime_type: &str,
+) -> Result<(Intent<'env>, File), Error> {
+    let pipe = env
+        .call_static("android/os/ParcelFileDescriptor", "createPipe", "()[Landroid/os/ParcelFileDescriptor;", &[])?
+        .l()?;
+    let pipe = bundle::object_array(&mut env, pipe.into())?;
+    let (read, write) = (&pipe[0], &pipe[1]);
+
+    let extras = mime_type_extras(&mut env, mime_type)?;
+    let fd_key = env.new_string(bridge::FD_KEY)?;
+    env.call(
+        &extras,
+        "putParcelable",
+        "(Ljava/lang/String;Landroid/os/Parcelable;)V",
+        &[(&fd_key).into(), read.into()],
+    )?;
+    call_provider(&mut env, authority, bridge::REGISTER_PIPE, name, &extras)?;
+    // The provider now owns the read end; it is passed to it in-process, so closing it here
+    // would close the provider's copy too.
+
+    let file = File::from(detach_fd(&mut env, write)?);
+    let intent = share_stream(env, authority, name, mime_type)?;
+
+    Ok((intent, file))
+}
+
+/// Serve `bytes` as `content://<authority>/<name>` from the generated
+/// [`RustShareProvider`](crate::bridge::Bridge::share_provider_source), until [`unregister`]ed.
+/// Unlike a pipe, the URI can be opened any number of times, and reports its size.
+pub fn register_bytes(env: &mut JNIEnv, authority: &str, name: &str, mime_type: &str, bytes: &[u8]) -> Result<Uri, Error> {
+    trace::checked(env, |env| {
+        let extras = mime_type_extras(env, mime_type)?;
+        let bytes_key = env.new_string(bridge::BYTES_KEY)?;
+        let jbytes = env.byte_array_from_slice(bytes)?;
+        env.call(
+            &extras,
+            "putByteArray",
+            "(Ljava/lang/String;[B)V",
+            &[(&bytes_key).into(), (&jbytes).into()],
+        )?;
+        call_provider(env, authority, bridge::REGISTER_BYTES, name, &extras)?;
+
+        Ok(Uri::from(format!("content://{}/{}", authority, name)))
+    })
+}
+
+/// Stop serving `name`, registered with [`register_bytes`] or [`share_pipe`]. Receivers that
+/// already opened it keep reading.
+pub fn unregister(env: &mut JNIEnv, authority: &str, name: &str) -> Result<(), Error> {
+    trace::checked(env, |env| {
+        call_provider(env, authority, bridge::UNREGISTER, name, &JObject::null())
+    })
+}
+
+/// Share `bytes` generated by Rust, e.g. a rendered image, through the chooser. They stay
+/// registered so the receiver can read them more than once; [`unregister`] them once done.
+/// ```no_run
+/// # let intent_env = android_intent::IntentEnv::new();
+/// # let png: Vec<u8> = Vec::new();
+/// android_intent::content::share_bytes(intent_env.get_env(), "com.example.app.share", "chart.png", "image/png", png)
+///     .unwrap();
+/// ```
+pub fn share_bytes<'env>(
+    mut env: AttachGuard<'env>,
+    authority: &str,
+    name: &str,
+    mime_type: &str,
+    bytes: Vec<u8>,
+) -> Result<Intent<'env>, Error> {
+    register_bytes(&mut env, authority, name, mime_type, &bytes)?;
+
+    share_stream(env, authority, name, mime_type)
+}
+
+/// Offer `content://<authority>/<name>` through an `ACTION_SEND` chooser.
+fn share_stream<'env>(env: AttachGuard<'env>, authority: &str, name: &str, mime_type: &str) -> Result<Intent<'env>, Error> {
+    Intent::new(env, Action::Send)
+        .with_type(mime_type)
+        .with_extra_uri(EXTRA_STREAM, format!("content://{}/{}", authority, name))
+        .grant_read()
+        .into_chooser()
+        .start_activity()
+}
+
+/// A `Bundle` holding `mime_type`, for the provider to report from `getType`.
+fn mime_type_extras<'a>(env: &mut JNIEnv<'a>, mime_type: &str) -> Result<JObject<'a>, Error> {
+    let extras = env.new_object("android/os/Bundle", "()V", &[])?;
+    let mime_type_key = env.new_string(bridge::MIME_TYPE_KEY)?;
+    let jmime_type = env.new_string(mime_type)?;
+    env.call(
+        &extras,
+        "putString",
+        "(Ljava/lang/String;Ljava/lang/String;)V",
+        &[(&mime_type_key).into(), (&jmime_type).into()],
+    )?;
+
+    Ok(extras)
+}
+
+/// Invoke `method` on the share provider at `authority` via `ContentResolver.call`.
+fn call_provider(env: &mut JNIEnv, authority: &str, method: &str, name: &str, extras: &JObject) -> Result<(), Error> {
+    let resolver = context::content_resolver(env)?;
+    let provider = backend::parse_uri(env, &format!("content://{}", authority))?;
+    let method = env.new_string(method)?;
+    let jname = env.new_string(name)?;
+    env.call(
+        &resolver,
+        "call",
+        "(Landroid/net/Uri;Ljava/lang/String;Ljava/lang/String;Landroid/os/Bundle;)Landroid/os/Bundle;",
+        &[(&provider).into(), (&method).into(), (&jname).into(), extras.into()],
+    )?;
+
+    Ok(())
+}
+
+/// Take over the descriptor of the `ParcelFileDescriptor` `pfd`; `detachFd` hands its ownership
+/// over, so closing `pfd` no longer closes it.
+fn detach_fd(env: &mut JNIEnv, pfd: &JObject) -> Result<OwnedFd, Error> {
+    let fd = env.call(pfd, "detachFd", "()I", &[])?.i()?;
+
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Move `String` and `byte[]` extras longer than `threshold` bytes into files under the cache
+/// directory, replacing each with its `FileProvider` URI, readable by the receiver; see
+/// [`Intent::spill_large_extras`].
+pub(crate) fn spill_extras(inner: &mut Inner, authority: &str, threshold: usize) -> Result<(), Error> {
+    let large: Vec<_> = inner
+        .extras()?
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            ExtraValue::Str(text) if text.len() > threshold => Some((key, "string", text.into_bytes())),
+            ExtraValue::ByteArray(bytes) if bytes.len() > threshold => Some((key, "bytes", bytes)),
+            _ => None,
+        })
+        .collect();
+    if large.is_empty() {
+        return Ok(());
+    }
+    let dir = spill_dir(&mut inner.env)?;
+    prepare_spill_dir(&dir)?;
+
+    let mut uris = Vec::new();
+    for (key, kind, bytes) in large {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let path = format!("{}/{}-{}", dir, millis, NEXT_SPILL.fetch_add(1, Ordering::Relaxed));
+        fs::write(&path, &bytes)?;
+        let uri = recipes::file_provider_uri(&mut inner.env, &path, authority)?;
+        debug!("spilled {} bytes of extra {} to {}", bytes.len(), key, uri);
+
+        inner.put_extra(&key, &ExtraValue::Uri(uri.clone().into()))?;
+        inner.put_extra(&format!("{}{}", key, SPILLED_SUFFIX), &ExtraValue::Str(kind.to_owned()))?;
+        uris.push(uri);
+    }
+
+    // URI grants only cover the data and the clip data, not extras.
+    add_clip_uris(inner, &uris)?;
+    inner.add_flags(Flags::GRANT_READ_URI_PERMISSION)
+}
+
+/// The extra `key`, read back from its file if the sender spilled it with
+/// [`Intent::spill_large_extras`]; see [`Intent::get_spilled_extra`].
+pub(crate) fn read_spilled(inner: &mut dyn IntentBackend, key: &str) -> Result<Option<ExtraValue>, Error> {
+    let value = inner.extra(key)?;
+    let kind = inner.extra(&format!("{}{}", key, SPILLED_SUFFIX))?;
+    let (Some(ExtraValue::Uri(uri)), Some(ExtraValue::Str(kind))) = (&value, kind) else {
+        return Ok(value);
+    };
+    let Some(jni) = inner.jni() else {
+        return Err(Error::Unsupported("get_spilled_extra"));
+    };
+
+    let mut bytes = Vec::new();
+    File::from(open_fd(&mut jni.env, uri.as_str(), "r")?).read_to_end(&mut bytes)?;
+
+    Ok(Some(match kind.as_str() {
+        "string" => ExtraValue::Str(String::from_utf8_lossy(&bytes).into_owned()),
+        _ => ExtraValue::ByteArray(bytes),
+    }))
+}
+
+fn spill_dir(env: &mut JNIEnv) -> Result<String, Error> {
+    let activity = context::activity(env)?;
+    let cache_dir = env
+        .call(&activity, "getCacheDir", "()Ljava/io/File;", &[])?
+        .l()?;
+    let cache_dir = backend::string_getter(env, &cache_dir, "getAbsolutePath")?.unwrap_or_default();
+
+    Ok(format!("{}/{}", cache_dir, SPILL_DIR))
+}
+
+/// Create `dir`, and delete files spilled long enough ago that their receivers are done.
+fn prepare_spill_dir(dir: &str) -> Result<(), Error> {
+    fs::create_dir_all(dir)?;
+
+    for entry in fs::read_dir(dir)?.flatten() {
+        let modified = entry.metadata().and_then(|metadata| metadata.modified());
+        let age = modified.ok().and_then(|modified| modified.elapsed().ok());
+        if age.is_some_and(|age| age > SPILL_MAX_AGE) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+
+    Ok(())
+}
+
+/// Add `uris` to the intent's `ClipData`, creating it if needed.
+fn add_clip_uris(inner: &mut Inner, uris: &[String]) -> Result<(), Error> {
+    let env = &mut inner.env;
+    let mut clip = env.call(&inner.object, "getClipData", "()Landroid/content/ClipData;", &[])?.l()?;
+
+    for uri in uris {
+        let uri = backend::parse_uri(env, uri)?;
+        if clip.is_null() {
+            let label = env.new_string("")?;
+            clip = env
+                .call_static(
+                    "android/content/ClipData",
+                    "newRawUri",
+                    "(Ljava/lang/CharSequence;Landroid/net/Uri;)Landroid/content/ClipData;",
+                    &[(&label).into(), (&uri).into()],
+                )?
+                .l()?;
+        } else {
+            let item = env.new_object("android/content/ClipData$Item", "(Landroid/net/Uri;)V", &[(&uri).into()])?;
+            env.call(&clip, "addItem", "(Landroid/content/ClipData$Item;)V", &[(&item).into()])?;
+        }
+    }
+    env.call(&inner.object, "setClipData", "(Landroid/content/ClipData;)V", &[(&clip).into()])?;
+
+    Ok(())
+}
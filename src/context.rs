@@ -0,0 +1,120 @@
+use std::sync::Mutex;
+
+use jni::{errors::Error, objects::{JClass, JObject, WeakRef}, JNIEnv, JavaVM};
+
+use crate::backend;
+use crate::trace::Traced;
+
+/// Weak reference to the activity, taken from [`ndk_context`] on first use and by [`refresh`].
+static ACTIVITY: Mutex<Option<WeakRef>> = Mutex::new(None);
+
+/// The `android.content.Context` (normally the Activity) provided by [`ndk_context`].
+fn android_context<'a>() -> JObject<'a> {
+    let cx = ndk_context::android_context();
+    unsafe { JObject::from_raw(cx.context() as jni::sys::jobject) }
+}
+
+/// The activity, through a weak reference so a destroyed one isn't kept alive, or
+/// [`NullContext`](crate::Error::NullContext) if there is none or it has been collected.
+pub(crate) fn activity<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>, crate::Error> {
+    let mut activity = ACTIVITY.lock().unwrap_or_else(|err| err.into_inner());
+    if activity.is_none() {
+        *activity = weak_context(env)?;
+    }
+
+    match activity.as_ref() {
+        Some(weak) => weak.upgrade_local(env)?.ok_or(crate::Error::NullContext),
+        None => Err(crate::Error::NullContext),
+    }
+}
+
+/// Take a new weak reference to the context [`ndk_context`] holds now, e.g. after the activity
+/// was recreated.
+pub(crate) fn refresh(env: &mut JNIEnv) -> Result<(), crate::Error> {
+    let weak = weak_context(env)?;
+    let found = weak.is_some();
+    *ACTIVITY.lock().unwrap_or_else(|err| err.into_inner()) = weak;
+
+    if found {
+        Ok(())
+    } else {
+        Err(crate::Error::NullContext)
+    }
+}
+
+fn weak_context(env: &mut JNIEnv) -> Result<Option<WeakRef>, Error> {
+    let context = android_context();
+    if context.is_null() {
+        return Ok(None);
+    }
+
+    env.new_weak_ref(context)
+}
+
+/// The `JavaVM` provided by [`ndk_context`].
+pub(crate) fn java_vm() -> Result<JavaVM, Error> {
+    let cx = ndk_context::android_context();
+    unsafe { JavaVM::from_raw(cx.vm().cast()) }
+}
+
+/// Name of the package this app runs as, via `Context.getPackageName()`.
+pub(crate) fn package_name(env: &mut JNIEnv) -> Result<String, crate::Error> {
+    let context = activity(env)?;
+    let jname = env
+        .call(context, "getPackageName", "()Ljava/lang/String;", &[])?
+        .l()?;
+
+    Ok(env.get_string(&jname.into())?.into())
+}
+
+/// A `package:<name>` URI for this app, as expected by most per-app settings screens.
+pub(crate) fn package_uri(env: &mut JNIEnv) -> Result<String, crate::Error> {
+    Ok(format!("package:{}", package_name(env)?))
+}
+
+/// Look up a system service by the name of its `Context.*_SERVICE` constant.
+pub(crate) fn system_service<'a>(env: &mut JNIEnv<'a>, service: &str) -> Result<JObject<'a>, crate::Error> {
+    let name = backend::static_field(env, "android/content/Context", service, "Ljava/lang/String;")?;
+    let context = activity(env)?;
+
+    Ok(env
+        .call(context, "getSystemService", "(Ljava/lang/String;)Ljava/lang/Object;", &[(&name).into()])?
+        .l()?)
+}
+
+/// This app's `PackageManager`, via `Context.getPackageManager()`.
+pub(crate) fn package_manager<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>, crate::Error> {
+    let context = activity(env)?;
+
+    Ok(env.call(context, "getPackageManager", "()Landroid/content/pm/PackageManager;", &[])?.l()?)
+}
+
+/// This app's `ContentResolver`, via `Context.getContentResolver()`.
+pub(crate) fn content_resolver<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>, crate::Error> {
+    let context = activity(env)?;
+
+    Ok(env.call(context, "getContentResolver", "()Landroid/content/ContentResolver;", &[])?.l()?)
+}
+
+/// Load `name` (e.g. `androidx.core.content.FileProvider`) through the app's class loader.
+///
+/// `FindClass` on a native thread only sees framework classes, so this is needed for classes
+/// bundled with the app.
+pub(crate) fn load_class<'a>(env: &mut JNIEnv<'a>, name: &str) -> Result<JClass<'a>, crate::Error> {
+    let context = activity(env)?;
+    let class_loader = env
+        .call(context, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+        .l()?;
+    let name = env.new_string(name)?;
+
+    let class = env
+        .call(
+            class_loader,
+            "loadClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[(&name).into()],
+        )?
+        .l()?;
+
+    Ok(class.into())
+}
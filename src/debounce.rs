@@ -0,0 +1,114 @@
+//! Suppressing repeated launches of the same intent, see
+//! [`Intent::start_activity_debounced`](crate::Intent::start_activity_debounced).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::IntentSpec;
+
+/// What `Intent.filterEquals` compares: everything but the extras and flags.
+#[derive(PartialEq, Eq, Hash)]
+struct FilterKey {
+    action: Option<String>,
+    data: Option<String>,
+    mime_type: Option<String>,
+    package: Option<String>,
+    component: Option<String>,
+    categories: Vec<String>,
+    identifier: Option<String>,
+}
+
+impl FilterKey {
+    fn new(spec: &IntentSpec) -> Self {
+        let mut categories = spec.categories.clone();
+        categories.sort();
+
+        Self {
+            action: spec.action.clone(),
+            data: spec.data.clone(),
+            mime_type: spec.mime_type.clone(),
+            package: spec.package.clone(),
+            component: spec.component.clone(),
+            categories,
+            identifier: spec.identifier.clone(),
+        }
+    }
+}
+
+/// When each recently launched intent may be launched again.
+static QUIET_UNTIL: Mutex<Option<HashMap<FilterKey, Instant>>> = Mutex::new(None);
+
+/// Whether `spec` may launch now, i.e. no filter-equal intent was admitted within its window.
+/// If so, it starts a new `window`.
+pub(crate) fn admit(spec: &IntentSpec, window: Duration) -> bool {
+    let now = Instant::now();
+    let mut quiet_until = QUIET_UNTIL.lock().unwrap();
+    let quiet_until = quiet_until.get_or_insert_with(HashMap::new);
+    quiet_until.retain(|_, until| *until > now);
+
+    let key = FilterKey::new(spec);
+    if quiet_until.contains_key(&key) {
+        return false;
+    }
+    quiet_until.insert(key, now + window);
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(action: &str) -> IntentSpec {
+        IntentSpec { action: Some(action.to_owned()), ..Default::default() }
+    }
+
+    #[test]
+    fn suppresses_filter_equal_launches_within_the_window() {
+        let window = Duration::from_secs(60);
+        assert!(admit(&spec("test.debounce.REPEATED"), window));
+        assert!(!admit(&spec("test.debounce.REPEATED"), window));
+
+        // Extras and flags don't take part in `filterEquals`.
+        let mut with_extras = spec("test.debounce.REPEATED");
+        with_extras.flags = 1;
+        with_extras.extras.insert("key".to_owned(), crate::ExtraValue::Int(1));
+        assert!(!admit(&with_extras, window));
+
+        assert!(admit(&spec("test.debounce.OTHER"), window));
+    }
+
+    #[test]
+    fn categories_compare_regardless_of_order() {
+        let window = Duration::from_secs(60);
+        let mut first = spec("test.debounce.CATEGORIES");
+        first.categories = vec!["a".to_owned(), "b".to_owned()];
+        let mut second = first.clone();
+        second.categories.reverse();
+
+        assert!(admit(&first, window));
+        assert!(!admit(&second, window));
+    }
+
+    #[test]
+    fn admits_again_once_the_window_passed() {
+        let window = Duration::from_millis(20);
+        assert!(admit(&spec("test.debounce.EXPIRED"), window));
+        std::thread::sleep(window * 2);
+        assert!(admit(&spec("test.debounce.EXPIRED"), window));
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn start_activity_debounced_skips_the_repeated_launch() {
+        use crate::Intent;
+
+        let window = Duration::from_secs(60);
+        let first = Intent::mock("ACTION_DEBOUNCED_TEST").start_activity_debounced(window).unwrap();
+        assert_eq!(first.unwrap().mock_backend().unwrap().launches, [None]);
+
+        let second = Intent::mock("ACTION_DEBOUNCED_TEST").start_activity_debounced(window).unwrap();
+        assert!(second.is_none());
+    }
+}
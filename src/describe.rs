@@ -0,0 +1,152 @@
+//! Structured, launch-free summaries of intents.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::{ExtraValue, Flags, IntentSpec};
+
+/// What an intent holds, with its flags decoded to names and its extras typed, from
+/// [`IntentSpec::describe`] or [`Intent::describe`](crate::Intent::describe). For logging,
+/// analytics and golden-file tests.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntentDescription {
+    pub action: Option<String>,
+    pub data: Option<String>,
+    pub mime_type: Option<String>,
+    pub package: Option<String>,
+    pub component: Option<String>,
+    pub categories: Vec<String>,
+    /// Names of the [`Flags`] that are set, e.g. `ACTIVITY_NEW_TASK`.
+    pub flags: Vec<String>,
+    /// Set flag bits that [`Flags`] has no name for.
+    pub unknown_flags: u32,
+    pub extras: BTreeMap<String, DescribedExtra>,
+}
+
+/// An extra with its Java type, see [`ExtraValue::java_type`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescribedExtra {
+    pub java_type: String,
+    pub value: ExtraValue,
+}
+
+impl IntentSpec {
+    /// Summarize the spec, see [`IntentDescription`].
+    /// ```
+    /// use android_intent::{Flags, IntentSpec};
+    ///
+    /// let spec = IntentSpec {
+    ///     action: Some("android.intent.action.VIEW".to_owned()),
+    ///     flags: Flags::ACTIVITY_NEW_TASK.bits() as i32,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(spec.describe().flags, ["ACTIVITY_NEW_TASK"]);
+    /// ```
+    pub fn describe(&self) -> IntentDescription {
+        let flags = Flags::from_bits_retain(self.flags as u32);
+
+        IntentDescription {
+            action: self.action.clone(),
+            data: self.data.clone(),
+            mime_type: self.mime_type.clone(),
+            package: self.package.clone(),
+            component: self.component.clone(),
+            categories: self.categories.clone(),
+            flags: flags.iter_names().map(|(name, _)| name.to_owned()).collect(),
+            unknown_flags: flags.bits() & !Flags::all().bits(),
+            extras: self
+                .extras
+                .iter()
+                .map(|(key, value)| {
+                    let extra = DescribedExtra { java_type: value.java_type().to_owned(), value: value.clone() };
+                    (key.clone(), extra)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A multi-line summary, one field per line.
+impl fmt::Display for IntentDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = [
+            ("action", &self.action),
+            ("data", &self.data),
+            ("type", &self.mime_type),
+            ("package", &self.package),
+            ("component", &self.component),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                writeln!(f, "{}: {}", name, value)?;
+            }
+        }
+        for category in &self.categories {
+            writeln!(f, "category: {}", category)?;
+        }
+        if !self.flags.is_empty() {
+            writeln!(f, "flags: {}", self.flags.join(" | "))?;
+        }
+        if self.unknown_flags != 0 {
+            writeln!(f, "unknown flags: {:#x}", self.unknown_flags)?;
+        }
+        for (key, extra) in &self.extras {
+            writeln!(f, "extra {}: {} = {:?}", key, extra.java_type, extra.value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_flags_and_types_extras() {
+        let spec = IntentSpec {
+            action: Some("android.intent.action.SEND".to_owned()),
+            flags: (Flags::ACTIVITY_NEW_TASK | Flags::GRANT_READ_URI_PERMISSION).bits() as i32 | 0x0000_0004,
+            extras: [("android.intent.extra.TEXT".to_owned(), ExtraValue::Str("hi".to_owned()))].into(),
+            ..Default::default()
+        };
+        let description = spec.describe();
+
+        assert_eq!(description.flags, ["GRANT_READ_URI_PERMISSION", "ACTIVITY_NEW_TASK"]);
+        assert_eq!(description.unknown_flags, 0x4);
+        assert_eq!(description.extras["android.intent.extra.TEXT"].java_type, "String");
+    }
+
+    #[test]
+    fn display_lists_the_fields_that_are_set() {
+        let spec = IntentSpec {
+            action: Some("android.intent.action.VIEW".to_owned()),
+            categories: vec!["android.intent.category.BROWSABLE".to_owned()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            spec.describe().to_string(),
+            "action: android.intent.action.VIEW\ncategory: android.intent.category.BROWSABLE\n"
+        );
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn describes_mock_intents() {
+        use crate::{Action, Extra, Intent};
+
+        let mut intent = Intent::mock(Action::Send)
+            .with_type("text/plain")
+            .with_extra(Extra::Text, "Hello")
+            .add_flags(Flags::ACTIVITY_CLEAR_TOP);
+        let description = intent.describe().unwrap();
+
+        assert_eq!(description.action.as_deref(), Some("android.intent.action.SEND"));
+        assert_eq!(description.mime_type.as_deref(), Some("text/plain"));
+        assert_eq!(description.flags, ["ACTIVITY_CLEAR_TOP"]);
+        assert_eq!(description.extras["android.intent.extra.TEXT"].value, ExtraValue::Str("Hello".to_owned()));
+    }
+}
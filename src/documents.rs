@@ -0,0 +1,350 @@
+//! Storage Access Framework documents: operations on the `content:` URIs returned by document
+//! pickers, via `DocumentsContract`.
+//!
+//! Each operation checks the document's [`DocumentFlags`] first and fails with
+//! [`Error::DocumentUnsupported`] if its provider doesn't support it.
+//! ```no_run
+//! use android_intent::documents::{self, DocumentFlags};
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! let mut env = intent_env.get_env();
+//! let uri = "content://com.android.externalstorage.documents/tree/primary%3ANotes/document/primary%3ANotes%2Fa.txt";
+//! if documents::flags(&mut env, uri).unwrap().contains(DocumentFlags::SUPPORTS_RENAME) {
+//!     let renamed = documents::rename(&mut env, uri, "b.txt").unwrap();
+//! }
+//! ```
+
+use bitflags::bitflags;
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::trace::{self, Traced};
+use crate::{backend, bundle, context, ApiLevel, Error, Uri};
+
+const DOCUMENTS_CONTRACT_CLASS: &str = "android/provider/DocumentsContract";
+const COLUMN_FLAGS: &str = "flags";
+const CHILD_COLUMNS: [&str; 5] = ["document_id", "_display_name", "mime_type", "_size", "last_modified"];
+const MIME_TYPE_DIR: &str = "vnd.android.document/directory";
+
+bitflags! {
+    /// `DocumentsContract.Document.FLAG_*` capabilities of a document. Each flag's bits equal the
+    /// value of the Java constant of the same name.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct DocumentFlags: u32 {
+        const SUPPORTS_WRITE = 0x0000_0002;
+        const SUPPORTS_DELETE = 0x0000_0004;
+        const DIR_SUPPORTS_CREATE = 0x0000_0008;
+        const SUPPORTS_RENAME = 0x0000_0040;
+        const SUPPORTS_COPY = 0x0000_0080;
+        const SUPPORTS_MOVE = 0x0000_0100;
+        const SUPPORTS_REMOVE = 0x0000_0400;
+    }
+}
+
+/// Capabilities of the document at `uri`, from its `COLUMN_FLAGS`.
+pub fn flags(env: &mut JNIEnv, uri: &str) -> Result<DocumentFlags, Error> {
+    trace::checked(env, |env| {
+        let juri = backend::parse_uri(env, uri)?;
+        let cursor = query(env, &juri, &[COLUMN_FLAGS])?;
+        if cursor.is_null() {
+            return Ok(DocumentFlags::empty());
+        }
+
+        let flags = read_flags(env, &cursor);
+        env.call(&cursor, "close", "()V", &[])?;
+
+        Ok(DocumentFlags::from_bits_retain(flags? as u32))
+    })
+}
+
+/// `ContentResolver.query` of `columns` of all rows at `uri`. The cursor is null if the provider
+/// is gone.
+fn query<'a>(env: &mut JNIEnv<'a>, uri: &JObject, columns: &[&str]) -> Result<JObject<'a>, Error> {
+    let resolver = context::content_resolver(env)?;
+    let projection = env.new_object_array(columns.len() as i32, "java/lang/String", JObject::null())?;
+    for (i, column) in columns.iter().enumerate() {
+        let column = env.new_string(column)?;
+        env.set_object_array_element(&projection, i as i32, column)?;
+    }
+
+    let null = JObject::null();
+    let cursor = env
+        .call(
+            &resolver,
+            "query",
+            "(Landroid/net/Uri;[Ljava/lang/String;Ljava/lang/String;[Ljava/lang/String;Ljava/lang/String;)\
+             Landroid/database/Cursor;",
+            &[uri.into(), (&projection).into(), (&null).into(), (&null).into(), (&null).into()],
+        )?
+        .l()?;
+
+    Ok(cursor)
+}
+
+fn read_flags(env: &mut JNIEnv, cursor: &JObject) -> Result<i32, Error> {
+    if !env.call(cursor, "moveToFirst", "()Z", &[])?.z()? {
+        return Ok(0);
+    }
+
+    Ok(env.call(cursor, "getInt", "(I)I", &[0.into()])?.i()?)
+}
+
+/// A document listed by [`children`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocEntry {
+    pub document_id: String,
+    /// The document's URI within the tree, for the other functions of this module.
+    pub uri: Uri,
+    pub name: String,
+    pub mime_type: String,
+    /// Size in bytes, if known.
+    pub size: Option<i64>,
+    /// Last modification in milliseconds since the epoch, if known.
+    pub last_modified: Option<i64>,
+}
+
+impl DocEntry {
+    pub fn is_directory(&self) -> bool {
+        self.mime_type == MIME_TYPE_DIR
+    }
+}
+
+/// The documents in the directory `tree_uri`, which is a tree URI from
+/// `ACTION_OPEN_DOCUMENT_TREE` or the [`DocEntry::uri`] of a subdirectory within one.
+///
+/// Rows are read from the provider's cursor as the iterator advances, and the cursor is closed
+/// when it is dropped.
+/// ```no_run
+/// use android_intent::documents;
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// let mut env = intent_env.get_env();
+/// let tree = "content://com.android.externalstorage.documents/tree/primary%3ANotes";
+/// for entry in documents::children(&mut env, tree).unwrap() {
+///     let entry = entry.unwrap();
+///     log::info!("{} ({} bytes)", entry.name, entry.size.unwrap_or(0));
+/// }
+/// ```
+pub fn children<'a, 'env>(env: &'a mut JNIEnv<'env>, tree_uri: &str) -> Result<Children<'a, 'env>, Error> {
+    let (tree, cursor) = trace::checked(env, |env| {
+        let tree = backend::parse_uri(env, tree_uri)?;
+        let activity = context::activity(env)?;
+        let is_document = env
+            .call_static(
+                DOCUMENTS_CONTRACT_CLASS,
+                "isDocumentUri",
+                "(Landroid/content/Context;Landroid/net/Uri;)Z",
+                &[(&activity).into(), (&tree).into()],
+            )?
+            .z()?;
+        let id_getter = if is_document { "getDocumentId" } else { "getTreeDocumentId" };
+        let parent_id = env
+            .call_static(
+                DOCUMENTS_CONTRACT_CLASS,
+                id_getter,
+                "(Landroid/net/Uri;)Ljava/lang/String;",
+                &[(&tree).into()],
+            )?
+            .l()?;
+
+        let children_uri = env
+            .call_static(
+                DOCUMENTS_CONTRACT_CLASS,
+                "buildChildDocumentsUriUsingTree",
+                "(Landroid/net/Uri;Ljava/lang/String;)Landroid/net/Uri;",
+                &[(&tree).into(), (&parent_id).into()],
+            )?
+            .l()?;
+        let cursor = query(env, &children_uri, &CHILD_COLUMNS)?;
+
+        Ok((tree, cursor))
+    })?;
+
+    Ok(Children { env, tree, cursor })
+}
+
+/// Iterator over the documents in a directory, see [`children`].
+pub struct Children<'a, 'env> {
+    env: &'a mut JNIEnv<'env>,
+    tree: JObject<'env>,
+    cursor: JObject<'env>,
+}
+
+impl Children<'_, '_> {
+    fn read_row(&mut self) -> Result<DocEntry, Error> {
+        let (tree, cursor) = (&self.tree, &self.cursor);
+        self.env.with_local_frame(8, |env| {
+            let document_id = cursor_string(env, cursor, 0)?;
+            let jid = env.new_string(&document_id)?;
+            let uri = env
+                .call_static(
+                    DOCUMENTS_CONTRACT_CLASS,
+                    "buildDocumentUriUsingTree",
+                    "(Landroid/net/Uri;Ljava/lang/String;)Landroid/net/Uri;",
+                    &[tree.into(), (&jid).into()],
+                )?
+                .l()?;
+
+            Ok(DocEntry {
+                uri: bundle::to_string(env, &uri)?.into(),
+                name: cursor_string(env, cursor, 1)?,
+                mime_type: cursor_string(env, cursor, 2)?,
+                size: cursor_long(env, cursor, 3)?,
+                last_modified: cursor_long(env, cursor, 4)?,
+                document_id,
+            })
+        })
+    }
+}
+
+impl Iterator for Children<'_, '_> {
+    type Item = Result<DocEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor.is_null() {
+            return None;
+        }
+
+        let row = match self.env.call(&self.cursor, "moveToNext", "()Z", &[]).and_then(|more| more.z()) {
+            Ok(true) => self.read_row(),
+            Ok(false) => return None,
+            Err(err) => Err(err.into()),
+        };
+        Some(row.map_err(|err| trace::clear_exception(self.env, err)))
+    }
+}
+
+impl Drop for Children<'_, '_> {
+    fn drop(&mut self) {
+        if !self.cursor.is_null() {
+            let _ = self.env.call(&self.cursor, "close", "()V", &[]);
+        }
+    }
+}
+
+/// The string in `column` of the cursor's current row, empty if null.
+fn cursor_string(env: &mut JNIEnv, cursor: &JObject, column: i32) -> Result<String, Error> {
+    let value = env.call(cursor, "getString", "(I)Ljava/lang/String;", &[column.into()])?.l()?;
+    if value.is_null() {
+        return Ok(String::new());
+    }
+
+    Ok(env.get_string(&value.into())?.into())
+}
+
+/// The long in `column` of the cursor's current row, `None` if null.
+fn cursor_long(env: &mut JNIEnv, cursor: &JObject, column: i32) -> Result<Option<i64>, Error> {
+    if env.call(cursor, "isNull", "(I)Z", &[column.into()])?.z()? {
+        return Ok(None);
+    }
+
+    Ok(Some(env.call(cursor, "getLong", "(I)J", &[column.into()])?.j()?))
+}
+
+/// Rename the document at `uri` to `display_name`. Returns its URI, which may have changed.
+pub fn rename(env: &mut JNIEnv, uri: &str, display_name: &str) -> Result<Uri, Error> {
+    trace::checked(env, |env| {
+        require(env, uri, DocumentFlags::SUPPORTS_RENAME, "rename")?;
+
+        let resolver = context::content_resolver(env)?;
+        let juri = backend::parse_uri(env, uri)?;
+        let display_name = env.new_string(display_name)?;
+        let renamed = env
+            .call_static(
+                DOCUMENTS_CONTRACT_CLASS,
+                "renameDocument",
+                "(Landroid/content/ContentResolver;Landroid/net/Uri;Ljava/lang/String;)Landroid/net/Uri;",
+                &[(&resolver).into(), (&juri).into(), (&display_name).into()],
+            )?
+            .l()?;
+
+        result_uri(env, renamed, "rename")
+    })
+}
+
+/// Copy the document at `uri` into the directory `target_parent` (API 24+). Returns the copy's
+/// URI.
+pub fn copy(env: &mut JNIEnv, uri: &str, target_parent: &str) -> Result<Uri, Error> {
+    trace::checked(env, |env| {
+        ApiLevel(24).require(env)?;
+        require(env, uri, DocumentFlags::SUPPORTS_COPY, "copy")?;
+
+        let resolver = context::content_resolver(env)?;
+        let juri = backend::parse_uri(env, uri)?;
+        let target_parent = backend::parse_uri(env, target_parent)?;
+        let copied = env
+            .call_static(
+                DOCUMENTS_CONTRACT_CLASS,
+                "copyDocument",
+                "(Landroid/content/ContentResolver;Landroid/net/Uri;Landroid/net/Uri;)Landroid/net/Uri;",
+                &[(&resolver).into(), (&juri).into(), (&target_parent).into()],
+            )?
+            .l()?;
+
+        result_uri(env, copied, "copy")
+    })
+}
+
+/// Move the document at `uri` from the directory `source_parent` into `target_parent` (API 24+).
+/// Returns its new URI.
+pub fn move_to(env: &mut JNIEnv, uri: &str, source_parent: &str, target_parent: &str) -> Result<Uri, Error> {
+    trace::checked(env, |env| {
+        ApiLevel(24).require(env)?;
+        require(env, uri, DocumentFlags::SUPPORTS_MOVE, "move")?;
+
+        let resolver = context::content_resolver(env)?;
+        let juri = backend::parse_uri(env, uri)?;
+        let source_parent = backend::parse_uri(env, source_parent)?;
+        let target_parent = backend::parse_uri(env, target_parent)?;
+        let moved = env
+            .call_static(
+                DOCUMENTS_CONTRACT_CLASS,
+                "moveDocument",
+                "(Landroid/content/ContentResolver;Landroid/net/Uri;Landroid/net/Uri;Landroid/net/Uri;)Landroid/net/Uri;",
+                &[(&resolver).into(), (&juri).into(), (&source_parent).into(), (&target_parent).into()],
+            )?
+            .l()?;
+
+        result_uri(env, moved, "move")
+    })
+}
+
+/// Delete the document at `uri`.
+pub fn delete(env: &mut JNIEnv, uri: &str) -> Result<(), Error> {
+    trace::checked(env, |env| {
+        require(env, uri, DocumentFlags::SUPPORTS_DELETE, "delete")?;
+
+        let resolver = context::content_resolver(env)?;
+        let juri = backend::parse_uri(env, uri)?;
+        let deleted = env
+            .call_static(
+                DOCUMENTS_CONTRACT_CLASS,
+                "deleteDocument",
+                "(Landroid/content/ContentResolver;Landroid/net/Uri;)Z",
+                &[(&resolver).into(), (&juri).into()],
+            )?
+            .z()?;
+        if !deleted {
+            return Err(Error::DocumentUnsupported("delete"));
+        }
+
+        Ok(())
+    })
+}
+
+fn require(env: &mut JNIEnv, uri: &str, flag: DocumentFlags, operation: &'static str) -> Result<(), Error> {
+    if !flags(env, uri)?.contains(flag) {
+        return Err(Error::DocumentUnsupported(operation));
+    }
+
+    Ok(())
+}
+
+/// The URI a `DocumentsContract` operation returned, which is null if the provider refused it.
+fn result_uri(env: &mut JNIEnv, uri: JObject, operation: &'static str) -> Result<Uri, Error> {
+    if uri.is_null() {
+        return Err(Error::DocumentUnsupported(operation));
+    }
+
+    Ok(bundle::to_string(env, &uri)?.into())
+}
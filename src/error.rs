@@ -0,0 +1,119 @@
+use std::fmt;
+
+/// Errors returned while building or launching intents.
+#[derive(Debug)]
+pub enum Error {
+    /// A JNI call failed, or threw a Java exception.
+    Jni(jni::errors::Error),
+    /// The running device is older than the API level the operation needs.
+    UnsupportedApiLevel { required: i32, actual: i32 },
+    /// The intent could not be inspected because an earlier builder step failed. That error is
+    /// returned when the intent is launched.
+    InvalidIntent,
+    /// An `am start` command line could not be parsed.
+    InvalidAmCommand(String),
+    /// An extra of this Java class can't be rebuilt from its Rust representation.
+    UnsupportedExtra(String),
+    /// A required extra is not present.
+    MissingExtra(String),
+    /// An extra holds a different type than the one asked for.
+    ExtraTypeMismatch { key: String, expected: &'static str, found: crate::ExtraValue },
+    /// The operation needs a real intent and is not available on a mock one.
+    Unsupported(&'static str),
+    /// Starting the activity threw; holds the Java exception.
+    LaunchFailed(String),
+    /// The document provider doesn't support this operation on the document, see
+    /// [`documents::flags`](crate::documents::flags).
+    DocumentUnsupported(&'static str),
+    /// The operation was attempted on the wrong thread, see
+    /// [`set_strict_mode`](crate::set_strict_mode).
+    WrongThread(&'static str),
+    /// A builder step failed. `step` names it, with the key for extras, e.g.
+    /// `with_extra(key=android.intent.extra.STREAM)`; use [`Self::root_cause`] to match on what
+    /// went wrong.
+    Step { step: String, source: Box<Error> },
+    /// A Java method threw; holds its name and the exception, e.g.
+    /// `java.lang.ClassCastException: ...`.
+    JavaException { method: String, exception: String },
+    /// The operation needs a permission or special app access the user hasn't granted, e.g.
+    /// `android.permission.SCHEDULE_EXACT_ALARM`.
+    PermissionRequired(&'static str),
+    /// The extras parcel into more bytes than the limit set with
+    /// [`set_extras_size_limit`](crate::set_extras_size_limit), so the launch was not attempted.
+    ExtrasTooLarge { size: usize, limit: usize },
+    /// A launch with this request code is still waiting for its result, see
+    /// [`requests`](crate::requests).
+    RequestCodeInUse(i32),
+    /// Reading or writing a file failed.
+    Io(std::io::Error),
+    /// `bindService` found no service matching the intent, or wasn't allowed to bind to it.
+    BindFailed(String),
+    /// There is no activity to launch from: [`ndk_context`] holds none, or the one it held has
+    /// been destroyed. See [`IntentEnv::refresh_context`](crate::IntentEnv::refresh_context).
+    NullContext,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Jni(err) => write!(f, "JNI error: {}", err),
+            Self::UnsupportedApiLevel { required, actual } => {
+                write!(f, "requires API level {}, device has {}", required, actual)
+            }
+            Self::InvalidIntent => f.write_str("intent failed to build"),
+            Self::InvalidAmCommand(reason) => write!(f, "invalid am command: {}", reason),
+            Self::UnsupportedExtra(class) => write!(f, "cannot put an extra of type {}", class),
+            Self::MissingExtra(key) => write!(f, "missing extra {}", key),
+            Self::ExtraTypeMismatch { key, expected, found } => {
+                write!(f, "extra {} should be {}, found {:?}", key, expected, found)
+            }
+            Self::Unsupported(operation) => write!(f, "{} is not supported by this backend", operation),
+            Self::LaunchFailed(exception) => write!(f, "failed to start activity: {}", exception),
+            Self::DocumentUnsupported(operation) => write!(f, "document does not support {}", operation),
+            Self::WrongThread(reason) => write!(f, "wrong thread: {}", reason),
+            Self::PermissionRequired(permission) => write!(f, "{} is not granted", permission),
+            Self::ExtrasTooLarge { size, limit } => {
+                write!(f, "extras take {} bytes, over the limit of {}", size, limit)
+            }
+            Self::RequestCodeInUse(code) => write!(f, "request code {} is already in flight", code),
+            Self::Io(err) => write!(f, "I/O error: {}", err),
+            Self::BindFailed(service) => write!(f, "failed to bind to {}", service),
+            Self::NullContext => f.write_str("no Android context, the activity is gone"),
+            Self::Step { step, source } => write!(f, "{}: {}", step, source),
+            Self::JavaException { method, exception } => write!(f, "{} threw {}", method, exception),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Jni(err) => Some(err),
+            Self::Io(err) => Some(err),
+            Self::Step { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// The error without the [`Self::Step`] context around it.
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Self::Step { source, .. } => source.root_cause(),
+            err => err,
+        }
+    }
+}
+
+impl From<jni::errors::Error> for Error {
+    fn from(err: jni::errors::Error) -> Self {
+        Self::Jni(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
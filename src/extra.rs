@@ -1,12 +1,374 @@
+use std::collections::BTreeMap;
+
+use jni::objects::{
+    JBooleanArray, JByteArray, JCharArray, JDoubleArray, JFloatArray, JIntArray, JLongArray, JObject, JObjectArray,
+    JShortArray, JString,
+};
+use jni::JNIEnv;
+
+use crate::backend::INTENT_CLASS;
+use crate::{backend, bundle, constants, Error, IntentSpec, Uri};
+use crate::trace::Traced;
+
 /// Extra data to include with an intent
 pub enum Extra {
     Text,
+    /// HTML version of [`Self::Text`], see [`recipes::share_html`](crate::recipes::share_html).
+    HtmlText,
 }
 
+const EXTRA_TEXT: &str = constants::expect_string(INTENT_CLASS, "EXTRA_TEXT");
+const EXTRA_HTML_TEXT: &str = constants::expect_string(INTENT_CLASS, "EXTRA_HTML_TEXT");
+
 impl AsRef<str> for Extra {
     fn as_ref(&self) -> &str {
         match self {
-            Self::Text => "android.intent.extra.TEXT",
+            Self::Text => EXTRA_TEXT,
+            Self::HtmlText => EXTRA_HTML_TEXT,
+        }
+    }
+}
+
+/// An element of a `Parcelable[]` or `ArrayList<Parcelable>` extra, see
+/// [`Intent::with_extra_parcelable_array`](crate::Intent::with_extra_parcelable_array).
+pub enum Parcelable<'a> {
+    /// Any `android.os.Parcelable` object.
+    Object(&'a JObject<'a>),
+    /// An `android.net.Uri`, parsed when the extra is added.
+    Uri(Uri),
+    /// An `android.content.ComponentName` from its flattened form, e.g. `com.example/.Receiver`.
+    Component(String),
+}
+
+impl<'a> From<&'a JObject<'a>> for Parcelable<'a> {
+    fn from(object: &'a JObject<'a>) -> Self {
+        Self::Object(object)
+    }
+}
+
+impl From<Uri> for Parcelable<'_> {
+    fn from(uri: Uri) -> Self {
+        Self::Uri(uri)
+    }
+}
+
+impl Parcelable<'_> {
+    pub(crate) fn to_object<'a>(&self, env: &mut JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+        match self {
+            Self::Object(object) => Ok(env.new_local_ref(object)?),
+            Self::Uri(uri) => Ok(backend::parse_uri(env, uri.as_str())?),
+            Self::Component(component) => backend::component_name(env, component),
+        }
+    }
+}
+
+/// A value read back from an intent's extras.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExtraValue {
+    Null,
+    Str(String),
+    Int(i32),
+    Long(i64),
+    Bool(bool),
+    Float(f32),
+    Double(f64),
+    StringArray(Vec<String>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    FloatArray(Vec<f32>),
+    DoubleArray(Vec<f64>),
+    ByteArray(Vec<u8>),
+    /// A Java `char[]`, as UTF-16 code units.
+    CharArray(Vec<u16>),
+    ShortArray(Vec<i16>),
+    BoolArray(Vec<bool>),
+    Uri(Uri),
+    Bundle(BTreeMap<String, ExtraValue>),
+    /// A nested `android.content.Intent`, such as the target of a chooser.
+    Intent(Box<IntentSpec>),
+    /// Any other type, identified by its Java class name.
+    Other(String),
+}
+
+/// Copy a primitive Java array such as `int[]` into a `Vec`.
+macro_rules! read_array {
+    ($env:ident, $object:ident, $array:ty, $get_region:ident) => {{
+        let array: &$array = $object.into();
+        let mut values = vec![Default::default(); $env.get_array_length(array)? as usize];
+        $env.$get_region(array, 0, &mut values)?;
+        values
+    }};
+}
+
+impl ExtraValue {
+    /// The Java type of the value, e.g. `int[]` or `android.net.Uri`.
+    pub fn java_type(&self) -> &str {
+        match self {
+            Self::Null => "null",
+            Self::Str(_) => "String",
+            Self::Int(_) => "int",
+            Self::Long(_) => "long",
+            Self::Bool(_) => "boolean",
+            Self::Float(_) => "float",
+            Self::Double(_) => "double",
+            Self::StringArray(_) => "String[]",
+            Self::IntArray(_) => "int[]",
+            Self::LongArray(_) => "long[]",
+            Self::FloatArray(_) => "float[]",
+            Self::DoubleArray(_) => "double[]",
+            Self::ByteArray(_) => "byte[]",
+            Self::CharArray(_) => "char[]",
+            Self::ShortArray(_) => "short[]",
+            Self::BoolArray(_) => "boolean[]",
+            Self::Uri(_) => "android.net.Uri",
+            Self::Bundle(_) => "android.os.Bundle",
+            Self::Intent(_) => "android.content.Intent",
+            Self::Other(class) => class,
+        }
+    }
+
+    pub(crate) fn from_object(env: &mut JNIEnv, object: &JObject) -> Result<Self, Error> {
+        if object.is_null() {
+            return Ok(Self::Null);
+        }
+
+        let value = if env.is_instance_of(object, "java/lang/String")? {
+            Self::Str(bundle::to_string(env, object)?)
+        } else if env.is_instance_of(object, "java/lang/Integer")? {
+            Self::Int(env.call(object, "intValue", "()I", &[])?.i()?)
+        } else if env.is_instance_of(object, "java/lang/Long")? {
+            Self::Long(env.call(object, "longValue", "()J", &[])?.j()?)
+        } else if env.is_instance_of(object, "java/lang/Boolean")? {
+            Self::Bool(env.call(object, "booleanValue", "()Z", &[])?.z()?)
+        } else if env.is_instance_of(object, "java/lang/Float")? {
+            Self::Float(env.call(object, "floatValue", "()F", &[])?.f()?)
+        } else if env.is_instance_of(object, "java/lang/Double")? {
+            Self::Double(env.call(object, "doubleValue", "()D", &[])?.d()?)
+        } else if env.is_instance_of(object, "[Ljava/lang/String;")? {
+            let array: &JObjectArray = object.into();
+            let len = env.get_array_length(array)?;
+            let mut strings = Vec::with_capacity(len as usize);
+            for i in 0..len {
+                let element: JString = env.get_object_array_element(array, i)?.into();
+                let element = if element.is_null() {
+                    String::new()
+                } else {
+                    env.get_string(&element)?.into()
+                };
+                strings.push(element);
+            }
+            Self::StringArray(strings)
+        } else if env.is_instance_of(object, "[I")? {
+            Self::IntArray(read_array!(env, object, JIntArray, get_int_array_region))
+        } else if env.is_instance_of(object, "[J")? {
+            Self::LongArray(read_array!(env, object, JLongArray, get_long_array_region))
+        } else if env.is_instance_of(object, "[F")? {
+            Self::FloatArray(read_array!(env, object, JFloatArray, get_float_array_region))
+        } else if env.is_instance_of(object, "[D")? {
+            Self::DoubleArray(read_array!(env, object, JDoubleArray, get_double_array_region))
+        } else if env.is_instance_of(object, "[B")? {
+            let array: &JByteArray = object.into();
+            Self::ByteArray(env.convert_byte_array(array)?)
+        } else if env.is_instance_of(object, "[C")? {
+            Self::CharArray(read_array!(env, object, JCharArray, get_char_array_region))
+        } else if env.is_instance_of(object, "[S")? {
+            Self::ShortArray(read_array!(env, object, JShortArray, get_short_array_region))
+        } else if env.is_instance_of(object, "[Z")? {
+            let values = read_array!(env, object, JBooleanArray, get_boolean_array_region);
+            Self::BoolArray(values.into_iter().map(|value| value != 0).collect())
+        } else if env.is_instance_of(object, "android/net/Uri")? {
+            Self::Uri(bundle::to_string(env, object)?.into())
+        } else if env.is_instance_of(object, "android/os/Bundle")? {
+            Self::Bundle(Self::map_from_bundle(env, object)?)
+        } else if env.is_instance_of(object, "android/content/Intent")? {
+            Self::Intent(Box::new(backend::spec_of(env, object)?))
+        } else {
+            Self::Other(bundle::class_name(env, object)?)
+        };
+
+        Ok(value)
+    }
+
+    pub(crate) fn map_from_bundle(env: &mut JNIEnv, bundle: &JObject) -> Result<BTreeMap<String, Self>, Error> {
+        let mut map = BTreeMap::new();
+        for key in bundle::keys(env, bundle)? {
+            let object = bundle::get(env, bundle, &key)?;
+            let value = Self::from_object(env, &object)?;
+            map.insert(key, value);
+        }
+
+        Ok(map)
+    }
+}
+
+macro_rules! impl_extra_value_conversions {
+    ($($variant:ident($ty:ty)),* $(,)?) => {
+        $(
+            impl From<$ty> for ExtraValue {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value)
+                }
+            }
+
+            /// Fails with the original value if it holds a different type.
+            impl TryFrom<ExtraValue> for $ty {
+                type Error = ExtraValue;
+
+                fn try_from(value: ExtraValue) -> Result<Self, Self::Error> {
+                    match value {
+                        ExtraValue::$variant(value) => Ok(value),
+                        other => Err(other),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_extra_value_conversions! {
+    Str(String),
+    Int(i32),
+    Long(i64),
+    Bool(bool),
+    Float(f32),
+    Double(f64),
+    StringArray(Vec<String>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    FloatArray(Vec<f32>),
+    DoubleArray(Vec<f64>),
+    ByteArray(Vec<u8>),
+    CharArray(Vec<u16>),
+    ShortArray(Vec<i16>),
+    BoolArray(Vec<bool>),
+    Uri(Uri),
+    Bundle(BTreeMap<String, ExtraValue>),
+}
+
+impl From<&str> for ExtraValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_owned())
+    }
+}
+
+/// A Rust type that maps to a set of intent extras, one per field.
+///
+/// With the `derive` feature, `#[derive(IntentExtras)]` implements this for structs whose fields
+/// convert to and from [`ExtraValue`]. Each field is stored under its name, unless renamed with
+/// `#[extra(key = "...")]`. `Option` fields may be missing.
+#[cfg_attr(
+    feature = "derive",
+    doc = r#"```
+use android_intent::{Error, ExtraValue, IntentExtras, Uri};
+
+#[derive(Debug, PartialEq, IntentExtras)]
+struct ShareDoc {
+    #[extra(key = "android.intent.extra.TITLE")]
+    title: String,
+    page: i32,
+    uri: Option<Uri>,
+}
+
+let doc = ShareDoc { title: "Notes".into(), page: 3, uri: None };
+let mut extras = doc.to_extras();
+assert_eq!(extras["android.intent.extra.TITLE"], ExtraValue::Str("Notes".into()));
+assert!(!extras.contains_key("uri"));
+assert_eq!(ShareDoc::from_extras(&extras).unwrap(), doc);
+
+extras.insert("uri".into(), ExtraValue::Uri(Uri::new("content://notes/3")));
+assert_eq!(ShareDoc::from_extras(&extras).unwrap().uri, Some(Uri::new("content://notes/3")));
+
+extras.remove("page");
+assert!(matches!(ShareDoc::from_extras(&extras), Err(Error::MissingExtra(key)) if key == "page"));
+```"#
+)]
+pub trait IntentExtras: Sized {
+    fn to_extras(&self) -> BTreeMap<String, ExtraValue>;
+
+    fn from_extras(extras: &BTreeMap<String, ExtraValue>) -> Result<Self, Error>;
+}
+
+/// A type that can be read from a single intent extra, see [`Intent::get`](crate::Intent::get).
+///
+/// Implemented for `String`, `i32`, `i64`, `bool`, `f32`, `f64`, `Vec`s of those, `Vec<String>`,
+/// `Vec<u8>`, `Vec<u16>` (`char[]`), `Vec<i16>`, [`Uri`], nested bundles, [`ExtraValue`] itself
+/// and `Option`s of those. `Option` reads a missing or null extra as `None`.
+pub trait FromIntent: Sized {
+    /// Convert the extra stored under `key`, or `None` if there is none.
+    fn from_extra(key: &str, value: Option<&ExtraValue>) -> Result<Self, Error>;
+}
+
+macro_rules! impl_from_intent {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl FromIntent for $ty {
+                fn from_extra(key: &str, value: Option<&ExtraValue>) -> Result<Self, Error> {
+                    let value = value.ok_or_else(|| Error::MissingExtra(key.to_owned()))?;
+                    Self::try_from(value.clone()).map_err(|found| Error::ExtraTypeMismatch {
+                        key: key.to_owned(),
+                        expected: stringify!($ty),
+                        found,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_from_intent!(
+    String,
+    i32,
+    i64,
+    bool,
+    f32,
+    f64,
+    Vec<String>,
+    Vec<i32>,
+    Vec<i64>,
+    Vec<f32>,
+    Vec<f64>,
+    Vec<u8>,
+    Vec<u16>,
+    Vec<i16>,
+    Vec<bool>,
+    Uri,
+    BTreeMap<String, ExtraValue>,
+);
+
+impl FromIntent for ExtraValue {
+    fn from_extra(key: &str, value: Option<&ExtraValue>) -> Result<Self, Error> {
+        value.cloned().ok_or_else(|| Error::MissingExtra(key.to_owned()))
+    }
+}
+
+impl<T: FromIntent> FromIntent for Option<T> {
+    fn from_extra(key: &str, value: Option<&ExtraValue>) -> Result<Self, Error> {
+        match value {
+            None | Some(ExtraValue::Null) => Ok(None),
+            value => T::from_extra(key, value).map(Some),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use std::collections::BTreeMap;
+
+    use super::{ExtraValue, FromIntent};
+    use crate::Error;
+
+    pub fn put<T: Clone + Into<ExtraValue>>(extras: &mut BTreeMap<String, ExtraValue>, key: &str, field: &T) {
+        extras.insert(key.to_owned(), field.clone().into());
+    }
+
+    pub fn put_optional<T: Clone + Into<ExtraValue>>(extras: &mut BTreeMap<String, ExtraValue>, key: &str, field: &Option<T>) {
+        if let Some(field) = field {
+            put(extras, key, field);
         }
     }
+
+    pub fn get<T: FromIntent>(extras: &BTreeMap<String, ExtraValue>, key: &str) -> Result<T, Error> {
+        T::from_extra(key, extras.get(key))
+    }
 }
@@ -1,8 +1,42 @@
 use bitflags::bitflags;
 
+use crate::backend::INTENT_CLASS;
+use crate::constants;
+
 bitflags! {
+    /// `Intent.FLAG_*` flags. Each flag's bits equal the value of the Java constant of the same name.
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
     pub struct Flags: u32 {
         const GRANT_READ_URI_PERMISSION = 0b00000001;
+        const GRANT_WRITE_URI_PERMISSION = 0b00000010;
+        const ACTIVITY_EXCLUDE_FROM_RECENTS = 0x0080_0000;
+        /// Open the activity next to the current one in split-screen multi-window mode (API 24).
+        const ACTIVITY_LAUNCH_ADJACENT = 0x0000_1000;
+        const ACTIVITY_CLEAR_TOP = 0x0400_0000;
+        const ACTIVITY_NEW_TASK = 0x1000_0000;
+        const ACTIVITY_SINGLE_TOP = 0x2000_0000;
+        const ACTIVITY_NO_HISTORY = 0x4000_0000;
     }
 }
+
+/// Fail the build unless each flag's bits equal the `Intent.FLAG_*` constant of the same name.
+macro_rules! check_flags {
+    ($($flag:ident),*) => {
+        const _: () = {
+            $(assert!(
+                Flags::$flag.bits() as i32 == constants::expect_int(INTENT_CLASS, concat!("FLAG_", stringify!($flag)))
+            );)*
+        };
+    };
+}
+
+check_flags!(
+    GRANT_READ_URI_PERMISSION,
+    GRANT_WRITE_URI_PERMISSION,
+    ACTIVITY_EXCLUDE_FROM_RECENTS,
+    ACTIVITY_LAUNCH_ADJACENT,
+    ACTIVITY_CLEAR_TOP,
+    ACTIVITY_NEW_TASK,
+    ACTIVITY_SINGLE_TOP,
+    ACTIVITY_NO_HISTORY
+);
@@ -0,0 +1,230 @@
+//! What hosts the app: the `Context` [`ndk_context`] provides and how launches and results go
+//! through it.
+//!
+//! Results need an activity extending the generated [bridge](crate::bridge) class. Pick a
+//! [`HostActivity`] for the app's architecture with [`set_host_activity`]:
+//! - [`NativeActivityHost`] for `android.app.NativeActivity`, e.g. through the
+//!   `native-activity` backend of the `android-activity` crate. Without the bridge subclass,
+//!   [`Intent::get_result`](crate::Intent::get_result) finds no results.
+//! - [`GameActivityHost`] for `GameActivity`, e.g. through its `game-activity` backend, with the
+//!   bridge generated using [`Bridge::with_superclass`](crate::bridge::Bridge::with_superclass).
+//!   It rejects request codes androidx would throw on.
+//! - [`ContextHost`] when the context is no activity at all, e.g. a service running the Rust
+//!   code. Activities start in a new task, and there are no results.
+//!
+//! Without one set, the crate uses [`NativeActivityHost`] if the context is an
+//! `android.app.Activity` and [`ContextHost`] otherwise. Custom hosts implement the trait,
+//! overriding what their activity does differently:
+//! ```no_run
+//! use android_intent::host::{self, HostActivity};
+//! use android_intent::jni::{objects::JObject, JNIEnv};
+//! use android_intent::Error;
+//!
+//! /// An activity that routes launches through its own `launch(Intent, int)` method.
+//! struct RoutingActivity;
+//!
+//! impl HostActivity for RoutingActivity {
+//!     fn start_activity(
+//!         &self,
+//!         env: &mut JNIEnv,
+//!         activity: &JObject,
+//!         intent: &JObject,
+//!         request_code: Option<i32>,
+//!     ) -> Result<(), Error> {
+//!         let args = [intent.into(), request_code.unwrap_or(-1).into()];
+//!         env.call_method(activity, "launch", "(Landroid/content/Intent;I)V", &args)?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! host::set_host_activity(RoutingActivity);
+//! ```
+
+use std::sync::{Arc, RwLock};
+
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::trace::Traced;
+use crate::{Error, Flags};
+
+/// How launches and results go through the app's `Context`. The default methods expect an
+/// activity extending the generated [bridge](crate::bridge) class.
+pub trait HostActivity: Send + Sync {
+    /// Start `intent` from `activity`, with its result delivered under `request_code` if given.
+    fn start_activity(
+        &self,
+        env: &mut JNIEnv,
+        activity: &JObject,
+        intent: &JObject,
+        request_code: Option<i32>,
+    ) -> Result<(), Error> {
+        match request_code {
+            Some(request_code) => {
+                env.call(
+                    activity,
+                    "startActivityForResult",
+                    "(Landroid/content/Intent;I)V",
+                    &[intent.into(), request_code.into()],
+                )?;
+            }
+            None => {
+                env.call(activity, "startActivity", "(Landroid/content/Intent;)V", &[intent.into()])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The next result queued for [`Intent::get_result`](crate::Intent::get_result), a
+    /// `RustNativeIntentResult`, or null if there is none.
+    #[cfg(feature = "result-bridge")]
+    fn next_result<'a>(&self, env: &mut JNIEnv<'a>, activity: &JObject) -> Result<JObject<'a>, Error> {
+        let result = env.call(activity, crate::bridge::GET_NEXT_RESULT, crate::bridge::get_next_result_sig(), &[])?;
+
+        Ok(result.l()?)
+    }
+
+    /// Have results posted on the `BlockingQueue` `results` instead of queued for
+    /// [`Self::next_result`], see [`winit::forward_results`](crate::winit::forward_results).
+    #[cfg(feature = "winit")]
+    fn register_result_queue(&self, env: &mut JNIEnv, activity: &JObject, results: &JObject) -> Result<(), Error> {
+        let method = crate::bridge::REGISTER_RESULT_QUEUE;
+        env.call(activity, method, crate::bridge::RESULT_QUEUE_SIG, &[results.into()])?;
+
+        Ok(())
+    }
+
+    /// Undo [`Self::register_result_queue`], handing the results still in `results` back to
+    /// [`Self::next_result`].
+    #[cfg(feature = "winit")]
+    fn unregister_result_queue(&self, env: &mut JNIEnv, activity: &JObject, results: &JObject) -> Result<(), Error> {
+        let method = crate::bridge::UNREGISTER_RESULT_QUEUE;
+        env.call(activity, method, crate::bridge::RESULT_QUEUE_SIG, &[results.into()])?;
+
+        Ok(())
+    }
+}
+
+/// An `android.app.NativeActivity`. Without the generated bridge subclass it can still launch,
+/// but has no results to report.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NativeActivityHost;
+
+impl HostActivity for NativeActivityHost {
+    #[cfg(feature = "result-bridge")]
+    fn next_result<'a>(&self, env: &mut JNIEnv<'a>, activity: &JObject) -> Result<JObject<'a>, Error> {
+        bridge_result(env, activity)
+    }
+}
+
+/// A `com.google.androidgamesdk.GameActivity`. Like [`NativeActivityHost`], it only reports
+/// results through the generated bridge subclass.
+///
+/// As an androidx `ComponentActivity`, it only accepts request codes that fit in 16 bits;
+/// larger ones fail with [`Error::Unsupported`] instead of throwing.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GameActivityHost;
+
+impl HostActivity for GameActivityHost {
+    fn start_activity(
+        &self,
+        env: &mut JNIEnv,
+        activity: &JObject,
+        intent: &JObject,
+        request_code: Option<i32>,
+    ) -> Result<(), Error> {
+        if request_code.is_some_and(|request_code| request_code & !0xffff != 0) {
+            return Err(Error::Unsupported("request codes beyond 16 bits on androidx activities"));
+        }
+
+        NativeActivityHost.start_activity(env, activity, intent, request_code)
+    }
+
+    #[cfg(feature = "result-bridge")]
+    fn next_result<'a>(&self, env: &mut JNIEnv<'a>, activity: &JObject) -> Result<JObject<'a>, Error> {
+        bridge_result(env, activity)
+    }
+}
+
+/// The bridge's next result, or null if `activity` isn't a bridge subclass.
+#[cfg(feature = "result-bridge")]
+fn bridge_result<'a>(env: &mut JNIEnv<'a>, activity: &JObject) -> Result<JObject<'a>, Error> {
+    let class = env.get_object_class(activity)?;
+    let sig = crate::bridge::get_next_result_sig();
+    if env.get_method_id(&class, crate::bridge::GET_NEXT_RESULT, &sig).is_err() {
+        // Looking up a missing method leaves a NoSuchMethodError pending.
+        env.exception_clear()?;
+        return Ok(JObject::null());
+    }
+
+    Ok(env.call(activity, crate::bridge::GET_NEXT_RESULT, sig, &[])?.l()?)
+}
+
+/// A plain `Context` such as a service or the application, without an activity to launch from
+/// or receive results with.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ContextHost;
+
+impl HostActivity for ContextHost {
+    /// Start `intent` in a new task, as required outside an activity. Fails with
+    /// [`Error::Unsupported`] for launches waiting for a result.
+    fn start_activity(
+        &self,
+        env: &mut JNIEnv,
+        context: &JObject,
+        intent: &JObject,
+        request_code: Option<i32>,
+    ) -> Result<(), Error> {
+        if request_code.is_some() {
+            return Err(Error::Unsupported("start_activity_for_result without an activity"));
+        }
+
+        let new_task = Flags::ACTIVITY_NEW_TASK.bits() as i32;
+        env.call(intent, "addFlags", "(I)Landroid/content/Intent;", &[new_task.into()])?;
+        env.call(context, "startActivity", "(Landroid/content/Intent;)V", &[intent.into()])?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "result-bridge")]
+    fn next_result<'a>(&self, _env: &mut JNIEnv<'a>, _context: &JObject) -> Result<JObject<'a>, Error> {
+        Ok(JObject::null())
+    }
+
+    #[cfg(feature = "winit")]
+    fn register_result_queue(&self, _env: &mut JNIEnv, _context: &JObject, _results: &JObject) -> Result<(), Error> {
+        Err(Error::Unsupported("forwarding results without an activity"))
+    }
+}
+
+static HOST: RwLock<Option<Arc<dyn HostActivity>>> = RwLock::new(None);
+
+/// Launch and receive results through `host` from now on.
+pub fn set_host_activity(host: impl HostActivity + 'static) {
+    *HOST.write().unwrap() = Some(Arc::new(host));
+}
+
+/// Go back to picking the host from the context's class.
+pub fn clear_host_activity() {
+    *HOST.write().unwrap() = None;
+}
+
+/// Run `f` with the host set with [`set_host_activity`], or the one fitting `context`.
+pub(crate) fn with_host<'a, T>(
+    env: &mut JNIEnv<'a>,
+    context: &JObject,
+    f: impl FnOnce(&dyn HostActivity, &mut JNIEnv<'a>) -> Result<T, Error>,
+) -> Result<T, Error> {
+    // Not holding the lock while `f` runs, so hosts may replace themselves.
+    let host = HOST.read().unwrap().clone();
+    if let Some(host) = host {
+        return f(host.as_ref(), env);
+    }
+
+    if env.is_instance_of(context, "android/app/Activity")? {
+        f(&NativeActivityHost, env)
+    } else {
+        f(&ContextHost, env)
+    }
+}
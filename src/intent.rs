@@ -1,120 +1,494 @@
-use std::borrow::Borrow;
-use jni::{errors::Error, objects::{JObject, JString}, JNIEnv, AttachGuard, JavaVM};
-use jni::objects::{JValue, JValueOwned};
-use jni::sys::jint;
-use crate::Flags;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
 
-use log::debug;
+use jni::{objects::JObject, AttachGuard, JNIEnv};
+use crate::backend::{self, IntentBackend, Inner, INTENT_CLASS};
+use crate::{
+    api_level, bundle, chooser, compat, context, debounce, observe, parcel_size, ApiLevel, Bundle, ComponentName, Error,
+    ExtraValue, Flags, FromIntent, IntentDescription, IntentExtras, IntentSpec, Parcelable, Uri,
+};
+use crate::validate::{self, Diagnostic};
 
-struct Inner<'env> {
-    env: AttachGuard<'env>,
-    object: JObject<'env>,
-}
+use crate::trace::{self, debug, Traced};
+use crate::sig::sig;
+use crate::ui_thread::{self, UiLaunch};
+use crate::chooser::PendingRequest;
+
+const EXTRA_REFERRER: &str = "android.intent.extra.REFERRER";
+const EXTRA_REFERRER_NAME: &str = "android.intent.extra.REFERRER_NAME";
 
 /// A messaging object you can use to request an action from another android app component.
 #[must_use]
 pub struct Intent<'env> {
-    inner: Result<Inner<'env>, Error>,
+    inner: Result<Box<dyn IntentBackend<'env> + 'env>, Error>,
 }
 
 impl<'env> Intent<'env> {
-    pub fn from_object(env: AttachGuard<'env>, object: JObject<'env>) -> Self {
+    /// Wrap the `android.content.Intent` `object`. The intent takes its own local reference,
+    /// deleted when it is dropped, so `object` stays valid and owned by the caller.
+    pub fn from_object(env: AttachGuard<'env>, object: &JObject) -> Self {
+        match env.new_local_ref(object) {
+            Ok(object) => Self::from_local(env, object),
+            Err(err) => Self::from_error(err),
+        }
+    }
+
+    /// Wrap `object`, a local reference the intent takes over and deletes when it is dropped.
+    pub(crate) fn from_local(env: AttachGuard<'env>, object: JObject<'env>) -> Self {
+        Self::from_backend(Ok(Inner::new(env, object)))
+    }
+
+    fn from_backend(backend: Result<impl IntentBackend<'env> + 'env, Error>) -> Self {
         Self {
-            inner: Ok(Inner { env, object }),
+            inner: backend.map(|backend| Box::new(backend) as Box<dyn IntentBackend<'env> + 'env>),
         }
     }
 
-    fn from_fn(f: impl FnOnce() -> Result<Inner<'env>, Error>) -> Self {
-        let inner = f();
-        Self { inner }
+    pub(crate) fn from_error(err: impl Into<Error>) -> Self {
+        Self { inner: Err(err.into()) }
     }
 
-    fn get_static_field_val<'a>(env: &mut AttachGuard<'a>, field_name: impl AsRef<str>, field_type: &str) -> Result<JValueOwned<'a>, Error> {
-        debug!("get static field Intent.{} with type {}", field_name.as_ref(), field_type);
+    pub fn new(env: AttachGuard<'env>, action: impl AsRef<str>) -> Self {
+        Self::new_with_class_action(env, INTENT_CLASS, action, None::<&str>)
+    }
 
-        let intent_class = env.find_class("android/content/Intent")?;
-        let val = env.get_static_field(&intent_class, field_name.as_ref(), field_type)?;
+    pub fn new_with_uri(env: AttachGuard<'env>, action: impl AsRef<str>, uri: impl AsRef<str>) -> Self {
+        Self::new_with_class_action(env, INTENT_CLASS, action, Some(uri))
+    }
 
-        return Ok(val);
+    /// An intent that records every builder step instead of calling into Java, for testing
+    /// intent-building code on a desktop host. Inspect it with [`Self::mock_backend`] or
+    /// [`Self::to_spec`].
+    #[cfg(feature = "mock")]
+    pub fn mock(action: impl AsRef<str>) -> Self {
+        Self::from_backend(Ok(crate::MockBackend::new(INTENT_CLASS, action.as_ref(), None)))
     }
 
-    pub fn new(mut env: AttachGuard<'env>, action: impl AsRef<str>) -> Self {
-        Self::from_fn(|| {
-            let action_view = Self::get_static_field_val(&mut env, action.as_ref(), "Ljava/lang/String;")?;
+    /// Same as [`Self::mock`], with a data URI.
+    #[cfg(feature = "mock")]
+    pub fn mock_with_uri(action: impl AsRef<str>, uri: impl AsRef<str>) -> Self {
+        Self::from_backend(Ok(crate::MockBackend::new(INTENT_CLASS, action.as_ref(), Some(uri.as_ref()))))
+    }
 
-            let intent_class = env.find_class("android/content/Intent")?;
-            let intent =
-                env.new_object(&intent_class, "(Ljava/lang/String;)V", &[(&action_view).into()])?;
+    /// What a [`Self::mock`] intent recorded, or `None` for a real intent.
+    #[cfg(feature = "mock")]
+    pub fn mock_backend(&self) -> Option<&crate::MockBackend> {
+        self.inner.as_ref().ok().and_then(|inner| inner.mock())
+    }
 
-            Ok(Inner {
-                env,
-                object: intent,
-            })
-        })
+    /// Builds an intent whose action is the static `String` field `action` of `class`, e.g.
+    /// `ACTION_APPLICATION_DETAILS_SETTINGS` on `android/provider/Settings`.
+    pub(crate) fn new_with_class_action(
+        env: AttachGuard<'env>,
+        class: &str,
+        action: impl AsRef<str>,
+        uri: Option<impl AsRef<str>>,
+    ) -> Self {
+        let uri = uri.as_ref().map(AsRef::as_ref);
+        Self::from_backend(Inner::new_with_class_action(env, class, action.as_ref(), uri))
     }
 
-    pub fn new_with_uri(mut env: AttachGuard<'env>, action: impl AsRef<str>, uri: impl AsRef<str>) -> Self {
-        Self::from_fn(|| {
-            let url_string = env.new_string(uri)?;
-            let uri_class = env.find_class("android/net/Uri")?;
-            let uri = env.call_static_method(
-                uri_class,
-                "parse",
-                "(Ljava/lang/String;)Landroid/net/Uri;",
-                &[(&url_string).into()],
-            )?;
+    /// Parse an `am start` command line, such as one produced by [`Self::to_am_command`], into an
+    /// intent. Supports `-a`, `-d`, `-t`, `-c`, `-p`, `-n`, `-i`, `-f` and the `-e`/`--es`, `--esn`,
+    /// `--ei`, `--el`, `--ez`, `--ef`, `--ed`, `--eu`, `--esa`, `--eia`, `--ela` and `--efa` extras.
+    /// ```no_run
+    /// use android_intent::Intent;
+    ///
+    /// # let mut intent_env = android_intent::IntentEnv::new();
+    /// # android_intent::with_current_env(&mut intent_env, |env| {
+    /// Intent::from_am_args(env, "-a android.intent.action.VIEW -d https://example.com")
+    ///     .start_activity()
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub fn from_am_args(env: AttachGuard<'env>, args: &str) -> Self {
+        match IntentSpec::from_am_command(args) {
+            Ok(spec) => Self::from_spec(env, spec),
+            Err(err) => Self::from_error(err),
+        }
+    }
 
-            let action_view = Self::get_static_field_val(&mut env, action.as_ref(), "Ljava/lang/String;")?;
+    pub(crate) fn from_spec(env: AttachGuard<'env>, spec: IntentSpec) -> Self {
+        Self::from_backend(Inner::from_spec(env, &spec))
+    }
 
-            let intent_class = env.find_class("android/content/Intent")?;
-            let intent = env.new_object(
-                &intent_class,
-                "(Ljava/lang/String;Landroid/net/Uri;)V",
-                &[(&action_view).into(), (&uri).into()],
-            )?;
+    /// An explicit intent for this app's activity `class`, fully qualified or relative to the
+    /// app's package (`.SecondActivity`), so navigating between the app's own activities needs
+    /// no hardcoded package name.
+    /// ```no_run
+    /// use android_intent::Intent;
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// Intent::for_own_activity(intent_env.get_env(), "com.example.app.SecondActivity")
+    ///     .with_extra_int("item", 7)
+    ///     .start_activity()
+    ///     .unwrap();
+    /// ```
+    pub fn for_own_activity(mut env: AttachGuard<'env>, class: impl AsRef<str>) -> Self {
+        match context::package_name(&mut env) {
+            Ok(package) => {
+                let spec = IntentSpec {
+                    component: Some(format!("{}/{}", package, class.as_ref())),
+                    ..Default::default()
+                };
+                Self::from_spec(env, spec)
+            }
+            Err(err) => Self::from_error(err),
+        }
+    }
 
-            Ok(Inner {
-                env,
-                object: intent,
-            })
-        })
+    /// An intent to launch `component`'s main activity, as the launcher would, via
+    /// `Intent.makeMainActivity`.
+    /// ```no_run
+    /// use android_intent::Intent;
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// Intent::make_main_activity(intent_env.get_env(), "com.example.other/.MainActivity")
+    ///     .new_task()
+    ///     .start_activity()
+    ///     .unwrap();
+    /// ```
+    pub fn make_main_activity(env: AttachGuard<'env>, component: impl AsRef<str>) -> Self {
+        Self::from_component_factory(env, "makeMainActivity", component.as_ref())
+    }
+
+    /// An intent to relaunch `component` as the root of a fresh task, clearing any existing
+    /// one, via `Intent.makeRestartActivityTask`. Pass this app's launcher activity to restart
+    /// it from scratch.
+    pub fn make_restart_activity_task(env: AttachGuard<'env>, component: impl AsRef<str>) -> Self {
+        Self::from_component_factory(env, "makeRestartActivityTask", component.as_ref())
+    }
+
+    /// An intent to launch the main activity of whichever app handles `selector_action` and
+    /// `selector_category`, via `Intent.makeMainSelectorActivity`. Both name `Intent` fields, like
+    /// [`Action`](crate::Action) and [`Category`](crate::Category) do:
+    /// ```no_run
+    /// use android_intent::Intent;
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// Intent::make_main_selector_activity(intent_env.get_env(), "ACTION_MAIN", "CATEGORY_APP_MUSIC")
+    ///     .start_activity()
+    ///     .unwrap();
+    /// ```
+    pub fn make_main_selector_activity(
+        mut env: AttachGuard<'env>,
+        selector_action: impl AsRef<str>,
+        selector_category: impl AsRef<str>,
+    ) -> Self {
+        let object = (|| {
+            let (action, category) = (selector_action.as_ref(), selector_category.as_ref());
+            let action = backend::static_field(&mut env, INTENT_CLASS, action, "Ljava/lang/String;")?;
+            let category = backend::static_field(&mut env, INTENT_CLASS, category, "Ljava/lang/String;")?;
+
+            env.call_static(
+                INTENT_CLASS,
+                "makeMainSelectorActivity",
+                sig!((String, String) -> Intent),
+                &[(&action).into(), (&category).into()],
+            )?
+            .l()
+        })();
+
+        match object {
+            Ok(object) => Self::from_local(env, object),
+            Err(err) => Self::from_error(err),
+        }
+    }
+
+    /// Build an intent with the static `Intent` factory `factory` taking a `ComponentName`.
+    fn from_component_factory(mut env: AttachGuard<'env>, factory: &str, component: &str) -> Self {
+        let object = backend::component_name(&mut env, component).and_then(|component| {
+            let object = env
+                .call_static(
+                    INTENT_CLASS,
+                    factory,
+                    sig!((ComponentName) -> Intent),
+                    &[(&component).into()],
+                )?
+                .l()?;
+
+            Ok(object)
+        });
+
+        match object {
+            Ok(object) => Self::from_local(env, object),
+            Err(err) => Self::from_error(err),
+        }
     }
 
     /// Add extended data to the intent.
     /// ```no_run
     /// use android_intent::{Action, Extra, Intent};
     ///
-    /// # android_intent::with_current_env(|env| {
+    /// # let mut intent_env = android_intent::IntentEnv::new();
+    /// # android_intent::with_current_env(&mut intent_env, |env| {
     /// let intent = Intent::new(env, Action::Send);
-    /// intent.push_extra(Extra::Text, "Hello World!")
+    /// intent.with_extra(Extra::Text, "Hello World!");
     /// # })
     /// ```
     pub fn with_extra(self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
-        self.and_then(|inner| {
-            let mut inner = inner;
+        let key = key.as_ref();
+        self.and_then_key("with_extra", Some(key), |inner| inner.put_extra(key, &ExtraValue::Str(value.as_ref().to_owned())))
+    }
+
+    /// Add every field of `extras` as an extra, see [`IntentExtras`].
+    pub fn with_struct(self, extras: &impl IntentExtras) -> Self {
+        self.and_then("with_struct", |inner| {
+            for (key, value) in extras.to_extras() {
+                inner.put_extra(&key, &value)?;
+            }
+
+            Ok(())
+        })
+    }
 
-            let key = inner.env.new_string(key)?;
-            let value = inner.env.new_string(value)?;
+    /// Add an `int` extra to the intent.
+    pub fn with_extra_int(self, key: impl AsRef<str>, value: i32) -> Self {
+        let key = key.as_ref();
+        self.and_then_key("with_extra_int", Some(key), |inner| inner.put_extra(key, &ExtraValue::Int(value)))
+    }
+
+    /// Remove the extra stored under `key`, if any.
+    pub fn remove_extra(self, key: impl AsRef<str>) -> Self {
+        self.and_then_key("remove_extra", Some(key.as_ref()), |inner| inner.remove_extra(key.as_ref()))
+    }
+
+    /// Replace all extras with `extras`.
+    pub fn replace_extras(self, extras: &BTreeMap<String, ExtraValue>) -> Self {
+        self.and_then("replace_extras", |inner| inner.replace_extras(extras))
+    }
+
+    /// Replace all extras with a copy of the extras of `source`, e.g. to forward only the
+    /// extras of a template intent and drop untrusted ones.
+    /// ```no_run
+    /// use android_intent::{Action, Extra, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// # let mut received = Intent::new(intent_env.get_env(), Action::Send);
+    /// let mut template = Intent::new(intent_env.get_env(), Action::Send)
+    ///     .with_extra(Extra::Text, received.get::<String>("android.intent.extra.TEXT").unwrap());
+    ///
+    /// let forwarded = received.replace_extras_from(&mut template);
+    /// ```
+    pub fn replace_extras_from(self, source: &mut Intent<'_>) -> Self {
+        let source = match source.inner_mut() {
+            Ok(source) => source,
+            Err(err) => return self.and_then("replace_extras_from", |_| Err(err)),
+        };
+
+        self.and_then("replace_extras_from", |inner| inner.replace_extras_from(source))
+    }
+
+    /// Add any typed extra, e.g. one of the primitive arrays that have no dedicated method.
+    /// ```no_run
+    /// use android_intent::{Action, ExtraValue, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// Intent::new(intent_env.get_env(), Action::View)
+    ///     .with_extra_value("samples", vec![0.5f64, 1.0, 2.0])
+    ///     .with_extra_value("checksum", ExtraValue::ByteArray(vec![0xde, 0xad]));
+    /// ```
+    pub fn with_extra_value(self, key: impl AsRef<str>, value: impl Into<ExtraValue>) -> Self {
+        let value = value.into();
+        self.and_then_key("with_extra_value", Some(key.as_ref()), |inner| inner.put_extra(key.as_ref(), &value))
+    }
+
+    /// Add a `boolean` extra to the intent.
+    pub fn with_extra_bool(self, key: impl AsRef<str>, value: bool) -> Self {
+        let key = key.as_ref();
+        self.and_then_key("with_extra_bool", Some(key), |inner| inner.put_extra(key, &ExtraValue::Bool(value)))
+    }
+
+    /// Add a `Uri` extra to the intent, as a `Parcelable`.
+    pub fn with_extra_uri(self, key: impl AsRef<str>, value: impl Into<Uri>) -> Self {
+        let value = ExtraValue::Uri(value.into());
+        self.and_then_key("with_extra_uri", Some(key.as_ref()), |inner| inner.put_extra(key.as_ref(), &value))
+    }
+
+    /// Add `text` through the `CharSequence` overload of `putExtra`, for receivers that read it
+    /// with `getCharSequenceExtra`.
+    pub fn with_extra_char_sequence(self, key: impl AsRef<str>, text: impl AsRef<str>) -> Self {
+        self.put_char_sequence("with_extra_char_sequence", key.as_ref(), text.as_ref(), false)
+    }
+
+    /// Add `html` as styled text, converted with `Html.fromHtml`. Mock intents store the HTML
+    /// source as a string.
+    pub fn with_extra_html(self, key: impl AsRef<str>, html: impl AsRef<str>) -> Self {
+        self.put_char_sequence("with_extra_html", key.as_ref(), html.as_ref(), true)
+    }
+
+    fn put_char_sequence(self, step: &'static str, key: &str, text: &str, html: bool) -> Self {
+        self.and_then_key(step, Some(key), |inner| match inner.jni() {
+            Some(inner) => {
+                let jkey = inner.env.new_string(key)?;
+                let value = if html {
+                    backend::from_html(&mut inner.env, text)?
+                } else {
+                    inner.env.new_string(text)?.into()
+                };
+                inner.env.call(
+                    &inner.object,
+                    "putExtra",
+                    sig!((String, CharSequence) -> Intent),
+                    &[(&jkey).into(), (&value).into()],
+                )?;
+                Ok(())
+            }
+            None => inner.put_extra(key, &ExtraValue::Str(text.to_owned())),
+        })
+    }
+
+    /// Add a `java.io.Serializable` object, for legacy contracts that pass one. Fails with
+    /// [`Error::UnsupportedExtra`] if `value` isn't serializable. Not available on mock intents.
+    pub fn with_extra_serializable(self, key: impl AsRef<str>, value: &JObject<'_>) -> Self {
+        self.and_then_key("with_extra_serializable", Some(key.as_ref()), |inner| {
+            let inner = inner.jni().ok_or(Error::Unsupported("with_extra_serializable"))?;
+            if !inner.env.is_instance_of(value, "java/io/Serializable")? {
+                return Err(Error::UnsupportedExtra(bundle::class_name(&mut inner.env, value)?));
+            }
+
+            let key = inner.env.new_string(key.as_ref())?;
+            inner.env.call(
+                &inner.object,
+                "putExtra",
+                sig!((String, Serializable) -> Intent),
+                &[(&key).into(), value.into()],
+            )?;
+            Ok(())
+        })
+    }
 
-            inner.env.call_method(
+    /// Add a single `Parcelable` extra, such as a `ComponentName`. Not available on mock intents.
+    pub fn with_extra_parcelable<'a>(self, key: impl AsRef<str>, value: impl Into<Parcelable<'a>>) -> Self {
+        let value = value.into();
+        self.and_then_key("with_extra_parcelable", Some(key.as_ref()), |inner| {
+            let inner = inner.jni().ok_or(Error::Unsupported("with_extra_parcelable"))?;
+            let value = value.to_object(&mut inner.env)?;
+            let key = inner.env.new_string(key.as_ref())?;
+            inner.env.call(
                 &inner.object,
                 "putExtra",
-                "(Ljava/lang/String;Ljava/lang/String;)Landroid/content/Intent;",
+                sig!((String, Parcelable) -> Intent),
                 &[(&key).into(), (&value).into()],
             )?;
+            Ok(())
+        })
+    }
 
-            Ok(inner)
+    /// Add a `Parcelable[]` extra, as used by e.g. `Intent.EXTRA_INITIAL_INTENTS`. Not available
+    /// on mock intents.
+    pub fn with_extra_parcelable_array<'a>(
+        self,
+        key: impl AsRef<str>,
+        values: impl IntoIterator<Item = Parcelable<'a>>,
+    ) -> Self {
+        self.and_then_key("with_extra_parcelable_array", Some(key.as_ref()), |inner| {
+            let inner = inner.jni().ok_or(Error::Unsupported("with_extra_parcelable_array"))?;
+            let values = values
+                .into_iter()
+                .map(|value| value.to_object(&mut inner.env))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let array = inner.env.new_object_array(values.len() as i32, "android/os/Parcelable", JObject::null())?;
+            for (i, value) in values.iter().enumerate() {
+                inner.env.set_object_array_element(&array, i as i32, value)?;
+            }
+
+            let key = inner.env.new_string(key.as_ref())?;
+            inner.env.call(
+                &inner.object,
+                "putExtra",
+                sig!((String, [Parcelable]) -> Intent),
+                &[(&key).into(), (&array).into()],
+            )?;
+            Ok(())
         })
     }
 
+    /// Add an `ArrayList<Parcelable>` extra, as used by e.g. `EXTRA_STREAM` of
+    /// `ACTION_SEND_MULTIPLE`. Not available on mock intents.
+    /// ```no_run
+    /// use android_intent::{Intent, Parcelable, Uri};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// let uris = [Uri::from("content://com.example/1"), Uri::from("content://com.example/2")];
+    /// Intent::new(intent_env.get_env(), "ACTION_SEND_MULTIPLE")
+    ///     .with_extra_parcelable_arraylist("android.intent.extra.STREAM", uris.map(Parcelable::from));
+    /// ```
+    pub fn with_extra_parcelable_arraylist<'a>(
+        self,
+        key: impl AsRef<str>,
+        values: impl IntoIterator<Item = Parcelable<'a>>,
+    ) -> Self {
+        self.and_then_key("with_extra_parcelable_arraylist", Some(key.as_ref()), |inner| {
+            let inner = inner.jni().ok_or(Error::Unsupported("with_extra_parcelable_arraylist"))?;
+            let list = inner.env.new_object("java/util/ArrayList", sig!(() -> ()), &[])?;
+            for value in values {
+                let value = value.to_object(&mut inner.env)?;
+                inner.env.call(&list, "add", sig!((Object) -> boolean), &[(&value).into()])?;
+            }
 
+            let key = inner.env.new_string(key.as_ref())?;
+            inner.env.call(
+                &inner.object,
+                "putParcelableArrayListExtra",
+                sig!((String, ArrayList) -> Intent),
+                &[(&key).into(), (&list).into()],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Add a nested bundle, e.g. app widget options. Not available on mock intents.
+    /// ```no_run
+    /// use android_intent::{Bundle, ExtraValue, IntentSpec};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// let options = [("appWidgetMinWidth".to_owned(), ExtraValue::Int(110))].into();
+    /// let options = Bundle::new(&mut intent_env.get_env(), &options).unwrap();
+    /// IntentSpec { action: Some("android.appwidget.action.APPWIDGET_UPDATE".to_owned()), ..Default::default() }
+    ///     .realize(intent_env.get_env())
+    ///     .with_extra_bundle("appWidgetOptions", &options);
+    /// ```
+    pub fn with_extra_bundle(self, key: impl AsRef<str>, value: &Bundle) -> Self {
+        self.and_then_key("with_extra_bundle", Some(key.as_ref()), |inner| {
+            let inner = inner.jni().ok_or(Error::Unsupported("with_extra_bundle"))?;
+            let key = inner.env.new_string(key.as_ref())?;
+            inner.env.call(
+                &inner.object,
+                "putExtra",
+                sig!((String, Bundle) -> Intent),
+                &[(&key).into(), value.as_obj().into()],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Tell the launched activity who sent it, as `Intent.EXTRA_REFERRER` and
+    /// `EXTRA_REFERRER_NAME`. `referrer` is a URI (e.g. the web page a link was on) or a package
+    /// name, which becomes `android-app://<package>`.
+    pub fn with_referrer(self, referrer: impl AsRef<str>) -> Self {
+        let referrer = referrer.as_ref();
+        let referrer = if referrer.contains(':') {
+            referrer.to_owned()
+        } else {
+            format!("android-app://{}", referrer)
+        };
+
+        self.and_then("with_referrer", |inner| {
+            inner.put_extra(EXTRA_REFERRER, &ExtraValue::Uri(Uri::new(&referrer)))?;
+            inner.put_extra(EXTRA_REFERRER_NAME, &ExtraValue::Str(referrer))
+        })
+    }
 
-    /// Builds a new [`Action::Chooser`] Intent that wraps the given target intent.
+    /// Builds a new [`Action::Chooser`](crate::Action::Chooser) Intent that wraps the given target intent.
     /// ```no_run
     /// use android_intent::{Action, Intent};
     ///
-    /// # android_intent::with_current_env(|env| {
-    /// let intent = Intent::new(env, Action::Send).into_chhoser();
+    /// # let mut intent_env = android_intent::IntentEnv::new();
+    /// # android_intent::with_current_env(&mut intent_env, |env| {
+    /// let intent = Intent::new(env, Action::Send).into_chooser();
     /// # })
     /// ```
     pub fn into_chooser(self) -> Self {
@@ -122,190 +496,1000 @@ impl<'env> Intent<'env> {
     }
 
     pub fn into_chooser_with_title(self, title: Option<impl AsRef<str>>) -> Self {
-        self.and_then(|inner| {
-            let title_value: JValueOwned = if let Some(title) = title {
-                let s = inner.env.new_string(title)?;
-                s.into()
-            } else {
-                JObject::null().into()
-            };
-            let mut inner = inner;
-
-            let intent_class = inner.env.find_class("android/content/Intent")?;
-            let intent = inner.env.call_static_method(
-                &intent_class,
-                "createChooser",
-                "(Landroid/content/Intent;Ljava/lang/CharSequence;)Landroid/content/Intent;",
-                &[(&inner.object).into(), (&title_value).into()],
-            )?;
+        self.and_then("into_chooser", |inner| inner.wrap_in_chooser(title.as_ref().map(AsRef::as_ref)))
+    }
 
-            inner.object = intent.try_into()?;
-            Ok(inner)
-        })
+    /// Like [`Self::into_chooser`], and call `on_chosen` with the flattened component the user
+    /// picked, e.g. for share analytics. Needs API 22 and the [bridge](crate::bridge) activity.
+    ///
+    /// `on_chosen` runs on a background thread, and never if the user cancels; backends other
+    /// than JNI just wrap the intent.
+    /// ```no_run
+    /// use android_intent::{Action, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// Intent::new(intent_env.get_env(), Action::Send)
+    ///     .with_type("text/plain")
+    ///     .into_chooser_with_callback(|component| log::info!("shared via {}", component))
+    ///     .start_activity()
+    ///     .unwrap();
+    /// ```
+    pub fn into_chooser_with_callback(self, on_chosen: impl FnOnce(String) + Send + 'static) -> Self {
+        self.into_chooser_with_request(on_chosen).0
+    }
+
+    /// Like [`Self::into_chooser_with_callback`], with a [`PendingRequest`] to cancel the
+    /// callback, or have it cancelled after a timeout, if the flow is abandoned.
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use android_intent::{Action, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// let (intent, request) = Intent::new(intent_env.get_env(), Action::Send)
+    ///     .with_type("text/plain")
+    ///     .into_chooser_with_request(|component| log::info!("shared via {}", component));
+    /// let _request = request.with_timeout(Duration::from_secs(120));
+    /// intent.start_activity().unwrap();
+    /// ```
+    pub fn into_chooser_with_request(self, on_chosen: impl FnOnce(String) + Send + 'static) -> (Self, PendingRequest) {
+        let mut request = PendingRequest::none();
+        let intent = self.and_then("into_chooser_with_callback", |inner| match inner.jni() {
+            Some(inner) => {
+                request = chooser::wrap_with_callback(inner, Box::new(on_chosen))?;
+                Ok(())
+            }
+            None => inner.wrap_in_chooser(None),
+        });
+
+        (intent, request)
     }
 
     /// Set an explicit MIME data type.
     /// ```no_run
     /// use android_intent::{Action, Intent};
     ///
-    /// # android_intent::with_current_env(|env| {
+    /// # let mut intent_env = android_intent::IntentEnv::new();
+    /// # android_intent::with_current_env(&mut intent_env, |env| {
     /// let intent = Intent::new(env, Action::Send);
-    /// intent.set_type("text/plain");
+    /// intent.with_type("text/plain");
     /// # })
     /// ```
     pub fn with_type(self, type_name: impl AsRef<str>) -> Self {
-        self.and_then(|inner| {
-            let mut inner = inner;
-            let jstring = inner.env.new_string(type_name)?;
+        self.and_then("with_type", |inner| inner.set_type(type_name.as_ref()))
+    }
 
-            inner.env.call_method(
-                &inner.object,
-                "setType",
-                "(Ljava/lang/String;)Landroid/content/Intent;",
-                &[(&jstring).into()],
-            )?;
+    /// The MIME type the intent will be resolved with: the explicit type, or the type of a
+    /// `content:` data URI as reported by its provider.
+    pub fn resolve_type(&mut self) -> Result<Option<String>, Error> {
+        let inner = self.inner_mut()?;
+        match inner.jni() {
+            Some(inner) => crate::mime::resolve_type(&mut inner.env, &inner.object),
+            None => Ok(inner.to_spec()?.mime_type),
+        }
+    }
 
-            Ok(inner)
+    /// How many bytes the extras take when parcelled for the launch, to keep them well under the
+    /// binder transaction limit (about 1 MB, shared by the whole process). See also
+    /// [`set_extras_size_limit`](crate::set_extras_size_limit).
+    /// ```no_run
+    /// use android_intent::{Action, Extra, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// let mut intent = Intent::new(intent_env.get_env(), Action::Send).with_extra(Extra::Text, "hello");
+    /// log::debug!("extras: {} bytes", intent.estimated_extras_size().unwrap());
+    /// ```
+    pub fn estimated_extras_size(&mut self) -> Result<usize, Error> {
+        match self.inner_mut()?.jni() {
+            Some(inner) => parcel_size::extras_size(&mut inner.env, &inner.object),
+            None => Err(Error::Unsupported("estimated_extras_size")),
+        }
+    }
+
+    /// Move `String` and `byte[]` extras longer than `threshold` bytes out of the intent, so large
+    /// generated text doesn't hit the binder transaction limit. Each is written to a file under
+    /// the cache directory's [`SPILL_DIR`](crate::content::SPILL_DIR) and replaced by its URI from
+    /// the app's `FileProvider` at `authority`, whose paths must include
+    /// `<cache-path path="android-intent-spill/" />`. The receiver reads them back with
+    /// [`Self::get_spilled_extra`]; read permission is granted with the intent.
+    ///
+    /// Spilled files are deleted a day later. Mock intents are left as they are.
+    /// ```no_run
+    /// use android_intent::{Action, Extra, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// # let log_dump = String::new();
+    /// Intent::new(intent_env.get_env(), Action::Send)
+    ///     .with_type("text/plain")
+    ///     .with_extra(Extra::Text, log_dump)
+    ///     .spill_large_extras("com.example.app.fileprovider", android_intent::RECOMMENDED_EXTRAS_SIZE_LIMIT / 2)
+    ///     .start_activity()
+    ///     .unwrap();
+    /// ```
+    #[cfg(unix)]
+    pub fn spill_large_extras(self, authority: &str, threshold: usize) -> Self {
+        self.and_then("spill_large_extras", |inner| match inner.jni() {
+            Some(inner) => crate::content::spill_extras(inner, authority, threshold),
+            None => Ok(()),
         })
     }
 
+    /// The extra `key`, read back from its file if the sender moved it out with
+    /// [`Self::spill_large_extras`], or as is otherwise.
+    /// ```no_run
+    /// use android_intent::ExtraValue;
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// # let mut intent = android_intent::Intent::new(intent_env.get_env(), android_intent::Action::Send);
+    /// if let Some(ExtraValue::Str(text)) = intent.get_spilled_extra("android.intent.extra.TEXT").unwrap() {
+    ///     log::info!("received {} bytes of text", text.len());
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn get_spilled_extra(&mut self, key: &str) -> Result<Option<ExtraValue>, Error> {
+        crate::content::read_spilled(self.inner_mut()?, key)
+    }
+
+    /// Set the data URI together with its MIME type, since [`Self::with_type`] alone clears the
+    /// data.
+    pub fn with_data_and_type(self, uri: impl AsRef<str>, type_name: impl AsRef<str>) -> Self {
+        self.and_then("with_data_and_type", |inner| inner.set_data(uri.as_ref(), Some(type_name.as_ref())))
+    }
+
+    /// Restrict the intent to components of `package`.
+    pub fn with_package(self, package: impl AsRef<str>) -> Self {
+        self.and_then("with_package", |inner| inner.set_package(Some(package.as_ref())))
+    }
+
+    /// Tell the launched activity where on screen the user tapped to open it, in screen pixels,
+    /// so it can animate from there.
+    pub fn with_source_bounds(self, left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        self.and_then("with_source_bounds", |inner| inner.set_source_bounds([left, top, right, bottom]))
+    }
+
+    /// Resolve the target with `selector` instead of this intent, while still delivering this
+    /// intent's data and extras, see `Intent.setSelector`.
+    /// ```no_run
+    /// use android_intent::{Action, Intent, IntentSpec};
+    ///
+    /// // Open the link in a browser, never in an app that claims the URL.
+    /// let browser = IntentSpec {
+    ///     action: Some("android.intent.action.VIEW".to_owned()),
+    ///     categories: vec!["android.intent.category.APP_BROWSER".to_owned()],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// # let mut intent_env = android_intent::IntentEnv::new();
+    /// # android_intent::with_current_env(&mut intent_env, |env| {
+    /// Intent::new_with_uri(env, Action::View, "https://example.com")
+    ///     .with_selector(browser)
+    ///     .start_activity()
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub fn with_selector(self, selector: IntentSpec) -> Self {
+        self.and_then("with_selector", |inner| inner.set_selector(&selector))
+    }
+
+    /// Set an identifier that makes this intent distinct from others with the same action, data
+    /// and so on, e.g. for `PendingIntent` matching (API 29+). It doesn't affect resolution.
+    pub fn with_identifier(self, identifier: impl AsRef<str>) -> Self {
+        self.and_then("with_identifier", |inner| inner.set_identifier(identifier.as_ref()))
+    }
+
+    /// The identifier set with [`Self::with_identifier`], if any.
+    pub fn identifier(&mut self) -> Result<Option<String>, Error> {
+        Ok(self.inner_mut()?.to_spec()?.identifier)
+    }
+
     pub fn add_flags(self, flags: Flags) -> Self {
-        self.and_then(|inner| {
-            let mut inner = inner;
+        self.and_then("add_flags", |inner| inner.add_flags(flags))
+    }
 
-            let mut jflags: jint = 0;
+    /// Remove flags from the intent. Does nothing below API 26, where `removeFlags` doesn't exist.
+    pub fn remove_flags(self, flags: Flags) -> Self {
+        self.and_then("remove_flags", |inner| inner.remove_flags(flags))
+    }
 
-            for (flag, _) in flags.iter_names() {
-                let flag_val = Self::get_static_field_val(&mut inner.env, &format!("FLAG_{}", flag), "I")?;
-                let jflag_val: jint = flag_val.i().unwrap();
-                jflags |= jflag_val;
-            }
+    /// Start the activity in a new task, see [`Flags::ACTIVITY_NEW_TASK`].
+    pub fn new_task(self) -> Self {
+        self.add_flags(Flags::ACTIVITY_NEW_TASK)
+    }
 
-            inner.env.call_method(
-                &inner.object,
-                "addFlags",
-                "(I)Landroid/content/Intent;",
-                &[jflags.into()],
-            )?;
+    /// Bring an existing instance of the activity to the front, finishing the activities above
+    /// it, see [`Flags::ACTIVITY_CLEAR_TOP`].
+    pub fn clear_top(self) -> Self {
+        self.add_flags(Flags::ACTIVITY_CLEAR_TOP)
+    }
 
-            Ok(inner)
-        })
+    /// Reuse the activity if it is already on top, see [`Flags::ACTIVITY_SINGLE_TOP`].
+    pub fn single_top(self) -> Self {
+        self.add_flags(Flags::ACTIVITY_SINGLE_TOP)
+    }
+
+    /// Don't keep the activity in the back stack once the user leaves it, see
+    /// [`Flags::ACTIVITY_NO_HISTORY`].
+    pub fn no_history(self) -> Self {
+        self.add_flags(Flags::ACTIVITY_NO_HISTORY)
+    }
+
+    /// Keep the new task out of the recent apps list, see
+    /// [`Flags::ACTIVITY_EXCLUDE_FROM_RECENTS`].
+    pub fn exclude_from_recents(self) -> Self {
+        self.add_flags(Flags::ACTIVITY_EXCLUDE_FROM_RECENTS)
+    }
+
+    /// Let the receiver read the data URI and `content:` URIs in extras, see
+    /// [`Flags::GRANT_READ_URI_PERMISSION`].
+    pub fn grant_read(self) -> Self {
+        self.add_flags(Flags::GRANT_READ_URI_PERMISSION)
+    }
+
+    /// Let the receiver write to the data URI and `content:` URIs in extras, see
+    /// [`Flags::GRANT_WRITE_URI_PERMISSION`].
+    pub fn grant_write(self) -> Self {
+        self.add_flags(Flags::GRANT_WRITE_URI_PERMISSION)
+    }
+
+    /// Open the activity next to this one when in split-screen, see [`is_in_multi_window`].
+    ///
+    /// Sets [`Flags::ACTIVITY_LAUNCH_ADJACENT`], which only takes effect together with
+    /// [`Flags::ACTIVITY_NEW_TASK`] and while this activity is in multi-window mode; otherwise the
+    /// activity opens normally in a new task. The flag is skipped below API 24.
+    pub fn launch_adjacent(self) -> Self {
+        self.add_flags(Flags::ACTIVITY_LAUNCH_ADJACENT | Flags::ACTIVITY_NEW_TASK)
     }
 
     pub fn add_category(self, category: impl AsRef<str>) -> Self {
-        self.and_then(|inner| {
-            let mut inner = inner;
+        self.and_then("add_category", |inner| inner.add_category(category.as_ref()))
+    }
 
-            let jcategory = Self::get_static_field_val(&mut inner.env, category.as_ref(), "Ljava/lang/String;")?;
+    /// Whether the intent has `category`, e.g. [`Category::Openable`](crate::Category).
+    pub fn has_category(&mut self, category: impl AsRef<str>) -> Result<bool, Error> {
+        self.inner_mut()?.has_category(category.as_ref())
+    }
 
-            inner.env.call_method(
-                &inner.object,
-                "addCategory",
-                "(Ljava/lang/String;)Landroid/content/Intent;",
-                &[(&jcategory).into()],
-            )?;
+    pub fn remove_category(self, category: impl AsRef<str>) -> Self {
+        self.and_then("remove_category", |inner| inner.remove_category(category.as_ref()))
+    }
 
-            Ok(inner)
-        })
+    /// All categories of the intent, as their values such as
+    /// `android.intent.category.LAUNCHER`.
+    pub fn categories(&mut self) -> Result<Vec<String>, Error> {
+        Ok(self.inner_mut()?.to_spec()?.categories)
+    }
+
+    /// Surface an error from an earlier builder step now instead of at launch.
+    /// ```no_run
+    /// use android_intent::{Action, Extra, Intent};
+    ///
+    /// # let mut intent_env = android_intent::IntentEnv::new();
+    /// # android_intent::with_current_env(&mut intent_env, |env| {
+    /// let intent = Intent::new(env, Action::Send)
+    ///     .with_type("text/plain")
+    ///     .check()
+    ///     .expect("ACTION_SEND exists");
+    /// # })
+    /// ```
+    pub fn check(self) -> Result<Self, Error> {
+        match self.inner {
+            Ok(inner) => Ok(Self { inner: Ok(inner) }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// [`Self::with_extra`], failing fast if this or an earlier step failed.
+    pub fn try_with_extra(self, key: impl AsRef<str>, value: impl AsRef<str>) -> Result<Self, Error> {
+        self.with_extra(key, value).check()
+    }
+
+    /// [`Self::with_extra_int`], failing fast if this or an earlier step failed.
+    pub fn try_with_extra_int(self, key: impl AsRef<str>, value: i32) -> Result<Self, Error> {
+        self.with_extra_int(key, value).check()
+    }
+
+    /// [`Self::with_struct`], failing fast if this or an earlier step failed.
+    pub fn try_with_struct(self, extras: &impl IntentExtras) -> Result<Self, Error> {
+        self.with_struct(extras).check()
+    }
+
+    /// [`Self::with_type`], failing fast if this or an earlier step failed.
+    pub fn try_with_type(self, type_name: impl AsRef<str>) -> Result<Self, Error> {
+        self.with_type(type_name).check()
+    }
+
+    /// [`Self::add_flags`], failing fast if this or an earlier step failed.
+    pub fn try_add_flags(self, flags: Flags) -> Result<Self, Error> {
+        self.add_flags(flags).check()
+    }
+
+    /// [`Self::add_category`], failing fast if this or an earlier step failed.
+    pub fn try_add_category(self, category: impl AsRef<str>) -> Result<Self, Error> {
+        self.add_category(category).check()
+    }
+
+    /// [`Self::into_chooser_with_title`], failing fast if this or an earlier step failed.
+    pub fn try_into_chooser_with_title(self, title: Option<impl AsRef<str>>) -> Result<Self, Error> {
+        self.into_chooser_with_title(title).check()
     }
 
     pub fn start_activity(self) -> Result<Self, Error> {
         debug!("start_activity");
 
-        let cx = ndk_context::android_context();
-        let activity = unsafe { JObject::from_raw(cx.context() as jni::sys::jobject) };
-
-        Ok(self.and_then(|inner| {
-            let mut inner = inner;
+        Ok(self.launch(None))
+    }
 
-            inner.env.call_method(
-                activity,
-                "startActivity",
-                "(Landroid/content/Intent;)V",
-                &[(&inner.object).into()],
-            )?;
+    /// Start the activity unless a filter-equal intent (same action, data, type, package,
+    /// component, categories and identifier; extras and flags don't count) was started this way
+    /// within the last `window`, e.g. by a double tap on a share button. Returns `None` if the
+    /// launch was suppressed.
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use android_intent::{Action, Extra, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// Intent::new(intent_env.get_env(), Action::Send)
+    ///     .with_type("text/plain")
+    ///     .with_extra(Extra::Text, "Hello World!")
+    ///     .into_chooser()
+    ///     .start_activity_debounced(Duration::from_millis(800))
+    ///     .unwrap();
+    /// ```
+    pub fn start_activity_debounced(mut self, window: Duration) -> Result<Option<Self>, Error> {
+        // An intent that failed to build launches straight away, to report its error.
+        if let Ok(spec) = self.to_spec() {
+            if !debounce::admit(&spec, window) {
+                debug!("suppressing a repeated launch within {:?}", window);
+                return Ok(None);
+            }
+        }
 
-            Ok(inner)
-        }))
+        self.start_activity().map(Some)
     }
 
-    pub fn start_activity_for_result(self, request_code: i32) -> Result<Self, Error> {
+    /// Start the activity, with its result arriving through [`Self::get_result`] under
+    /// `request_code`. Fails with [`Error::RequestCodeInUse`] while another launch with the same
+    /// code waits for its result, see [`requests`](crate::requests).
+    #[cfg_attr(not(feature = "result-bridge"), allow(unused_mut))]
+    pub fn start_activity_for_result(mut self, request_code: i32) -> Result<Self, Error> {
         debug!("start_activity_for_result: {}", request_code);
 
-        let cx = ndk_context::android_context();
-        let activity = unsafe { JObject::from_raw(cx.context() as jni::sys::jobject) };
+        #[cfg(feature = "result-bridge")]
+        let tracked = self.reserve_request(request_code)?;
+        let intent = self.launch(Some(request_code));
+        #[cfg(feature = "result-bridge")]
+        let mut intent = intent;
+        #[cfg(feature = "result-bridge")]
+        if intent.inner.is_ok() {
+            crate::pending_results::persist(&mut intent, request_code);
+        }
+        #[cfg(feature = "result-bridge")]
+        if tracked {
+            intent.finish_request(request_code);
+        }
 
-        let jcode: jint = request_code.into();
+        Ok(intent)
+    }
 
-        Ok(self.and_then(|inner| {
-            let mut inner = inner;
+    /// [`Self::start_activity_for_result`] under the request code of `key`, so the result goes
+    /// to the callback [registered](crate::requests::register) for it, even after the activity
+    /// was recreated, see [`requests`](crate::requests#keyed-callbacks).
+    #[cfg(feature = "result-bridge")]
+    pub fn start_activity_for_key(self, key: &str) -> Result<Self, Error> {
+        self.start_activity_for_result(crate::requests::request_code_for(key))
+    }
 
-            inner.env.call_method(
-                activity,
-                "startActivityForResult",
-                "(Landroid/content/Intent;I)V",
-                &[(&inner.object).into(), jcode.into()],
-            )?;
+    /// Claim `request_code` in [`requests`](crate::requests), for real intents only: mocks never
+    /// receive results. Returns whether it was claimed.
+    #[cfg(feature = "result-bridge")]
+    fn reserve_request(&mut self, request_code: i32) -> Result<bool, Error> {
+        if self.inner.as_mut().ok().and_then(|inner| inner.jni()).is_none() {
+            return Ok(false);
+        }
+
+        crate::requests::reserve(request_code)?;
+        Ok(true)
+    }
+
+    /// Record the launch of a reserved request, or release the code if it failed.
+    #[cfg(feature = "result-bridge")]
+    fn finish_request(&mut self, request_code: i32) {
+        match self.inner.is_ok() {
+            true => crate::requests::started(request_code, self.to_spec().ok()),
+            false => {
+                crate::requests::forget(request_code);
+            }
+        }
+    }
+
+    /// Start the activity on the activity's UI thread instead of the calling one, for launching
+    /// from worker threads. The returned [`UiLaunch`] reports the outcome once the UI thread ran
+    /// the launch.
+    ///
+    /// The activity has to provide the `startActivityOnUiThread` bridge method, see
+    /// [`bridge`](crate::bridge).
+    pub fn start_activity_on_ui_thread(self) -> Result<UiLaunch, Error> {
+        self.launch_on_ui_thread(None)
+    }
+
+    /// [`Self::start_activity_for_result`] on the UI thread, see
+    /// [`Self::start_activity_on_ui_thread`].
+    #[cfg_attr(not(feature = "result-bridge"), allow(unused_mut))]
+    pub fn start_activity_for_result_on_ui_thread(mut self, request_code: i32) -> Result<UiLaunch, Error> {
+        #[cfg(feature = "result-bridge")]
+        if self.reserve_request(request_code)? {
+            crate::requests::started(request_code, self.to_spec().ok());
+            let launch = self.launch_on_ui_thread(Some(request_code));
+            if launch.is_err() {
+                crate::requests::forget(request_code);
+            }
+            return launch;
+        }
+
+        self.launch_on_ui_thread(Some(request_code))
+    }
+
+    fn launch_on_ui_thread(self, request_code: Option<i32>) -> Result<UiLaunch, Error> {
+        let mut launch = None;
+
+        // The observer hears about the launch once the UI thread ran it, not when it was posted.
+        let _launched = self.launch_observed(request_code, false, |inner, request_code| {
+            let spec = match observe::is_observed() {
+                true => inner.to_spec().ok(),
+                false => None,
+            };
+            launch = Some(match inner.jni() {
+                Some(jni) => ui_thread::post_start_activity(&mut jni.env, &jni.object, request_code, spec)?,
+                // A mock has no UI thread to post to.
+                None => {
+                    let outcome = inner.start_activity(request_code);
+                    if let Some(spec) = &spec {
+                        observe::notify(spec, outcome.as_ref().copied());
+                    }
+                    UiLaunch::done(outcome)
+                }
+            });
+            Ok(())
+        })
+        .check()?;
+
+        Ok(launch.expect("launch succeeded without posting"))
+    }
 
-            Ok(inner)
+    /// Start the activity, and if nothing can handle it, retry once with the data replaced by
+    /// `fallback_uri` and without a target package. Used to open e.g. `market://` links in a
+    /// browser when no store app is installed.
+    pub(crate) fn start_activity_or_uri(self, fallback_uri: &str) -> Result<Self, Error> {
+        Ok(self.launch_with(None, |inner, request_code| match Self::start_checked(inner, request_code) {
+            Err(Error::Jni(jni::errors::Error::JavaException)) if Self::clear_activity_not_found(inner)? => {
+                debug!("no activity found, falling back to {}", fallback_uri);
+
+                inner.set_package(None)?;
+                inner.set_data(fallback_uri, None)?;
+                inner.start_activity(request_code)
+            }
+            result => result,
         }))
     }
 
-    pub fn get_result(&mut self, env: AttachGuard<'env>) -> Result<Option<CompletedIntent<'env>>, Error> {
-        debug!("get_result for intent");
+    /// Start the activity, and if nothing can handle it, offer it through a chooser instead,
+    /// which tells the user so rather than throwing `ActivityNotFoundException`.
+    /// ```no_run
+    /// use android_intent::{Action, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// Intent::new_with_uri(intent_env.get_env(), Action::View, "geo:0,0?q=Oslo")
+    ///     .start_activity_or_chooser()
+    ///     .unwrap();
+    /// ```
+    pub fn start_activity_or_chooser(self) -> Result<Self, Error> {
+        Ok(self.launch_with(None, |inner, request_code| match Self::start_checked(inner, request_code) {
+            Err(Error::Jni(jni::errors::Error::JavaException)) if Self::clear_activity_not_found(inner)? => {
+                debug!("no activity found, falling back to a chooser");
+
+                inner.wrap_in_chooser(None)?;
+                inner.start_activity(request_code)
+            }
+            result => result,
+        }))
+    }
 
-        let cx = ndk_context::android_context();
-        let activity = unsafe { JObject::from_raw(cx.context() as jni::sys::jobject) };
+    /// Start the activity, and if nothing can handle it, start `fallback` instead, e.g. the
+    /// store page of the app that would. Returns the intent that was started.
+    pub fn start_activity_or(self, fallback: Intent<'env>) -> Result<Self, Error> {
+        let mut not_found = false;
+        let intent = self.launch_with(None, |inner, request_code| match Self::start_checked(inner, request_code) {
+            Err(Error::Jni(jni::errors::Error::JavaException)) if Self::clear_activity_not_found(inner)? => {
+                not_found = true;
+                Ok(())
+            }
+            result => result,
+        });
+
+        if not_found {
+            debug!("no activity found, starting the fallback intent");
+            return fallback.start_activity();
+        }
+        Ok(intent)
+    }
 
-        let inner = match &mut self.inner {
-            Ok(inner) => inner,
-            Err(err) => return Ok(None),
+    /// Whether the pending Java exception is an `ActivityNotFoundException`, which is then
+    /// cleared. Other exceptions stay pending.
+    fn clear_activity_not_found(inner: &mut dyn IntentBackend<'env>) -> Result<bool, Error> {
+        let Some(jni) = inner.jni() else {
+            return Ok(false);
         };
 
-        let jobj = inner.env.call_method(
-            activity,
-            "getNextIntentResult",
-            "()Lcom/example/libnumistracker/RustNativeIntentResult;",
-            &[],
-        )?;
+        // Only a few JNI functions may run with an exception pending, so clear it before
+        // inspecting it, and rethrow anything else.
+        let exception = jni.env.exception_occurred()?;
+        jni.env.exception_clear()?;
+        if !jni.env.is_instance_of(&exception, "android/content/ActivityNotFoundException")? {
+            jni.env.throw(exception)?;
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    fn launch(self, request_code: Option<i32>) -> Self {
+        self.launch_with(request_code, Self::start_checked)
+    }
+
+    /// Start the activity on this thread, after the strict mode thread check.
+    fn start_checked(inner: &mut dyn IntentBackend<'env>, request_code: Option<i32>) -> Result<(), Error> {
+        if let Some(jni) = inner.jni() {
+            ui_thread::check_launch_thread(&mut jni.env)?;
+        }
+
+        inner.start_activity(request_code)
+    }
+
+    fn launch_with(
+        self,
+        request_code: Option<i32>,
+        start: impl FnOnce(&mut dyn IntentBackend<'env>, Option<i32>) -> Result<(), Error>,
+    ) -> Self {
+        self.launch_observed(request_code, true, start)
+    }
+
+    /// [`Self::launch_with`], but only reporting a successful `start` to the observer if
+    /// `observe_started`; otherwise `start` reports the outcome itself once it is known.
+    fn launch_observed(
+        self,
+        request_code: Option<i32>,
+        observe_started: bool,
+        start: impl FnOnce(&mut dyn IntentBackend<'env>, Option<i32>) -> Result<(), Error>,
+    ) -> Self {
+        #[cfg(feature = "record")]
+        if let Err(err) = &self.inner {
+            crate::record::record(None, request_code, Err(err));
+        }
+
+        self.and_then("start_activity", |inner| {
+            let spec = match cfg!(feature = "record") || observe::is_observed() {
+                true => inner.to_spec().ok(),
+                false => None,
+            };
+
+            let result = parcel_size::check(inner).and_then(|()| start(inner, request_code));
+
+            if let Some(spec) = &spec {
+                if observe_started || result.is_err() {
+                    observe::notify(spec, result.as_ref().copied());
+                }
+            }
+            #[cfg(feature = "record")]
+            crate::record::record(spec, request_code, result.as_ref().copied());
+
+            result
+        })
+    }
+
+    /// The next activity result the app-side [bridge](crate::bridge) queued up, if any.
+    ///
+    /// Needs the `result-bridge` feature (on by default). Apps that deliver results through their
+    /// own glue can build [`CompletedIntent`] themselves instead.
+    #[cfg(feature = "result-bridge")]
+    pub fn get_result(&mut self, env: AttachGuard<'env>) -> Result<Option<CompletedIntent<'env>>, Error> {
+        debug!("get_result for intent");
+
+        let inner = match self.inner.as_mut().ok().and_then(|inner| inner.jni()) {
+            Some(inner) => inner,
+            None => return Ok(None),
+        };
+        let jobj = trace::checked(&mut inner.env, |env| {
+            let activity = context::activity(env)?;
+            crate::host::with_host(env, &activity, |host, env| host.next_result(env, &activity))
+        })?;
 
         debug!("  completed get_result call");
 
-        let jobj = jobj.l().unwrap();
         if jobj.is_null() {
             debug!("  got null result");
             return Ok(None);
         }
 
-        let jreq_code = inner.env.get_field(&jobj, "requestCode", "I")?;
-        let jres_code = inner.env.get_field(&jobj, "resultCode", "I")?;
-        let jdata = inner.env.get_field(&jobj, "data", "Landroid/content/Intent;")?;
+        let mut completed =
+            CompletedIntent::from_result(env, &jobj).map_err(|err| trace::clear_exception(&mut inner.env, err))?;
+        debug!(
+            "  got non-null result, request_code={}, result_code={}",
+            completed.request_code, completed.result_code
+        );
+        crate::requests::complete(&mut completed);
 
-        let jdata_obj = jdata.l().unwrap();
+        Ok(Some(completed))
+    }
 
-        let intent = Intent::from_object(env, jdata_obj);
-        let request_code: i32 = jreq_code.i().unwrap().into();
-        let result_code: i32 = jres_code.i().unwrap().into();
+    /// All extras of the intent, keyed by name.
+    /// ```no_run
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// # let mut intent = android_intent::Intent::new(intent_env.get_env(), android_intent::Action::Send);
+    /// for (key, value) in intent.extras_map().unwrap() {
+    ///     log::info!("{key} = {value:?}");
+    /// }
+    /// ```
+    pub fn extras_map(&mut self) -> Result<BTreeMap<String, ExtraValue>, Error> {
+        self.inner_mut()?.extras()
+    }
 
-        debug!("  got non-null result, request_code={}, result_code={}", request_code, result_code);
-        return Ok(Some(CompletedIntent {
-            request_code,
-            result_code,
-            data: intent,
-        }));
+    /// The intent's extras as a live [`Bundle`], or `None` if it has none. Not
+    /// available on mock intents, use [`Self::extras_map`] there.
+    pub fn extras_bundle(&mut self) -> Result<Option<Bundle>, Error> {
+        let inner = self.jni("extras_bundle")?;
+        let extras = inner
+            .env
+            .call(&inner.object, "getExtras", sig!(() -> Bundle), &[])?
+            .l()?;
+        if extras.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(Bundle::from_object(&mut inner.env, &extras)?))
+    }
+
+    /// The `java.io.Serializable` extra stored under `key`, or `None` if there is none or it isn't
+    /// an instance of `class` (dotted, e.g. `java.util.HashMap`). Uses the type-safe overload on
+    /// API 33+. Not available on mock intents.
+    pub fn get_serializable_extra(&mut self, key: &str, class: &str) -> Result<Option<JObject<'env>>, Error> {
+        let inner = self.jni("get_serializable_extra")?;
+        let class = context::load_class(&mut inner.env, class)?;
+        let jkey = inner.env.new_string(key)?;
+
+        let value = if compat::supports_typed_extras(api_level::sdk_int_with(&mut inner.env)?) {
+            inner
+                .env
+                .call(
+                    &inner.object,
+                    "getSerializableExtra",
+                    sig!((String, Class) -> Serializable),
+                    &[(&jkey).into(), (&class).into()],
+                )?
+                .l()?
+        } else {
+            let value = inner
+                .env
+                .call(
+                    &inner.object,
+                    "getSerializableExtra",
+                    sig!((String) -> Serializable),
+                    &[(&jkey).into()],
+                )?
+                .l()?;
+            if !value.is_null() && !inner.env.is_instance_of(&value, &class)? {
+                return Ok(None);
+            }
+            value
+        };
+
+        Ok(if value.is_null() { None } else { Some(value) })
+    }
+
+    /// The elements of the `Parcelable[]` extra stored under `key`, or `None` if there is none. Not
+    /// available on mock intents.
+    pub fn get_parcelable_array_extra(&mut self, key: &str) -> Result<Option<Vec<JObject<'env>>>, Error> {
+        let inner = self.jni("get_parcelable_array_extra")?;
+        let jkey = inner.env.new_string(key)?;
+        let array = inner
+            .env
+            .call(
+                &inner.object,
+                "getParcelableArrayExtra",
+                sig!((String) -> [Parcelable]),
+                &[(&jkey).into()],
+            )?
+            .l()?;
+        if array.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(bundle::object_array(&mut inner.env, array.into())?))
+    }
+
+    /// The elements of the `ArrayList<Parcelable>` extra stored under `key`, or `None` if there is
+    /// none. Not available on mock intents.
+    pub fn get_parcelable_arraylist_extra(&mut self, key: &str) -> Result<Option<Vec<JObject<'env>>>, Error> {
+        let inner = self.jni("get_parcelable_arraylist_extra")?;
+        let jkey = inner.env.new_string(key)?;
+        let list = inner
+            .env
+            .call(
+                &inner.object,
+                "getParcelableArrayListExtra",
+                sig!((String) -> ArrayList),
+                &[(&jkey).into()],
+            )?
+            .l()?;
+        if list.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(bundle::collection(&mut inner.env, &list)?))
+    }
+
+    /// The extra stored under `key` as text, or `None` if there is none. Unlike [`Self::get`],
+    /// this also reads styled `CharSequence`s, dropping their styling.
+    pub fn get_char_sequence_extra(&mut self, key: &str) -> Result<Option<String>, Error> {
+        let inner = match self.inner_mut()?.jni() {
+            Some(inner) => inner,
+            None => return self.get(key),
+        };
+
+        let jkey = inner.env.new_string(key)?;
+        let text = inner
+            .env
+            .call(
+                &inner.object,
+                "getCharSequenceExtra",
+                sig!((String) -> CharSequence),
+                &[(&jkey).into()],
+            )?
+            .l()?;
+        if text.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(bundle::to_string(&mut inner.env, &text)?))
+    }
+
+    /// The nested bundle stored under `key`, or `None` if there is none. Not available on mock
+    /// intents, where [`Self::get`] reads it as a map.
+    pub fn get_bundle_extra(&mut self, key: &str) -> Result<Option<Bundle>, Error> {
+        let inner = self.jni("get_bundle_extra")?;
+        let jkey = inner.env.new_string(key)?;
+        let bundle = inner
+            .env
+            .call(&inner.object, "getBundleExtra", sig!((String) -> Bundle), &[(&jkey).into()])?
+            .l()?;
+        if bundle.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(Bundle::from_object(&mut inner.env, &bundle)?))
+    }
+
+    /// Read the extra stored under `key` as `T`, failing with a descriptive error if it is
+    /// missing or has another type. Ask for an `Option<T>` to accept a missing extra.
+    /// ```no_run
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// # let mut intent = android_intent::Intent::new(intent_env.get_env(), android_intent::Action::Send);
+    /// let text: String = intent.get("android.intent.extra.TEXT").unwrap();
+    /// let page = intent.get::<Option<i32>>("page").unwrap().unwrap_or(1);
+    /// ```
+    pub fn get<T: FromIntent>(&mut self, key: &str) -> Result<T, Error> {
+        let value = self.inner_mut()?.extra(key)?;
+        T::from_extra(key, value.as_ref())
+    }
+
+    /// The equivalent `adb shell am start` command, for reproducing this intent from a shell.
+    /// Extras that `am` cannot express (bundles, parcelables, empty string arrays, ...) are left out.
+    /// ```no_run
+    /// use android_intent::{Action, Extra, Intent};
+    ///
+    /// # let mut intent_env = android_intent::IntentEnv::new();
+    /// # android_intent::with_current_env(&mut intent_env, |env| {
+    /// let mut intent = Intent::new(env, Action::Send)
+    ///     .with_type("text/plain")
+    ///     .with_extra(Extra::Text, "Hello World!");
+    /// // am start -a android.intent.action.SEND -t text/plain --es android.intent.extra.TEXT 'Hello World!'
+    /// log::debug!("{}", intent.to_am_command().unwrap());
+    /// # })
+    /// ```
+    pub fn to_am_command(&mut self) -> Result<String, Error> {
+        Ok(self.to_spec()?.to_am_command())
+    }
+
+    /// The explicit target of the intent, via `Intent.getComponent`, or `None` if it is implicit.
+    pub fn get_component(&mut self) -> Result<Option<ComponentName>, Error> {
+        let component = match self.inner_mut()?.jni() {
+            Some(inner) => {
+                let component = inner
+                    .env
+                    .call(&inner.object, "getComponent", sig!(() -> ComponentName), &[])?
+                    .l()?;
+                if component.is_null() {
+                    return Ok(None);
+                }
+                backend::string_getter(&mut inner.env, &component, "flattenToString")?
+            }
+            None => self.to_spec()?.component,
+        };
+
+        Ok(component.as_deref().and_then(ComponentName::unflatten))
+    }
+
+    /// The package the intent is limited to, via `Intent.getPackage`, or `None` if any app may
+    /// handle it.
+    pub fn get_package(&mut self) -> Result<Option<String>, Error> {
+        match self.inner_mut()?.jni() {
+            Some(inner) => backend::string_getter(&mut inner.env, &inner.object, "getPackage"),
+            None => Ok(self.to_spec()?.package),
+        }
     }
 
-    fn and_then(mut self, f: impl FnOnce(Inner) -> Result<Inner, Error>) -> Self {
+    /// Summarize the intent without launching it, see [`IntentDescription`].
+    /// ```no_run
+    /// use android_intent::{Action, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// let mut intent = Intent::new(intent_env.get_env(), Action::View).new_task();
+    /// log::debug!("{}", intent.describe().unwrap());
+    /// ```
+    pub fn describe(&mut self) -> Result<IntentDescription, Error> {
+        Ok(self.to_spec()?.describe())
+    }
+
+    /// Check the intent for common mistakes before launching it, see [`IntentSpec::validate`].
+    /// On a real intent, `ACTION_CALL` is only reported if `CALL_PHONE` isn't granted.
+    /// ```no_run
+    /// use android_intent::{Action, Intent};
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// let mut intent =
+    ///     Intent::new(intent_env.get_env(), Action::Send).with_extra_uri("android.intent.extra.STREAM", "content://com.example/1");
+    /// for diagnostic in intent.validate().unwrap() {
+    ///     log::warn!("share intent: {}", diagnostic);
+    /// }
+    /// ```
+    pub fn validate(&mut self) -> Result<Vec<Diagnostic>, Error> {
+        let spec = self.to_spec()?;
+        let mut diagnostics = spec.validate();
+
+        if let Some(inner) = self.inner_mut()?.jni() {
+            if spec.action.as_deref() == Some(validate::ACTION_CALL) {
+                let permission = inner.env.new_string("android.permission.CALL_PHONE")?;
+                let context = context::activity(&mut inner.env)?;
+                let granted = inner
+                    .env
+                    .call(
+                        context,
+                        "checkCallingOrSelfPermission",
+                        sig!((String) -> int),
+                        &[(&permission).into()],
+                    )?
+                    .i()?;
+                if granted == 0 {
+                    diagnostics.retain(|diagnostic| *diagnostic != Diagnostic::CallWithoutPermission);
+                }
+            }
+        }
+
+        Ok(diagnostics)
+    }
+
+    /// Snapshot the intent as an [`IntentSpec`].
+    pub fn to_spec(&mut self) -> Result<IntentSpec, Error> {
+        self.inner_mut()?.to_spec()
+    }
+
+    fn inner_mut(&mut self) -> Result<&mut (dyn IntentBackend<'env> + 'env), Error> {
+        match &mut self.inner {
+            Ok(inner) => Ok(inner.as_mut()),
+            Err(_) => Err(Error::InvalidIntent),
+        }
+    }
+
+    /// The JNI backend, or [`Error::Unsupported`] naming `operation` on a mock intent.
+    pub(crate) fn jni(&mut self, operation: &'static str) -> Result<&mut Inner<'env>, Error> {
+        self.inner_mut()?.jni().ok_or(Error::Unsupported(operation))
+    }
+
+    fn and_then(self, step: &'static str, f: impl FnOnce(&mut dyn IntentBackend<'env>) -> Result<(), Error>) -> Self {
+        self.and_then_key(step, None, f)
+    }
+
+    /// [`Self::and_then`] for a step on the extra `key`, which a failure reports.
+    fn and_then_key(
+        mut self,
+        step: &'static str,
+        key: Option<&str>,
+        f: impl FnOnce(&mut dyn IntentBackend<'env>) -> Result<(), Error>,
+    ) -> Self {
+        let _span = trace::step(step);
+
         self.inner = match self.inner {
-            Ok(inner) => f(inner),
+            Ok(mut inner) => match f(inner.as_mut()) {
+                Ok(()) => Ok(inner),
+                Err(err) => Err(Self::step_error(inner.as_mut(), step, key, err)),
+            },
             Err(err) => Err(err),
         };
         self
     }
+
+    /// `err` with the failed `step` as context, and a pending Java exception described.
+    fn step_error(inner: &mut dyn IntentBackend<'env>, step: &str, key: Option<&str>, err: Error) -> Error {
+        let err = match (err, inner.jni()) {
+            (err, Some(jni)) => trace::clear_exception(&mut jni.env, err),
+            (err, None) => err,
+        };
+        let step = match key {
+            Some(key) => format!("{}(key={})", step, key),
+            None => step.to_owned(),
+        };
+
+        Error::Step { step, source: Box::new(err) }
+    }
 }
 
+impl fmt::Debug for Intent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.inner {
+            Ok(inner) => f.write_str(&inner.describe()),
+            Err(err) => write!(f, "Intent(<error: {}>)", err),
+        }
+    }
+}
+
+/// Whether this activity is shown in multi-window mode, via `Activity.isInMultiWindowMode`.
+/// Always `false` below API 24.
+pub fn is_in_multi_window(env: &mut JNIEnv) -> Result<bool, Error> {
+    if !ApiLevel(24).is_supported(env)? {
+        return Ok(false);
+    }
+
+    let activity = context::activity(env)?;
+    let in_multi_window = env.call(activity, "isInMultiWindowMode", sig!(() -> boolean), &[])?.z()?;
+
+    Ok(in_multi_window)
+}
+
+/// `Activity.RESULT_OK`
+pub const RESULT_OK: i32 = -1;
+/// `Activity.RESULT_CANCELED`
+pub const RESULT_CANCELED: i32 = 0;
+
+#[derive(Debug)]
 pub struct CompletedIntent<'env> {
     pub request_code: i32,
     pub result_code: i32,
     pub data: Intent<'env>,
+}
+
+impl<'env> CompletedIntent<'env> {
+    /// Read a result the [bridge](crate::bridge) queued up.
+    #[cfg(feature = "result-bridge")]
+    pub(crate) fn from_result(mut env: AttachGuard<'env>, result: &JObject) -> Result<Self, Error> {
+        let request_code = env.get_field(result, "requestCode", "I")?.i()?;
+        let result_code = env.get_field(result, "resultCode", "I")?.i()?;
+        let data = env.get_field(result, "data", "Landroid/content/Intent;")?.l()?;
+
+        Ok(Self { request_code, result_code, data: Intent::from_local(env, data) })
+    }
+
+    /// Whether the activity finished with [`RESULT_OK`].
+    pub fn is_ok(&self) -> bool {
+        self.result_code == RESULT_OK
+    }
+
+    /// Read the result data's extra stored under `key`, see [`Intent::get`].
+    pub fn get<T: FromIntent>(&mut self, key: &str) -> Result<T, Error> {
+        self.data.get(key)
+    }
+
+    /// Read the result data's extras into `T`, see [`IntentExtras`].
+    pub fn parse<T: IntentExtras>(&mut self) -> Result<T, Error> {
+        T::from_extras(&self.data.extras_map()?)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,77 @@
+//! Counting the JNI references held by this crate's wrappers, to catch reference leaks in
+//! long-running loops.
+//!
+//! Each JNI-backed [`Intent`](crate::Intent) holds a local reference, deleted when it is dropped,
+//! and each [`Bundle`](crate::Bundle) a global one. With debug assertions on, the crate counts
+//! the live ones; compare the counts around a loop body:
+//! ```no_run
+//! use android_intent::{leak_check, Action, Intent};
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! let before = leak_check::live_refs();
+//! for _ in 0..1000 {
+//!     let mut intent = Intent::new(intent_env.get_env(), Action::View);
+//!     let _extras = intent.extras_bundle().unwrap();
+//! }
+//! leak_check::assert_no_leaks(before);
+//! ```
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static INTENTS: AtomicUsize = AtomicUsize::new(0);
+static BUNDLES: AtomicUsize = AtomicUsize::new(0);
+
+/// Live wrappers holding a JNI reference. Always zero without debug assertions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LiveRefs {
+    /// JNI-backed intents, each holding a local reference.
+    pub intents: usize,
+    /// Bundles, each holding a global reference.
+    pub bundles: usize,
+}
+
+/// The wrappers alive right now, on all threads.
+pub fn live_refs() -> LiveRefs {
+    LiveRefs { intents: INTENTS.load(Ordering::Relaxed), bundles: BUNDLES.load(Ordering::Relaxed) }
+}
+
+/// With debug assertions on, panic if more wrappers are alive than at `baseline`, as taken
+/// with [`live_refs`]. Other threads creating wrappers in the meantime count too.
+pub fn assert_no_leaks(baseline: LiveRefs) {
+    let live = live_refs();
+    debug_assert!(
+        live.intents <= baseline.intents && live.bundles <= baseline.bundles,
+        "JNI references leaked: {:?} alive, {:?} before",
+        live,
+        baseline,
+    );
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Kind {
+    Intent,
+    Bundle,
+}
+
+impl Kind {
+    fn counter(self) -> &'static AtomicUsize {
+        match self {
+            Self::Intent => &INTENTS,
+            Self::Bundle => &BUNDLES,
+        }
+    }
+}
+
+/// Count a new wrapper of `kind`.
+pub(crate) fn acquired(kind: Kind) {
+    if cfg!(debug_assertions) {
+        kind.counter().fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Count a dropped wrapper of `kind`.
+pub(crate) fn released(kind: Kind) {
+    if cfg!(debug_assertions) {
+        kind.counter().fetch_sub(1, Ordering::Relaxed);
+    }
+}
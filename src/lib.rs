@@ -1,8 +1,18 @@
 mod action;
 pub use action::Action;
 
+mod api_level;
+pub use api_level::{sdk_int, ApiLevel};
+
+mod error;
+pub use error::Error;
+
 mod extra;
-pub use extra::Extra;
+pub use extra::{Extra, ExtraValue, FromIntent, IntentExtras, Parcelable};
+#[doc(hidden)]
+pub use extra::__private;
+#[cfg(feature = "derive")]
+pub use android_intent_derive::IntentExtras;
 
 mod intent;
 
@@ -12,32 +22,293 @@ pub use flag::Flags;
 mod category;
 pub use category::Category;
 
-pub use intent::Intent;
-use jni::{JNIEnv, JavaVM, AttachGuard};
-use ndk_context::AndroidContext;
+mod am;
+
+mod backend;
+
+mod builder;
+pub use builder::IntentBuilder;
+
+mod bundle;
+pub use bundle::Bundle;
+
+mod chooser;
+pub use chooser::PendingRequest;
+
+mod component;
+pub use component::ComponentName;
+
+mod debounce;
+
+mod describe;
+pub use describe::{DescribedExtra, IntentDescription};
+
+pub mod alarms;
+pub mod assist;
+pub mod bridge;
+pub mod bus;
+pub mod caller;
+pub mod compat;
+#[cfg(unix)]
+pub mod content;
+pub mod documents;
+pub mod host;
+pub mod leak_check;
+pub mod lifecycle;
+
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "mock")]
+pub use mock::MockBackend;
+
+mod constants;
+mod context;
+
+mod observe;
+pub use observe::{clear_launch_observer, set_launch_observer, LaunchObserver, LaunchOutcome};
+
+mod parcel_size;
+pub use parcel_size::{set_extras_size_limit, RECOMMENDED_EXTRAS_SIZE_LIMIT};
+
+mod pending;
+mod sig;
+mod spec;
+mod trace;
+mod ui_thread;
+pub use ui_thread::{is_main_thread, set_strict_mode, UiLaunch};
+pub use spec::IntentSpec;
+
+pub mod uri;
+pub use uri::Uri;
+
+pub mod messenger;
+pub mod mime;
+pub mod nfc;
+pub mod notify;
+pub mod permissions;
+#[cfg(feature = "result-bridge")]
+pub mod pending_results;
+pub mod process_text;
+pub mod profiles;
+pub mod receivers;
+#[cfg(feature = "result-bridge")]
+pub mod requests;
+pub mod recipes;
+#[cfg(feature = "record")]
+pub mod record;
+pub mod settings;
+pub mod shortcuts;
+pub mod validate;
+pub mod widgets;
+#[cfg(feature = "winit")]
+pub mod winit;
+
+pub use intent::{is_in_multi_window, CompletedIntent, Intent, RESULT_CANCELED, RESULT_OK};
+use std::sync::OnceLock;
+
+/// The `jni` crate this crate is built against. Use its types (or depend on the same version)
+/// when passing a `JNIEnv` or `JavaVM` in, so they match the ones expected here.
+pub use jni;
+
+use jni::objects::GlobalRef;
+use jni::{JavaVM, AttachGuard};
+
+use crate::trace::Traced;
+
+/// How [`IntentEnv::get_env`] attaches threads that aren't attached to the JVM yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AttachMode {
+    /// Attach for as long as the returned guard lives, then detach again.
+    #[default]
+    Scoped,
+    /// Stay attached until the thread exits, so long-lived worker threads only attach once.
+    Permanent,
+    /// Like [`Self::Permanent`], but as a daemon thread that doesn't keep the JVM from shutting
+    /// down.
+    Daemon,
+}
 
 pub struct IntentEnv {
-    cx: AndroidContext,
     vm: JavaVM,
+    attach_mode: AttachMode,
 }
 
 impl IntentEnv {
     pub fn new() -> Self {
-        let cx = ndk_context::android_context();
-        let vm = unsafe { JavaVM::from_raw(cx.vm().cast()) }.unwrap();
+        Self::from_java_vm(context::java_vm().unwrap())
+    }
 
-        return Self {
-            cx,
+    /// Use `vm` instead of the one provided by [`ndk_context`], e.g. one obtained in
+    /// `JNI_OnLoad`.
+    pub fn from_java_vm(vm: JavaVM) -> Self {
+        Self {
             vm,
+            attach_mode: AttachMode::default(),
+        }
+    }
+
+    /// Attach threads according to `attach_mode` from now on. Threads that are already attached
+    /// keep their status.
+    /// ```no_run
+    /// use android_intent::{AttachMode, IntentEnv};
+    ///
+    /// std::thread::spawn(|| {
+    ///     let intent_env = IntentEnv::new().with_attach_mode(AttachMode::Daemon);
+    ///     for _ in 0..10 {
+    ///         // Only the first call attaches the thread.
+    ///         let env = intent_env.get_env();
+    ///     }
+    /// });
+    /// ```
+    pub fn with_attach_mode(self, attach_mode: AttachMode) -> Self {
+        Self { attach_mode, ..self }
+    }
+
+    pub fn get_env(&self) -> AttachGuard<'_> {
+        // The guard doesn't detach a thread that was attached before, so attaching it for good
+        // first makes it a no-op.
+        match self.attach_mode {
+            AttachMode::Scoped => {}
+            AttachMode::Permanent => {
+                self.vm.attach_current_thread_permanently().unwrap();
+            }
+            AttachMode::Daemon => {
+                self.vm.attach_current_thread_as_daemon().unwrap();
+            }
+        }
+
+        self.vm.attach_current_thread().unwrap()
+    }
+
+    /// Pick up the activity [`ndk_context`] holds now. Launches go through a weak reference to
+    /// the activity taken on first use, so call this once a recreated activity has replaced it,
+    /// e.g. after a configuration change. Fails with [`Error::NullContext`] if there is none.
+    /// ```no_run
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// // From the new activity's onCreate glue:
+    /// intent_env.refresh_context().unwrap();
+    /// ```
+    pub fn refresh_context(&self) -> Result<(), Error> {
+        context::refresh(&mut self.get_env())
+    }
+
+    /// Who started this activity, via `Activity.getReferrer` (API 22+): an
+    /// `android-app://<package>` URI for apps, or whatever the caller passed with
+    /// [`Intent::with_referrer`]. `None` if unknown.
+    pub fn get_referrer(&self) -> Result<Option<Uri>, Error> {
+        let mut env = self.get_env();
+        ApiLevel(22).require(&mut env)?;
+
+        let activity = context::activity(&mut env)?;
+        let referrer = env.call(activity, "getReferrer", "()Landroid/net/Uri;", &[])?.l()?;
+        if referrer.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(bundle::to_string(&mut env, &referrer)?.into()))
+    }
+
+    /// A `PendingIntent` that delivers `data` back to this activity's `onActivityResult` under
+    /// `request_code`, via `Activity.createPendingResult`, e.g. as the sent and delivered
+    /// intents of `SmsManager.sendTextMessage`. Read it with [`Intent::get_result`] like any
+    /// other result.
+    ///
+    /// `flags` are `PendingIntent.FLAG_*` bits. Without a mutability flag, it is immutable;
+    /// pass [`compat::PENDING_INTENT_FLAG_MUTABLE`] if the sender fills in extras, as `SmsManager`
+    /// does.
+    /// ```no_run
+    /// use android_intent::IntentSpec;
+    ///
+    /// # let intent_env = android_intent::IntentEnv::new();
+    /// let mut data = IntentSpec { action: Some("com.example.app.SMS_SENT".to_owned()), ..Default::default() }
+    ///     .realize(intent_env.get_env());
+    /// let sent = intent_env
+    ///     .create_pending_result(7, &mut data, android_intent::compat::PENDING_INTENT_FLAG_MUTABLE)
+    ///     .unwrap();
+    /// ```
+    pub fn create_pending_result(&self, request_code: i32, data: &mut Intent, flags: i32) -> Result<GlobalRef, Error> {
+        let data = &data.jni("create_pending_result")?.object;
+        let mut env = self.get_env();
+
+        let mutability = compat::PENDING_INTENT_FLAG_MUTABLE | compat::PENDING_INTENT_FLAG_IMMUTABLE;
+        let flags = match flags & mutability {
+            0 => flags | compat::pending_intent_flags(api_level::sdk_int_with(&mut env)?, false),
+            _ => flags,
         };
+        let activity = context::activity(&mut env)?;
+        let pending = env
+            .call(
+                activity,
+                "createPendingResult",
+                "(ILandroid/content/Intent;I)Landroid/app/PendingIntent;",
+                &[request_code.into(), data.into(), flags.into()],
+            )?
+            .l()?;
+
+        Ok(env.new_global_ref(pending)?)
+    }
+
+    /// Close this activity, via `Activity.finish`, e.g. once a one-shot deep link is handled.
+    pub fn finish(&self) -> Result<(), Error> {
+        let mut env = self.get_env();
+        let activity = context::activity(&mut env)?;
+        env.call(activity, "finish", "()V", &[])?;
+
+        Ok(())
     }
 
-    pub fn get_env(&self) -> AttachGuard {
-        return self.vm.attach_current_thread().unwrap();
+    /// Close this activity and remove its task from the recents screen, via
+    /// `Activity.finishAndRemoveTask` (API 21+).
+    pub fn finish_and_remove_task(&self) -> Result<(), Error> {
+        let mut env = self.get_env();
+        ApiLevel(21).require(&mut env)?;
+
+        let activity = context::activity(&mut env)?;
+        env.call(activity, "finishAndRemoveTask", "()V", &[])?;
+
+        Ok(())
+    }
+
+    /// Send this activity's task to the back, via `Activity.moveTaskToBack`. Unless `non_root`,
+    /// this only works from the task's root activity. Returns whether the task moved.
+    pub fn move_task_to_back(&self, non_root: bool) -> Result<bool, Error> {
+        let mut env = self.get_env();
+        let activity = context::activity(&mut env)?;
+        let moved = env.call(activity, "moveTaskToBack", "(Z)Z", &[non_root.into()])?.z()?;
+
+        Ok(moved)
     }
 }
 
-/// Run 'f' with the current [`JNIEnv`] from [`ndk_context`].
+impl Default for IntentEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A process-wide [`IntentEnv`], created on first use, for code that has no `IntentEnv` of its
+/// own to pass around, such as library crates.
+///
+/// It uses [`AttachMode::Permanent`], so each thread is attached on its first use and stays
+/// attached until it exits.
+/// ```no_run
+/// use android_intent::{Action, Intent};
+///
+/// fn share(text: &str) {
+///     Intent::new(android_intent::global().get_env(), Action::Send)
+///         .with_type("text/plain")
+///         .with_extra(android_intent::Extra::Text, text)
+///         .start_activity()
+///         .unwrap();
+/// }
+/// ```
+pub fn global() -> &'static IntentEnv {
+    static GLOBAL: OnceLock<IntentEnv> = OnceLock::new();
+
+    GLOBAL.get_or_init(|| IntentEnv::new().with_attach_mode(AttachMode::Permanent))
+}
+
+/// Run `f` with the current [`JNIEnv`](jni::JNIEnv) from [`ndk_context`].
 pub fn with_current_env(intent_env: &mut IntentEnv, f: impl FnOnce(AttachGuard)) {
     let env = intent_env.get_env();
 
@@ -0,0 +1,170 @@
+//! Following the activity's `onResume`/`onPause`, so the crate reacts to them itself instead of
+//! relying on the app to poll at the right moments.
+//!
+//! Once [`enable`]d, the crate tracks whether the activity is in the foreground. On every resume
+//! it runs the [`requests`](crate::requests) callbacks for results queued while the app was
+//! away, and timeouts such as
+//! [`PendingRequest::with_timeout`](crate::PendingRequest::with_timeout) only count time spent in
+//! the foreground. Results no callback waits for stay queued for
+//! [`Intent::get_result`](crate::Intent::get_result).
+//!
+//! Needs the activity to extend the generated [bridge] class. The registration
+//! ends with the activity, so enable it again once a recreated activity is up:
+//! ```no_run
+//! use android_intent::lifecycle;
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! lifecycle::enable(&mut intent_env.get_env()).unwrap();
+//!
+//! if !lifecycle::is_foreground() {
+//!     log::debug!("not launching while in the background");
+//! }
+//! ```
+
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use jni::objects::GlobalRef;
+use jni::JNIEnv;
+
+use crate::trace::{debug, Traced};
+use crate::{bridge, context, Error};
+
+struct State {
+    /// When the app went to the background, `None` while in the foreground.
+    paused_at: Option<Instant>,
+    /// Background time before `paused_at`.
+    paused_total: Duration,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { paused_at: None, paused_total: Duration::ZERO });
+static CHANGED: Condvar = Condvar::new();
+
+/// Start following the activity's lifecycle. Assumes it is in the foreground until told
+/// otherwise.
+pub fn enable(env: &mut JNIEnv) -> Result<(), Error> {
+    let events = env.new_object("java/util/concurrent/LinkedBlockingQueue", "()V", &[])?;
+    let activity = context::activity(env)?;
+    env.call(
+        &activity,
+        bridge::REGISTER_LIFECYCLE_QUEUE,
+        bridge::REGISTER_LIFECYCLE_QUEUE_SIG,
+        &[(&events).into()],
+    )?;
+
+    let events = env.new_global_ref(events)?;
+    thread::spawn(move || {
+        if let Err(err) = follow(&events) {
+            debug!("following the activity lifecycle failed: {}", err);
+        }
+    });
+
+    Ok(())
+}
+
+/// Whether the activity is in the foreground, as of its last `onResume` or `onPause`. Always
+/// `true` unless [`enable`]d.
+pub fn is_foreground() -> bool {
+    STATE.lock().unwrap().paused_at.is_none()
+}
+
+/// Run the [`requests`](crate::requests) callbacks for all queued results that have one, as
+/// happens on every resume once [`enable`]d. Returns how many ran.
+#[cfg(feature = "result-bridge")]
+pub fn flush_results() -> Result<usize, Error> {
+    let vm = context::java_vm()?;
+    let mut flushed = 0;
+
+    loop {
+        let codes = crate::requests::callback_codes();
+        if codes.is_empty() {
+            return Ok(flushed);
+        }
+
+        let mut env = vm.attach_current_thread()?;
+        let jcodes = env.new_int_array(codes.len() as i32)?;
+        env.set_int_array_region(&jcodes, 0, &codes)?;
+        let activity = context::activity(&mut env)?;
+        let result = env
+            .call(activity, bridge::TAKE_RESULT_FOR, bridge::take_result_for_sig(), &[(&jcodes).into()])?
+            .l()?;
+        if result.is_null() {
+            return Ok(flushed);
+        }
+
+        let mut completed = crate::CompletedIntent::from_result(env, &result)?;
+        crate::requests::complete(&mut completed);
+        flushed += 1;
+    }
+}
+
+/// Sleep until the app spent `duration` in the foreground.
+pub(crate) fn sleep_foreground(duration: Duration) {
+    let mut state = STATE.lock().unwrap();
+    let deadline = foreground_time(&state, Instant::now()) + duration;
+
+    loop {
+        if state.paused_at.is_some() {
+            state = CHANGED.wait(state).unwrap();
+            continue;
+        }
+
+        let elapsed = foreground_time(&state, Instant::now());
+        if elapsed >= deadline {
+            return;
+        }
+        state = CHANGED.wait_timeout(state, deadline - elapsed).unwrap().0;
+    }
+}
+
+/// Time spent in the foreground since it was first measured.
+fn foreground_time(state: &State, now: Instant) -> Duration {
+    static START: Mutex<Option<Instant>> = Mutex::new(None);
+    let start = *START.lock().unwrap().get_or_insert(now);
+
+    let paused = state.paused_total + state.paused_at.map_or(Duration::ZERO, |at| now - at);
+    (now - start).saturating_sub(paused)
+}
+
+/// Apply the events the bridge posts on `events` until the activity is destroyed.
+fn follow(events: &GlobalRef) -> Result<(), Error> {
+    let vm = context::java_vm()?;
+    let mut env = vm.attach_current_thread_as_daemon()?;
+
+    loop {
+        let event = env.call(events.as_obj(), "take", "()Ljava/lang/Object;", &[])?.l()?;
+        if !env.is_instance_of(&event, "java/lang/Boolean")? {
+            debug!("activity destroyed, no longer following its lifecycle");
+            return Ok(());
+        }
+        let resumed = env.call(&event, "booleanValue", "()Z", &[])?.z()?;
+        env.delete_local_ref(event)?;
+
+        set_foreground(resumed);
+        #[cfg(feature = "result-bridge")]
+        if resumed {
+            match flush_results() {
+                Ok(flushed) => debug!("ran {} result callbacks on resume", flushed),
+                Err(err) => debug!("running result callbacks on resume failed: {}", err),
+            }
+        }
+    }
+}
+
+fn set_foreground(foreground: bool) {
+    let mut state = STATE.lock().unwrap();
+    let now = Instant::now();
+    // Start the foreground clock before the first pause.
+    foreground_time(&state, now);
+
+    match (foreground, state.paused_at) {
+        (true, Some(at)) => {
+            state.paused_total += now - at;
+            state.paused_at = None;
+        }
+        (false, None) => state.paused_at = Some(now),
+        _ => {}
+    }
+    CHANGED.notify_all();
+}
@@ -0,0 +1,186 @@
+//! `Messenger` IPC with bound services, including those of other apps: bind to the service,
+//! send it `Message`s with a [`Bundle`] payload, and read its replies.
+//!
+//! Needs the [bridge] activity, which implements the `ServiceConnection` and the
+//! reply `Handler`. Don't block the UI thread on [`Binding::wait`] or [`Replies::recv`]: both
+//! are delivered on it.
+//! ```no_run
+//! use std::collections::BTreeMap;
+//!
+//! use android_intent::messenger::{self, Replies};
+//! use android_intent::{Bundle, ExtraValue, IntentSpec};
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! # let mut env = intent_env.get_env();
+//! let service = IntentSpec {
+//!     action: Some("com.example.weather.BIND".to_owned()),
+//!     package: Some("com.example.weather".to_owned()),
+//!     ..Default::default()
+//! };
+//! let binding = messenger::bind(&mut env, &service).unwrap();
+//! let messenger = binding.wait(&mut env).unwrap();
+//!
+//! let replies = Replies::new(&mut env).unwrap();
+//! let request = Bundle::new(&mut env, &BTreeMap::from([("city".to_owned(), ExtraValue::Str("Oslo".to_owned()))])).unwrap();
+//! messenger.send(&mut env, 1, &request, Some(&replies)).unwrap();
+//! let reply = replies.recv(&mut env).unwrap();
+//! log::info!("forecast: {:?}", reply.data.to_map(&mut env).unwrap());
+//! binding.unbind(&mut env).unwrap();
+//! ```
+
+use jni::objects::{GlobalRef, JObject};
+use jni::JNIEnv;
+
+use crate::trace::Traced;
+use crate::{backend, bridge, context, Bundle, Error, IntentSpec};
+
+const MESSAGE_CLASS: &str = "android/os/Message";
+
+/// A connection to a service, from [`bind`].
+pub struct Binding {
+    connection: GlobalRef,
+    bound: GlobalRef,
+    service: String,
+}
+
+/// Bind to the service described by `service`, via `Context.bindService` with
+/// `BIND_AUTO_CREATE`. From API 21 on, `service` must be explicit: set its package or component.
+pub fn bind(env: &mut JNIEnv, service: &IntentSpec) -> Result<Binding, Error> {
+    let intent = backend::new_intent(env, service)?;
+    let bound = env.new_object("java/util/concurrent/LinkedBlockingQueue", "()V", &[])?;
+
+    let activity = context::activity(env)?;
+    let connection = env
+        .call(
+            &activity,
+            bridge::BIND_MESSENGER_SERVICE,
+            bridge::BIND_MESSENGER_SERVICE_SIG,
+            &[(&intent).into(), (&bound).into()],
+        )?
+        .l()?;
+
+    Ok(Binding {
+        connection: env.new_global_ref(connection)?,
+        bound: env.new_global_ref(bound)?,
+        service: service.to_am_command(),
+    })
+}
+
+impl Binding {
+    /// Block until the service is connected, and return its `Messenger`. Fails with
+    /// [`Error::BindFailed`] if it can't be bound.
+    pub fn wait(&self, env: &mut JNIEnv) -> Result<Messenger, Error> {
+        let bound = env.call(self.bound.as_obj(), "take", "()Ljava/lang/Object;", &[])?.l()?;
+        if !env.is_instance_of(&bound, "android/os/Messenger")? {
+            return Err(Error::BindFailed(self.service.clone()));
+        }
+
+        Ok(Messenger { object: env.new_global_ref(bound)? })
+    }
+
+    /// Disconnect from the service, via `Context.unbindService`.
+    pub fn unbind(self, env: &mut JNIEnv) -> Result<(), Error> {
+        let activity = context::activity(env)?;
+        env.call(
+            &activity,
+            "unbindService",
+            "(Landroid/content/ServiceConnection;)V",
+            &[self.connection.as_obj().into()],
+        )?;
+
+        Ok(())
+    }
+}
+
+/// A service's `android.os.Messenger`.
+pub struct Messenger {
+    object: GlobalRef,
+}
+
+impl Messenger {
+    /// Send a `Message` with code `what` and `data` as its payload. The service answers through
+    /// `replies`, set as the message's `replyTo`.
+    pub fn send(&self, env: &mut JNIEnv, what: i32, data: &Bundle, replies: Option<&Replies>) -> Result<(), Error> {
+        let message = env
+            .call_static(
+                MESSAGE_CLASS,
+                "obtain",
+                "(Landroid/os/Handler;I)Landroid/os/Message;",
+                &[(&JObject::null()).into(), what.into()],
+            )?
+            .l()?;
+        env.call(&message, "setData", "(Landroid/os/Bundle;)V", &[data.as_obj().into()])?;
+        if let Some(replies) = replies {
+            env.set_field(&message, "replyTo", "Landroid/os/Messenger;", replies.messenger.as_obj().into())?;
+        }
+
+        env.call(self.object.as_obj(), "send", "(Landroid/os/Message;)V", &[(&message).into()])?;
+
+        Ok(())
+    }
+
+    /// The underlying `android.os.Messenger`.
+    pub fn as_obj(&self) -> &JObject<'static> {
+        self.object.as_obj()
+    }
+}
+
+/// A `Messenger` of this app for services to reply to, queueing what they send.
+pub struct Replies {
+    messenger: GlobalRef,
+    queue: GlobalRef,
+}
+
+/// A `Message` a service sent to [`Replies`].
+#[derive(Debug)]
+pub struct Reply {
+    pub what: i32,
+    pub arg1: i32,
+    pub arg2: i32,
+    pub data: Bundle,
+}
+
+impl Replies {
+    pub fn new(env: &mut JNIEnv) -> Result<Self, Error> {
+        let queue = env.new_object("java/util/concurrent/LinkedBlockingQueue", "()V", &[])?;
+        let activity = context::activity(env)?;
+        let messenger = env
+            .call(
+                &activity,
+                bridge::NEW_REPLY_MESSENGER,
+                bridge::NEW_REPLY_MESSENGER_SIG,
+                &[(&queue).into()],
+            )?
+            .l()?;
+
+        Ok(Self { messenger: env.new_global_ref(messenger)?, queue: env.new_global_ref(queue)? })
+    }
+
+    /// Block until the next reply arrives.
+    pub fn recv(&self, env: &mut JNIEnv) -> Result<Reply, Error> {
+        let message = env.call(self.queue.as_obj(), "take", "()Ljava/lang/Object;", &[])?.l()?;
+        reply(env, &message)
+    }
+
+    /// The next reply, if one has arrived.
+    pub fn try_recv(&self, env: &mut JNIEnv) -> Result<Option<Reply>, Error> {
+        let message = env.call(self.queue.as_obj(), "poll", "()Ljava/lang/Object;", &[])?.l()?;
+        if message.is_null() {
+            return Ok(None);
+        }
+
+        reply(env, &message).map(Some)
+    }
+}
+
+fn reply(env: &mut JNIEnv, message: &JObject) -> Result<Reply, Error> {
+    let what = env.get_field(message, "what", "I")?.i()?;
+    let arg1 = env.get_field(message, "arg1", "I")?.i()?;
+    let arg2 = env.get_field(message, "arg2", "I")?.i()?;
+    // getData creates an empty Bundle if the message has none.
+    let data = env.call(message, "getData", "()Landroid/os/Bundle;", &[])?.l()?;
+    let data = Bundle::from_object(env, &data)?;
+    env.call(message, "recycle", "()V", &[])?;
+
+    Ok(Reply { what, arg1, arg2, data })
+}
@@ -0,0 +1,204 @@
+//! MIME types, as used by [`Intent::with_type`](crate::Intent::with_type).
+//!
+//! ```no_run
+//! use android_intent::{mime, Action, Intent};
+//!
+//! # let mut intent_env = android_intent::IntentEnv::new();
+//! # android_intent::with_current_env(&mut intent_env, |env| {
+//! Intent::new(env, Action::GetContent).with_type(mime::IMAGE_ANY).start_activity().unwrap();
+//! # })
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::trace::Traced;
+use crate::{context, Error};
+
+const MIME_TYPE_MAP_CLASS: &str = "android/webkit/MimeTypeMap";
+
+pub const ANY: Mime = Mime::from_static("*/*");
+pub const TEXT_ANY: Mime = Mime::from_static("text/*");
+pub const TEXT_PLAIN: Mime = Mime::from_static("text/plain");
+pub const TEXT_HTML: Mime = Mime::from_static("text/html");
+pub const IMAGE_ANY: Mime = Mime::from_static("image/*");
+pub const IMAGE_PNG: Mime = Mime::from_static("image/png");
+pub const IMAGE_JPEG: Mime = Mime::from_static("image/jpeg");
+pub const AUDIO_ANY: Mime = Mime::from_static("audio/*");
+pub const VIDEO_ANY: Mime = Mime::from_static("video/*");
+pub const APPLICATION_PDF: Mime = Mime::from_static("application/pdf");
+pub const APPLICATION_OCTET_STREAM: Mime = Mime::from_static("application/octet-stream");
+/// Android application packages, see [`recipes::install_apk`](crate::recipes::install_apk).
+pub const APPLICATION_APK: Mime = Mime::from_static("application/vnd.android.package-archive");
+
+/// A MIME type such as `image/png`, or a wildcard such as `image/*`.
+///
+/// Use the constants of this module or [`Mime::new`], which normalizes its input. Accepted
+/// wherever a type string is, e.g. by [`Intent::with_type`](crate::Intent::with_type).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Mime(Cow<'static, str>);
+
+impl Mime {
+    /// Parse `mime_type`, normalized with [`normalize_mime_type`].
+    pub fn new(mime_type: &str) -> Self {
+        Self(Cow::Owned(normalize_mime_type(mime_type)))
+    }
+
+    const fn from_static(mime_type: &'static str) -> Self {
+        Self(Cow::Borrowed(mime_type))
+    }
+
+    /// `<top_level>/*`, e.g. `image/*`.
+    pub fn wildcard(top_level: &str) -> Self {
+        Self::new(&format!("{}/*", top_level))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The part before the `/`, e.g. `image`.
+    pub fn top_level(&self) -> &str {
+        self.0.split_once('/').map_or(&self.0, |(top_level, _)| top_level)
+    }
+
+    /// The part after the `/`, e.g. `png`.
+    pub fn subtype(&self) -> &str {
+        self.0.split_once('/').map_or("", |(_, subtype)| subtype)
+    }
+
+    /// Whether the type or subtype is `*`.
+    pub fn is_wildcard(&self) -> bool {
+        self.top_level() == "*" || self.subtype() == "*"
+    }
+
+    /// Whether `other` falls under this type, treating `*` as matching anything like intent
+    /// filters do.
+    /// ```
+    /// use android_intent::mime::{self, Mime};
+    ///
+    /// assert!(mime::IMAGE_ANY.matches(&mime::IMAGE_PNG));
+    /// assert!(mime::ANY.matches(&Mime::new("Video/MP4")));
+    /// assert!(!mime::IMAGE_PNG.matches(&mime::IMAGE_JPEG));
+    /// ```
+    pub fn matches(&self, other: &Mime) -> bool {
+        let part_matches = |pattern: &str, value: &str| pattern == "*" || pattern == value;
+
+        part_matches(self.top_level(), other.top_level()) && part_matches(self.subtype(), other.subtype())
+    }
+}
+
+impl AsRef<str> for Mime {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Mime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for Mime {
+    fn from(mime_type: &str) -> Self {
+        Self::new(mime_type)
+    }
+}
+
+/// Normalize `mime_type` the way `Intent.normalizeMimeType` does: lower case, without
+/// parameters such as `; charset=utf-8`. Intent filters match MIME types case-sensitively, so
+/// types from outside sources should go through this.
+/// ```
+/// use android_intent::mime;
+///
+/// assert_eq!(mime::normalize_mime_type(" Text/HTML; charset=UTF-8"), "text/html");
+/// ```
+pub fn normalize_mime_type(mime_type: &str) -> String {
+    let mime_type = mime_type.trim().to_ascii_lowercase();
+
+    match mime_type.split_once(';') {
+        Some((mime_type, _)) => mime_type.trim_end().to_owned(),
+        None => mime_type,
+    }
+}
+
+fn mime_type_map<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+    let map = env
+        .call_static(MIME_TYPE_MAP_CLASS, "getSingleton", "()Landroid/webkit/MimeTypeMap;", &[])?
+        .l()?;
+
+    Ok(map)
+}
+
+/// Call a `(Ljava/lang/String;)Ljava/lang/String;` method of `MimeTypeMap`.
+fn lookup(env: &mut JNIEnv, method: &str, key: &str) -> Result<Option<String>, Error> {
+    let map = mime_type_map(env)?;
+    let key = env.new_string(key)?;
+
+    let value = env
+        .call(&map, method, "(Ljava/lang/String;)Ljava/lang/String;", &[(&key).into()])?
+        .l()?;
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    Ok(Some(env.get_string(&value.into())?.into()))
+}
+
+/// The MIME type for the file extension `extension` (without the dot), e.g. `image/png` for
+/// `png`, via `MimeTypeMap.getMimeTypeFromExtension`.
+pub fn from_extension(env: &mut JNIEnv, extension: &str) -> Result<Option<String>, Error> {
+    lookup(env, "getMimeTypeFromExtension", &extension.to_ascii_lowercase())
+}
+
+/// The MIME type for a file path or URL, guessed from its extension.
+/// ```no_run
+/// use android_intent::{mime, Action, Intent};
+///
+/// # let mut intent_env = android_intent::IntentEnv::new();
+/// # android_intent::with_current_env(&mut intent_env, |mut env| {
+/// let uri = "content://com.example.app.fileprovider/files/report.pdf";
+/// let mime_type = mime::from_path(&mut env, uri).unwrap().unwrap_or_else(|| "*/*".to_owned());
+///
+/// Intent::new(env, Action::View).with_data_and_type(uri, mime_type).start_activity().unwrap();
+/// # })
+/// ```
+pub fn from_path(env: &mut JNIEnv, path: &str) -> Result<Option<String>, Error> {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let file_name = path.rsplit('/').next().unwrap_or_default();
+
+    match file_name.rsplit_once('.') {
+        Some((_, extension)) if !extension.is_empty() => from_extension(env, extension),
+        _ => Ok(None),
+    }
+}
+
+/// The usual file extension (without the dot) for `mime_type`, via
+/// `MimeTypeMap.getExtensionFromMimeType`.
+pub fn extension(env: &mut JNIEnv, mime_type: &str) -> Result<Option<String>, Error> {
+    lookup(env, "getExtensionFromMimeType", &normalize_mime_type(mime_type))
+}
+
+/// `Intent.resolveType` against this app's `ContentResolver`: the explicit type, or the type
+/// of a `content:` data URI.
+pub(crate) fn resolve_type(env: &mut JNIEnv, intent: &JObject) -> Result<Option<String>, Error> {
+    let resolver = context::content_resolver(env)?;
+
+    let mime_type = env
+        .call(
+            intent,
+            "resolveType",
+            "(Landroid/content/ContentResolver;)Ljava/lang/String;",
+            &[(&resolver).into()],
+        )?
+        .l()?;
+    if mime_type.is_null() {
+        return Ok(None);
+    }
+
+    Ok(Some(env.get_string(&mime_type.into())?.into()))
+}
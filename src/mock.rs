@@ -0,0 +1,193 @@
+//! A backend that records what an [`Intent`](crate::Intent) was asked to do, without a JVM.
+//!
+//! ```
+//! use android_intent::{Action, Extra, Intent};
+//!
+//! let intent = Intent::mock(Action::Send)
+//!     .with_type("text/plain")
+//!     .with_extra(Extra::Text, "Hello World!")
+//!     .start_activity()
+//!     .unwrap();
+//!
+//! let mock = intent.mock_backend().unwrap();
+//! assert_eq!(mock.spec.action.as_deref(), Some("android.intent.action.SEND"));
+//! assert_eq!(mock.spec.mime_type.as_deref(), Some("text/plain"));
+//! assert_eq!(mock.launches, [None]);
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::backend::{IntentBackend, Inner, INTENT_CLASS};
+use crate::{Error, ExtraValue, Flags, IntentSpec};
+
+/// Everything recorded for a mock [`Intent`](crate::Intent), see [`Intent::mock`](crate::Intent::mock).
+///
+/// Constants such as `ACTION_SEND` can't be read without a JVM. Those on `Intent` are resolved by
+/// their naming convention (`ACTION_SEND` is `android.intent.action.SEND`); those on other
+/// classes are recorded as `class.FIELD`, e.g. `android.provider.Settings.ACTION_WIFI_SETTINGS`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MockBackend {
+    /// The intent as built so far.
+    pub spec: IntentSpec,
+    /// One entry per launch, holding the request code for `start_activity_for_result`.
+    pub launches: Vec<Option<i32>>,
+}
+
+impl MockBackend {
+    pub(crate) fn new(class: &str, action: &str, uri: Option<&str>) -> Self {
+        Self {
+            spec: IntentSpec {
+                action: Some(resolve_field(class, action)),
+                data: uri.map(str::to_owned),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// The value a `String` constant of `class` has by convention.
+pub(crate) fn resolve_field(class: &str, field: &str) -> String {
+    if class == INTENT_CLASS {
+        for (prefix, namespace) in [
+            ("ACTION_", "android.intent.action."),
+            ("CATEGORY_", "android.intent.category."),
+            ("EXTRA_", "android.intent.extra."),
+        ] {
+            if let Some(name) = field.strip_prefix(prefix) {
+                return format!("{}{}", namespace, name);
+            }
+        }
+    }
+
+    format!("{}.{}", class.replace(['/', '$'], "."), field)
+}
+
+impl<'env> IntentBackend<'env> for MockBackend {
+    fn put_extra(&mut self, key: &str, value: &ExtraValue) -> Result<(), Error> {
+        self.spec.extras.insert(key.to_owned(), value.clone());
+        Ok(())
+    }
+
+    fn remove_extra(&mut self, key: &str) -> Result<(), Error> {
+        self.spec.extras.remove(key);
+        Ok(())
+    }
+
+    fn replace_extras(&mut self, extras: &BTreeMap<String, ExtraValue>) -> Result<(), Error> {
+        self.spec.extras = extras.clone();
+        Ok(())
+    }
+
+    fn replace_extras_from(&mut self, source: &mut dyn IntentBackend<'_>) -> Result<(), Error> {
+        self.spec.extras = source.extras()?;
+        Ok(())
+    }
+
+    fn set_type(&mut self, mime_type: &str) -> Result<(), Error> {
+        // Like `Intent.setType`, this clears the data URI.
+        self.spec.data = None;
+        self.spec.mime_type = Some(mime_type.to_owned());
+        Ok(())
+    }
+
+    fn set_data(&mut self, uri: &str, mime_type: Option<&str>) -> Result<(), Error> {
+        self.spec.data = Some(uri.to_owned());
+        self.spec.mime_type = mime_type.map(str::to_owned);
+        Ok(())
+    }
+
+    fn set_package(&mut self, package: Option<&str>) -> Result<(), Error> {
+        self.spec.package = package.map(str::to_owned);
+        Ok(())
+    }
+
+    fn set_source_bounds(&mut self, bounds: [i32; 4]) -> Result<(), Error> {
+        self.spec.source_bounds = Some(bounds);
+        Ok(())
+    }
+
+    fn set_selector(&mut self, selector: &IntentSpec) -> Result<(), Error> {
+        self.spec.selector = Some(Box::new(selector.clone()));
+        Ok(())
+    }
+
+    fn set_identifier(&mut self, identifier: &str) -> Result<(), Error> {
+        self.spec.identifier = Some(identifier.to_owned());
+        Ok(())
+    }
+
+    fn add_flags(&mut self, flags: Flags) -> Result<(), Error> {
+        self.spec.flags |= flags.bits() as i32;
+        Ok(())
+    }
+
+    fn remove_flags(&mut self, flags: Flags) -> Result<(), Error> {
+        self.spec.flags &= !(flags.bits() as i32);
+        Ok(())
+    }
+
+    fn add_category(&mut self, category: &str) -> Result<(), Error> {
+        let category = resolve_field(INTENT_CLASS, category);
+        if !self.spec.categories.contains(&category) {
+            self.spec.categories.push(category);
+        }
+        Ok(())
+    }
+
+    fn has_category(&mut self, category: &str) -> Result<bool, Error> {
+        Ok(self.spec.categories.contains(&resolve_field(INTENT_CLASS, category)))
+    }
+
+    fn remove_category(&mut self, category: &str) -> Result<(), Error> {
+        let category = resolve_field(INTENT_CLASS, category);
+        self.spec.categories.retain(|existing| *existing != category);
+        Ok(())
+    }
+
+    fn wrap_in_chooser(&mut self, title: Option<&str>) -> Result<(), Error> {
+        let target = std::mem::take(&mut self.spec);
+
+        let mut extras = BTreeMap::new();
+        extras.insert("android.intent.extra.INTENT".to_owned(), ExtraValue::Intent(Box::new(target)));
+        if let Some(title) = title {
+            extras.insert("android.intent.extra.TITLE".to_owned(), ExtraValue::Str(title.to_owned()));
+        }
+
+        self.spec = IntentSpec {
+            action: Some("android.intent.action.CHOOSER".to_owned()),
+            extras,
+            ..Default::default()
+        };
+        Ok(())
+    }
+
+    fn start_activity(&mut self, request_code: Option<i32>) -> Result<(), Error> {
+        self.launches.push(request_code);
+        Ok(())
+    }
+
+    fn extra(&mut self, key: &str) -> Result<Option<ExtraValue>, Error> {
+        Ok(self.spec.extras.get(key).cloned())
+    }
+
+    fn extras(&mut self) -> Result<BTreeMap<String, ExtraValue>, Error> {
+        Ok(self.spec.extras.clone())
+    }
+
+    fn to_spec(&mut self) -> Result<IntentSpec, Error> {
+        Ok(self.spec.clone())
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn jni(&mut self) -> Option<&mut Inner<'env>> {
+        None
+    }
+
+    fn mock(&self) -> Option<&MockBackend> {
+        Some(self)
+    }
+}
@@ -0,0 +1,281 @@
+//! NFC tags delivered to the app as intents.
+//!
+//! ```no_run
+//! use android_intent::nfc::{self, NdefContent};
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! # let mut intent = android_intent::Intent::new(intent_env.get_env(), android_intent::Action::View);
+//! // `intent` is the one the activity was started or resumed with.
+//! if nfc::discovery(&mut intent).unwrap().is_some() {
+//!     for message in nfc::ndef_messages(&mut intent).unwrap() {
+//!         for record in &message.records {
+//!             if let NdefContent::Uri(uri) = record.content() {
+//!                 println!("tag links to {}", uri);
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+use jni::objects::{JByteArray, JObject};
+use jni::JNIEnv;
+
+use crate::trace::Traced;
+use crate::{backend, bundle, context, pending, Error, Flags, Intent, IntentSpec, Uri};
+
+const NFC_ADAPTER_CLASS: &str = "android/nfc/NfcAdapter";
+const EXTRA_NDEF_MESSAGES: &str = "android.nfc.extra.NDEF_MESSAGES";
+
+const TNF_WELL_KNOWN: i16 = 0x01;
+const TNF_MIME_MEDIA: i16 = 0x02;
+const TNF_ABSOLUTE_URI: i16 = 0x03;
+
+/// Prefixes abbreviated by the first payload byte of a well-known URI record.
+const URI_PREFIXES: [&str; 36] = [
+    "",
+    "http://www.",
+    "https://www.",
+    "http://",
+    "https://",
+    "tel:",
+    "mailto:",
+    "ftp://anonymous:anonymous@",
+    "ftp://ftp.",
+    "ftps://",
+    "sftp://",
+    "smb://",
+    "nfs://",
+    "ftp://",
+    "dav://",
+    "news:",
+    "telnet://",
+    "imap:",
+    "rtsp://",
+    "urn:",
+    "pop:",
+    "sip:",
+    "sips:",
+    "tftp:",
+    "btspp://",
+    "btl2cap://",
+    "btgoep://",
+    "tcpobex://",
+    "irdaobex://",
+    "file://",
+    "urn:epc:id:",
+    "urn:epc:tag:",
+    "urn:epc:pat:",
+    "urn:epc:raw:",
+    "urn:epc:",
+    "urn:nfc:",
+];
+
+/// How the system dispatched a tag, from most to least specific match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Discovery {
+    /// `NfcAdapter.ACTION_NDEF_DISCOVERED`
+    Ndef,
+    /// `NfcAdapter.ACTION_TECH_DISCOVERED`
+    Tech,
+    /// `NfcAdapter.ACTION_TAG_DISCOVERED`
+    Tag,
+}
+
+impl Discovery {
+    fn from_action(action: &str) -> Option<Self> {
+        match action {
+            "android.nfc.action.NDEF_DISCOVERED" => Some(Self::Ndef),
+            "android.nfc.action.TECH_DISCOVERED" => Some(Self::Tech),
+            "android.nfc.action.TAG_DISCOVERED" => Some(Self::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// An NDEF message read from a tag.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NdefMessage {
+    pub records: Vec<NdefRecord>,
+}
+
+/// A raw `NdefRecord`, see [`Self::content`] for its decoded form.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NdefRecord {
+    /// Type name format, one of the `NdefRecord.TNF_*` constants.
+    pub tnf: i16,
+    pub record_type: Vec<u8>,
+    pub id: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+/// What an [`NdefRecord`] holds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NdefContent {
+    Uri(Uri),
+    Text { language: String, text: String },
+    Mime { mime_type: String, data: Vec<u8> },
+    /// A record of a type not decoded here; read the raw fields instead.
+    Other,
+}
+
+impl NdefRecord {
+    /// Decode well-known URI and text records, MIME records and absolute URIs.
+    pub fn content(&self) -> NdefContent {
+        match (self.tnf, self.record_type.as_slice()) {
+            (TNF_WELL_KNOWN, b"U") => self.uri().map_or(NdefContent::Other, NdefContent::Uri),
+            (TNF_WELL_KNOWN, b"T") => self.text().unwrap_or(NdefContent::Other),
+            (TNF_MIME_MEDIA, mime_type) => NdefContent::Mime {
+                mime_type: String::from_utf8_lossy(mime_type).to_ascii_lowercase(),
+                data: self.payload.clone(),
+            },
+            (TNF_ABSOLUTE_URI, uri) => NdefContent::Uri(String::from_utf8_lossy(uri).into_owned().into()),
+            _ => NdefContent::Other,
+        }
+    }
+
+    fn uri(&self) -> Option<Uri> {
+        let (prefix, rest) = self.payload.split_first()?;
+        let prefix = URI_PREFIXES.get(*prefix as usize).copied().unwrap_or("");
+
+        Some(format!("{}{}", prefix, String::from_utf8_lossy(rest)).into())
+    }
+
+    fn text(&self) -> Option<NdefContent> {
+        let (status, rest) = self.payload.split_first()?;
+        let language_len = (status & 0x3f) as usize;
+        if rest.len() < language_len {
+            return None;
+        }
+        let (language, text) = rest.split_at(language_len);
+
+        let text = if status & 0x80 == 0 {
+            String::from_utf8_lossy(text).into_owned()
+        } else {
+            let units = text.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+            char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+        };
+
+        Some(NdefContent::Text {
+            language: String::from_utf8_lossy(language).into_owned(),
+            text,
+        })
+    }
+}
+
+/// Whether `intent` reports a discovered tag, and how it was matched.
+pub fn discovery(intent: &mut Intent) -> Result<Option<Discovery>, Error> {
+    let inner = intent.jni("nfc::discovery")?;
+    let action = backend::string_getter(&mut inner.env, &inner.object, "getAction")?;
+
+    Ok(action.as_deref().and_then(Discovery::from_action))
+}
+
+/// The NDEF messages carried by a tag discovery intent, empty if there are none.
+pub fn ndef_messages(intent: &mut Intent) -> Result<Vec<NdefMessage>, Error> {
+    let inner = intent.jni("nfc::ndef_messages")?;
+    let env = &mut inner.env;
+
+    let key = env.new_string(EXTRA_NDEF_MESSAGES)?;
+    let messages = env
+        .call(
+            &inner.object,
+            "getParcelableArrayExtra",
+            "(Ljava/lang/String;)[Landroid/os/Parcelable;",
+            &[(&key).into()],
+        )?
+        .l()?;
+    if messages.is_null() {
+        return Ok(Vec::new());
+    }
+
+    bundle::object_array(env, messages.into())?
+        .iter()
+        .map(|message| {
+            let records = env.call(message, "getRecords", "()[Landroid/nfc/NdefRecord;", &[])?.l()?;
+            let records = bundle::object_array(env, records.into())?
+                .iter()
+                .map(|record| ndef_record(env, record))
+                .collect::<Result<_, _>>()?;
+
+            Ok(NdefMessage { records })
+        })
+        .collect()
+}
+
+fn ndef_record(env: &mut JNIEnv, record: &JObject) -> Result<NdefRecord, Error> {
+    let mut bytes = |method: &str| -> Result<Vec<u8>, Error> {
+        let array = env.call(record, method, "()[B", &[])?.l()?;
+        Ok(env.convert_byte_array(JByteArray::from(array))?)
+    };
+
+    let record_type = bytes("getType")?;
+    let id = bytes("getId")?;
+    let payload = bytes("getPayload")?;
+    let tnf = env.call(record, "getTnf", "()S", &[])?.s()?;
+
+    Ok(NdefRecord { tnf, record_type, id, payload })
+}
+
+/// Route every tag to this activity while it is in the foreground, ahead of other apps. The
+/// activity is resumed with the tag intent through `onNewIntent`.
+///
+/// Call this from `onResume` and [`disable_foreground_dispatch`] from `onPause`, on the UI
+/// thread. Returns `false` if the device has no NFC.
+pub fn enable_foreground_dispatch(env: &mut JNIEnv) -> Result<bool, Error> {
+    let adapter = default_adapter(env)?;
+    if adapter.is_null() {
+        return Ok(false);
+    }
+
+    let activity = context::activity(env)?;
+    let class = env.call(&activity, "getClass", "()Ljava/lang/Class;", &[])?.l()?;
+    let class_name = backend::string_getter(env, &class, "getName")?.unwrap_or_default();
+    let spec = IntentSpec {
+        component: Some(format!("{}/{}", context::package_name(env)?, class_name)),
+        flags: Flags::ACTIVITY_SINGLE_TOP.bits() as i32,
+        ..Default::default()
+    };
+    // The system adds the tag extras, so the intent must stay mutable.
+    let pending = pending::activity(env, &spec, 0, true)?;
+
+    env.call(
+        adapter,
+        "enableForegroundDispatch",
+        "(Landroid/app/Activity;Landroid/app/PendingIntent;[Landroid/content/IntentFilter;[[Ljava/lang/String;)V",
+        &[(&activity).into(), (&pending).into(), (&JObject::null()).into(), (&JObject::null()).into()],
+    )?;
+
+    Ok(true)
+}
+
+/// Undo [`enable_foreground_dispatch`].
+pub fn disable_foreground_dispatch(env: &mut JNIEnv) -> Result<(), Error> {
+    let adapter = default_adapter(env)?;
+    if adapter.is_null() {
+        return Ok(());
+    }
+
+    let activity = context::activity(env)?;
+    env.call(
+        adapter,
+        "disableForegroundDispatch",
+        "(Landroid/app/Activity;)V",
+        &[(&activity).into()],
+    )?;
+
+    Ok(())
+}
+
+/// `NfcAdapter.getDefaultAdapter`, null without NFC hardware.
+fn default_adapter<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+    let activity = context::activity(env)?;
+    let adapter = env
+        .call_static(
+            NFC_ADAPTER_CLASS,
+            "getDefaultAdapter",
+            "(Landroid/content/Context;)Landroid/nfc/NfcAdapter;",
+            &[(&activity).into()],
+        )?
+        .l()?;
+
+    Ok(adapter)
+}
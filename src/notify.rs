@@ -0,0 +1,150 @@
+//! `PendingIntent`s for notifications: the content intent opened by tapping the notification,
+//! and the intents behind its action buttons. [`ensure_permission`] makes sure they can be
+//! posted at all.
+//!
+//! Each uses `FLAG_UPDATE_CURRENT`, so posting again with the same request code refreshes the
+//! extras, and is immutable unless the system has to fill something in.
+//! ```no_run
+//! use android_intent::{notify, IntentSpec};
+//!
+//! # let mut intent_env = android_intent::IntentEnv::new();
+//! # android_intent::with_current_env(&mut intent_env, |mut env| {
+//! let thread = IntentSpec {
+//!     component: Some("com.example.app/.ThreadActivity".to_owned()),
+//!     data: Some("app://threads/42".to_owned()),
+//!     ..Default::default()
+//! };
+//! let content_intent = notify::content_intent(&mut env, &thread, 42).unwrap();
+//! // Pass `content_intent` to `Notification.Builder.setContentIntent`.
+//! # })
+//! ```
+
+use jni::objects::JObject;
+use jni::{AttachGuard, JNIEnv};
+
+use crate::permissions::{self, PermissionState};
+use crate::trace::Traced;
+use crate::{api_level, backend, compat, context, pending, settings, ApiLevel, Error, IntentSpec};
+
+const TASK_STACK_BUILDER_CLASS: &str = "android/app/TaskStackBuilder";
+const POST_NOTIFICATIONS: &str = "android.permission.POST_NOTIFICATIONS";
+
+/// Make sure this app may post notifications, asking for `POST_NOTIFICATIONS` on API 33+ if
+/// needed. Blocks until the user answers, so call it from a worker thread, see
+/// [`permissions::request`].
+///
+/// If the permission is permanently denied and `open_settings` is set, this opens
+/// [`settings::app_notification_settings`], the only place left to turn them on. Before API 33
+/// there is nothing to ask for; notifications the user turned off report
+/// [`PermissionState::PermanentlyDenied`].
+/// ```no_run
+/// use android_intent::notify;
+/// use android_intent::permissions::PermissionState;
+///
+/// std::thread::spawn(|| {
+///     let intent_env = android_intent::IntentEnv::new();
+///     if notify::ensure_permission(intent_env.get_env(), true).unwrap() != PermissionState::Granted {
+///         log::info!("reminders won't be shown");
+///     }
+/// });
+/// ```
+pub fn ensure_permission(mut env: AttachGuard<'_>, open_settings: bool) -> Result<PermissionState, Error> {
+    let state = if ApiLevel(33).is_supported(&mut env)? {
+        match permissions::is_granted(&mut env, POST_NOTIFICATIONS)? {
+            true => PermissionState::Granted,
+            false => permissions::request(&mut env, &[POST_NOTIFICATIONS])?[0],
+        }
+    } else if ApiLevel(24).is_supported(&mut env)? {
+        let notification_manager = context::system_service(&mut env, "NOTIFICATION_SERVICE")?;
+        match env.call(notification_manager, "areNotificationsEnabled", "()Z", &[])?.z()? {
+            true => PermissionState::Granted,
+            false => PermissionState::PermanentlyDenied,
+        }
+    } else {
+        PermissionState::Granted
+    };
+
+    if state == PermissionState::PermanentlyDenied && open_settings {
+        settings::app_notification_settings(env).start_activity_on_ui_thread()?.wait()?;
+    }
+
+    Ok(state)
+}
+
+/// A `PendingIntent` opening the activity `target` with its parent activities, as declared with
+/// `android:parentActivityName`, on the back stack, so Back leads into the app rather than out
+/// of it.
+pub fn content_intent<'a>(env: &mut JNIEnv<'a>, target: &IntentSpec, request_code: i32) -> Result<JObject<'a>, Error> {
+    task_stack(env, &[], target, request_code)
+}
+
+/// Like [`content_intent`], with an explicit `back_stack` of activities (outermost first) in place
+/// of the manifest parents.
+pub fn content_intent_with_back_stack<'a>(
+    env: &mut JNIEnv<'a>,
+    back_stack: &[IntentSpec],
+    target: &IntentSpec,
+    request_code: i32,
+) -> Result<JObject<'a>, Error> {
+    task_stack(env, back_stack, target, request_code)
+}
+
+/// A `PendingIntent` broadcasting `target`, for an action button handled by a receiver without
+/// opening the app, e.g. "Mark as read".
+pub fn action<'a>(env: &mut JNIEnv<'a>, target: &IntentSpec, request_code: i32) -> Result<JObject<'a>, Error> {
+    pending::broadcast_with_flags(env, target, request_code, false, pending::FLAG_UPDATE_CURRENT)
+}
+
+/// Like [`action`], but mutable, as a button with a `RemoteInput` (inline reply) needs: the
+/// system adds the typed text to `target`. Make `target` explicit, since mutable implicit
+/// `PendingIntent`s are rejected from API 34 on.
+pub fn reply_action<'a>(env: &mut JNIEnv<'a>, target: &IntentSpec, request_code: i32) -> Result<JObject<'a>, Error> {
+    pending::broadcast_with_flags(env, target, request_code, true, pending::FLAG_UPDATE_CURRENT)
+}
+
+fn task_stack<'a>(
+    env: &mut JNIEnv<'a>,
+    back_stack: &[IntentSpec],
+    target: &IntentSpec,
+    request_code: i32,
+) -> Result<JObject<'a>, Error> {
+    let activity = context::activity(env)?;
+    let builder = env
+        .call_static(
+            TASK_STACK_BUILDER_CLASS,
+            "create",
+            "(Landroid/content/Context;)Landroid/app/TaskStackBuilder;",
+            &[(&activity).into()],
+        )?
+        .l()?;
+
+    for spec in back_stack {
+        let intent = backend::new_intent(env, spec)?;
+        env.call(
+            &builder,
+            "addNextIntent",
+            "(Landroid/content/Intent;)Landroid/app/TaskStackBuilder;",
+            &[(&intent).into()],
+        )?;
+    }
+    let intent = backend::new_intent(env, target)?;
+    let add = if back_stack.is_empty() { "addNextIntentWithParentStack" } else { "addNextIntent" };
+    env.call(
+        &builder,
+        add,
+        "(Landroid/content/Intent;)Landroid/app/TaskStackBuilder;",
+        &[(&intent).into()],
+    )?;
+
+    let flags = pending::FLAG_UPDATE_CURRENT | compat::pending_intent_flags(api_level::sdk_int_with(env)?, false);
+    let pending = env
+        .call(
+            &builder,
+            "getPendingIntent",
+            "(II)Landroid/app/PendingIntent;",
+            &[request_code.into(), flags.into()],
+        )?
+        .l()?;
+
+    Ok(pending)
+}
@@ -0,0 +1,68 @@
+//! A process-wide hook told about every launch, e.g. to feed analytics or an audit log.
+
+use std::sync::RwLock;
+
+use crate::{Error, IntentSpec};
+
+/// An observer for [`set_launch_observer`].
+pub type LaunchObserver = Box<dyn Fn(&IntentSpec, &LaunchOutcome) + Send + Sync>;
+
+static OBSERVER: RwLock<Option<LaunchObserver>> = RwLock::new(None);
+
+/// How a launch ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LaunchOutcome {
+    Started,
+    /// The launch, or an earlier builder step, failed with this error.
+    Failed(String),
+}
+
+impl LaunchOutcome {
+    pub(crate) fn from_result(result: Result<(), &Error>) -> Self {
+        match result {
+            Ok(()) => Self::Started,
+            Err(err) => Self::Failed(err.to_string()),
+        }
+    }
+}
+
+/// Call `observer` after every activity launch of an [`Intent`](crate::Intent), whichever
+/// `start_activity*` method it went through, with the launched intent and how the launch ended.
+/// Replaces the previous observer. Intents that failed to build are not reported, as there is no
+/// intent to describe.
+///
+/// Only activity launches are reported: intents handed to a service, such as
+/// [`messenger::bind`](crate::messenger::bind), or wrapped in a pending intent are not.
+///
+/// The observer runs on the launching thread, so keep it short, and don't set or clear the
+/// observer from within it. Launches posted to the UI thread are reported once the UI thread ran
+/// them, from a background thread.
+/// ```no_run
+/// use android_intent::LaunchOutcome;
+///
+/// android_intent::set_launch_observer(Box::new(|spec, outcome| {
+///     if let LaunchOutcome::Failed(err) = outcome {
+///         log::warn!("launching {:?} failed: {}", spec.action, err);
+///     }
+/// }));
+/// ```
+pub fn set_launch_observer(observer: LaunchObserver) {
+    *OBSERVER.write().unwrap() = Some(observer);
+}
+
+/// Stop reporting launches to the observer set with [`set_launch_observer`].
+pub fn clear_launch_observer() {
+    *OBSERVER.write().unwrap() = None;
+}
+
+/// Whether an observer is set, so launches only convert themselves to an [`IntentSpec`] when
+/// someone is listening.
+pub(crate) fn is_observed() -> bool {
+    OBSERVER.read().unwrap().is_some()
+}
+
+pub(crate) fn notify(spec: &IntentSpec, result: Result<(), &Error>) {
+    if let Some(observer) = &*OBSERVER.read().unwrap() {
+        observer(spec, &LaunchOutcome::from_result(result));
+    }
+}
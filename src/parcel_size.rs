@@ -0,0 +1,66 @@
+//! Measuring extras before they hit the binder transaction limit.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::backend::IntentBackend;
+use crate::trace::{debug, Traced};
+use crate::Error;
+
+const PARCEL_CLASS: &str = "android/os/Parcel";
+
+/// A limit for [`set_extras_size_limit`] that leaves room in the 1 MB binder buffer shared by
+/// all of the process's transactions in flight.
+pub const RECOMMENDED_EXTRAS_SIZE_LIMIT: usize = 500 * 1024;
+
+/// 0 while the guard is off.
+static LIMIT: AtomicUsize = AtomicUsize::new(0);
+
+/// Opt into checking the size of the extras before each launch. Launches whose extras parcel to
+/// more than `limit` bytes then fail with [`Error::ExtrasTooLarge`] instead of crashing with
+/// `TransactionTooLargeException`. `None` turns the check off again.
+/// ```no_run
+/// android_intent::set_extras_size_limit(Some(android_intent::RECOMMENDED_EXTRAS_SIZE_LIMIT));
+/// ```
+pub fn set_extras_size_limit(limit: Option<usize>) {
+    LIMIT.store(limit.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// The size of `intent`'s extras as written to a `Parcel`, which is what the launch sends over
+/// binder.
+pub(crate) fn extras_size(env: &mut JNIEnv, intent: &JObject) -> Result<usize, Error> {
+    let extras = env.call(intent, "getExtras", "()Landroid/os/Bundle;", &[])?.l()?;
+    if extras.is_null() {
+        return Ok(0);
+    }
+
+    let parcel = env.call_static(PARCEL_CLASS, "obtain", "()Landroid/os/Parcel;", &[])?.l()?;
+    let size = env
+        .call(&parcel, "writeBundle", "(Landroid/os/Bundle;)V", &[(&extras).into()])
+        .and_then(|_| env.call(&parcel, "dataSize", "()I", &[]))
+        .and_then(|size| size.i());
+    env.call(&parcel, "recycle", "()V", &[])?;
+
+    Ok(size? as usize)
+}
+
+/// Fail if the guard is on and the extras of `inner` exceed the limit. Mocks are never checked.
+pub(crate) fn check(inner: &mut dyn IntentBackend) -> Result<(), Error> {
+    let limit = LIMIT.load(Ordering::Relaxed);
+    if limit == 0 {
+        return Ok(());
+    }
+    let Some(jni) = inner.jni() else {
+        return Ok(());
+    };
+
+    let size = extras_size(&mut jni.env, &jni.object)?;
+    if size > limit {
+        debug!("extras parcel to {} bytes, over the limit of {}", size, limit);
+        return Err(Error::ExtrasTooLarge { size, limit });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use jni::{objects::JObject, JNIEnv};
+
+use crate::trace::Traced;
+use crate::{api_level, backend, compat, context, Error, IntentSpec};
+
+const PENDING_INTENT_CLASS: &str = "android/app/PendingIntent";
+pub(crate) const FLAG_UPDATE_CURRENT: i32 = 0x0800_0000;
+
+/// A `PendingIntent` that sends `spec` as a broadcast, via `PendingIntent.getBroadcast`. It must be
+/// `mutable` if the sender fills in extras.
+pub(crate) fn broadcast<'a>(env: &mut JNIEnv<'a>, spec: &IntentSpec, request_code: i32, mutable: bool) -> Result<JObject<'a>, Error> {
+    broadcast_with_flags(env, spec, request_code, mutable, 0)
+}
+
+/// [`broadcast`] with further `PendingIntent.FLAG_*` bits, e.g. `FLAG_UPDATE_CURRENT`.
+pub(crate) fn broadcast_with_flags<'a>(
+    env: &mut JNIEnv<'a>,
+    spec: &IntentSpec,
+    request_code: i32,
+    mutable: bool,
+    flags: i32,
+) -> Result<JObject<'a>, Error> {
+    let intent = backend::new_intent(env, spec)?;
+    let flags = flags | compat::pending_intent_flags(api_level::sdk_int_with(env)?, mutable);
+
+    let activity = context::activity(env)?;
+    let pending = env
+        .call_static(
+            PENDING_INTENT_CLASS,
+            "getBroadcast",
+            "(Landroid/content/Context;ILandroid/content/Intent;I)Landroid/app/PendingIntent;",
+            &[(&activity).into(), request_code.into(), (&intent).into(), flags.into()],
+        )?
+        .l()?;
+
+    Ok(pending)
+}
+
+/// A `PendingIntent` that starts the activity described by `spec`, via
+/// `PendingIntent.getActivity`. It must be `mutable` if the sender fills in extras.
+pub(crate) fn activity<'a>(env: &mut JNIEnv<'a>, spec: &IntentSpec, request_code: i32, mutable: bool) -> Result<JObject<'a>, Error> {
+    let intent = backend::new_intent(env, spec)?;
+    let flags = compat::pending_intent_flags(api_level::sdk_int_with(env)?, mutable);
+
+    let activity = context::activity(env)?;
+    let pending = env
+        .call_static(
+            PENDING_INTENT_CLASS,
+            "getActivity",
+            "(Landroid/content/Context;ILandroid/content/Intent;I)Landroid/app/PendingIntent;",
+            &[(&activity).into(), request_code.into(), (&intent).into(), flags.into()],
+        )?
+        .l()?;
+
+    Ok(pending)
+}
+
+/// The `IntentSender` of an immutable `PendingIntent` that broadcasts `spec`, for APIs that report
+/// back through one.
+pub(crate) fn broadcast_sender<'a>(env: &mut JNIEnv<'a>, spec: &IntentSpec, request_code: i32) -> Result<JObject<'a>, Error> {
+    let pending = broadcast(env, spec, request_code, false)?;
+
+    let sender = env
+        .call(&pending, "getIntentSender", "()Landroid/content/IntentSender;", &[])?
+        .l()?;
+
+    Ok(sender)
+}
@@ -0,0 +1,152 @@
+//! Opt-in persistence of launches that still wait for their activity result, so a result that
+//! arrives after the process was killed and restarted can still be matched to what was launched.
+//!
+//! Once enabled, every successful [`Intent::start_activity_for_result`] stores its request code
+//! and [`IntentSpec`] in a file. Look the spec up again with [`take`] when the result arrives.
+//! Extras that `am` can't express (bundles, parcelables, ...) are not persisted.
+//! ```no_run
+//! use android_intent::pending_results;
+//!
+//! let intent_env = android_intent::IntentEnv::new();
+//! pending_results::enable(&mut intent_env.get_env()).unwrap();
+//!
+//! // After a restart:
+//! # let mut intent = android_intent::Intent::new(intent_env.get_env(), android_intent::Action::Send);
+//! if let Some(result) = intent.get_result(intent_env.get_env()).unwrap() {
+//!     let launched = pending_results::take(result.request_code).unwrap();
+//!     log::info!("{:?} finished with {}", launched.and_then(|spec| spec.action), result.result_code);
+//! };
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use jni::JNIEnv;
+
+use crate::trace::{debug, Traced};
+use crate::{bundle, context, Error, Intent, IntentSpec};
+
+const FILE_NAME: &str = "android-intent-pending-results";
+
+static STORE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// A launch still waiting for its result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingResult {
+    pub request_code: i32,
+    pub spec: IntentSpec,
+}
+
+/// Persist pending launches in the app's files directory (`Context.getFilesDir()`).
+pub fn enable(env: &mut JNIEnv) -> Result<(), Error> {
+    let activity = context::activity(env)?;
+    let files_dir = env
+        .call(&activity, "getFilesDir", "()Ljava/io/File;", &[])?
+        .l()?;
+    let files_dir = bundle::to_string(env, &files_dir)?;
+
+    enable_at(PathBuf::from(files_dir).join(FILE_NAME));
+    Ok(())
+}
+
+/// Persist pending launches in `path` instead.
+pub fn enable_at(path: impl Into<PathBuf>) {
+    *STORE.lock().unwrap() = Some(path.into());
+}
+
+/// Stop persisting launches. Already persisted ones stay on disk.
+pub fn disable() {
+    *STORE.lock().unwrap() = None;
+}
+
+/// All launches still waiting for their result, oldest first. Empty unless enabled.
+pub fn pending() -> io::Result<Vec<PendingResult>> {
+    match &*STORE.lock().unwrap() {
+        Some(path) => load(path),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Remove the launch with `request_code`, returning what was launched.
+pub fn take(request_code: i32) -> io::Result<Option<IntentSpec>> {
+    update(|pending| {
+        let index = pending.iter().position(|launch| launch.request_code == request_code)?;
+        Some(pending.remove(index).spec)
+    })
+    .map(Option::flatten)
+}
+
+/// Forget all pending launches.
+pub fn clear() -> io::Result<()> {
+    update(|pending| pending.clear()).map(drop)
+}
+
+/// Store `intent`'s launch, replacing an earlier one with the same request code. Failures are
+/// only logged, since they must not fail the launch itself.
+pub(crate) fn persist(intent: &mut Intent<'_>, request_code: i32) {
+    if STORE.lock().unwrap().is_none() {
+        return;
+    }
+
+    let spec = match intent.to_spec() {
+        Ok(spec) => spec,
+        Err(err) => {
+            debug!("not persisting launch {}: {}", request_code, err);
+            return;
+        }
+    };
+
+    let stored = update(|pending| {
+        pending.retain(|launch| launch.request_code != request_code);
+        pending.push(PendingResult { request_code, spec });
+    });
+    if let Err(err) = stored {
+        debug!("failed to persist launch {}: {}", request_code, err);
+    }
+}
+
+/// Apply `f` to the stored launches and write them back. `None` if not enabled.
+fn update<T>(f: impl FnOnce(&mut Vec<PendingResult>) -> T) -> io::Result<Option<T>> {
+    let store = STORE.lock().unwrap();
+    let path = match &*store {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    let mut pending = load(path)?;
+    let result = f(&mut pending);
+
+    let contents: String = pending
+        .iter()
+        .map(|launch| format!("{}\t{}\0", launch.request_code, launch.spec.to_am_command()))
+        .collect();
+    fs::write(path, contents)?;
+
+    Ok(Some(result))
+}
+
+/// One launch per NUL-terminated record, since commands may contain newlines: the request code,
+/// a tab, and the `am start` command of the spec.
+fn load(path: &Path) -> io::Result<Vec<PendingResult>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    contents
+        .split_terminator('\0')
+        .map(|line| {
+            let (request_code, command) = line.split_once('\t').unwrap_or((line, ""));
+            let request_code = request_code.parse().map_err(|_| corrupt(line))?;
+            let spec = IntentSpec::from_am_command(command).map_err(|_| corrupt(line))?;
+            Ok(PendingResult { request_code, spec })
+        })
+        .collect()
+}
+
+fn corrupt(line: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("corrupt pending result: {}", line))
+}
@@ -0,0 +1,137 @@
+//! Runtime permissions: checking them and asking the user for them.
+//!
+//! Requests go through the [bridge] activity, which receives the answer in
+//! `onRequestPermissionsResult`. [`request`] blocks until the user answers, so call it from a
+//! worker thread, never the UI thread.
+//! ```no_run
+//! use android_intent::permissions::{self, PermissionState};
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! # let mut env = intent_env.get_env();
+//! let camera = "android.permission.CAMERA";
+//! if !permissions::is_granted(&mut env, camera).unwrap() {
+//!     match permissions::request(&mut env, &[camera]).unwrap()[0] {
+//!         PermissionState::Granted => {}
+//!         PermissionState::Denied => log::info!("camera denied, may ask again"),
+//!         PermissionState::PermanentlyDenied => log::info!("camera denied, only settings can grant it"),
+//!     }
+//! }
+//! ```
+
+use jni::objects::{JIntArray, JObject};
+use jni::JNIEnv;
+
+use crate::trace::{self, Traced};
+use crate::{bridge, context, is_main_thread, ApiLevel, Error};
+
+const PERMISSION_GRANTED: i32 = 0;
+
+/// The outcome of a permission request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PermissionState {
+    Granted,
+    /// Denied, but the app may ask again, ideally after explaining why it needs the permission.
+    Denied,
+    /// Denied without the system showing the dialog again ("don't ask again", or denied twice
+    /// from API 30 on), or not requestable at runtime at all. Only the app's settings screen can
+    /// grant it now.
+    PermanentlyDenied,
+}
+
+/// Whether this app holds `permission`, e.g. `android.permission.CAMERA`, via
+/// `Context.checkPermission` for this process.
+pub fn is_granted(env: &mut JNIEnv, permission: &str) -> Result<bool, Error> {
+    trace::checked(env, |env| {
+        let pid = env.call_static("android/os/Process", "myPid", "()I", &[])?.i()?;
+        let uid = env.call_static("android/os/Process", "myUid", "()I", &[])?.i()?;
+        let jpermission = env.new_string(permission)?;
+
+        let activity = context::activity(env)?;
+        let result = env
+            .call(
+                &activity,
+                "checkPermission",
+                "(Ljava/lang/String;II)I",
+                &[(&jpermission).into(), pid.into(), uid.into()],
+            )?
+            .i()?;
+
+        Ok(result == PERMISSION_GRANTED)
+    })
+}
+
+/// Whether the app should explain why it needs `permission` before asking, via
+/// `Activity.shouldShowRequestPermissionRationale` (API 23+). Always `false` before API 23.
+pub fn should_show_rationale(env: &mut JNIEnv, permission: &str) -> Result<bool, Error> {
+    trace::checked(env, |env| {
+        if !ApiLevel(23).is_supported(env)? {
+            return Ok(false);
+        }
+        let jpermission = env.new_string(permission)?;
+
+        let activity = context::activity(env)?;
+        let show = env
+            .call(
+                &activity,
+                "shouldShowRequestPermissionRationale",
+                "(Ljava/lang/String;)Z",
+                &[(&jpermission).into()],
+            )?
+            .z()?;
+
+        Ok(show)
+    })
+}
+
+/// Ask the user for `permissions`, via `Activity.requestPermissions` (API 23+), and block until
+/// they answer. Returns one state per permission, in order.
+///
+/// Before API 23, permissions are granted at install time, so this only checks them; one
+/// missing then is [`PermissionState::PermanentlyDenied`]. A request interrupted before the user
+/// answered, e.g. by the activity being destroyed, reports [`PermissionState::Denied`].
+pub fn request(env: &mut JNIEnv, permissions: &[&str]) -> Result<Vec<PermissionState>, Error> {
+    trace::checked(env, |env| {
+        if !ApiLevel(23).is_supported(env)? {
+            return permissions
+                .iter()
+                .map(|permission| match is_granted(env, permission)? {
+                    true => Ok(PermissionState::Granted),
+                    false => Ok(PermissionState::PermanentlyDenied),
+                })
+                .collect();
+        }
+        if is_main_thread(env)? {
+            return Err(Error::WrongThread("requesting permissions blocks until the user answers"));
+        }
+
+        let array = env.new_object_array(permissions.len() as i32, "java/lang/String", JObject::null())?;
+        for (i, permission) in permissions.iter().enumerate() {
+            let permission = env.new_string(permission)?;
+            env.set_object_array_element(&array, i as i32, permission)?;
+        }
+        let results = env.new_object("java/util/concurrent/LinkedBlockingQueue", "()V", &[])?;
+        let activity = context::activity(env)?;
+        env.call(
+            &activity,
+            bridge::REQUEST_PERMISSIONS,
+            bridge::REQUEST_PERMISSIONS_SIG,
+            &[(&array).into(), (&results).into()],
+        )?;
+
+        let grant_results: JIntArray = env.call(&results, "take", "()Ljava/lang/Object;", &[])?.l()?.into();
+        let mut grants = vec![0; env.get_array_length(&grant_results)? as usize];
+        env.get_int_array_region(&grant_results, 0, &mut grants)?;
+        let interrupted = grants.len() != permissions.len();
+
+        permissions
+            .iter()
+            .zip(grants.into_iter().map(Some).chain(std::iter::repeat(None)))
+            .map(|(permission, grant)| match grant {
+                Some(PERMISSION_GRANTED) => Ok(PermissionState::Granted),
+                // Once the system stops showing the dialog, it also stops asking for a rationale.
+                _ if !interrupted && !should_show_rationale(env, permission)? => Ok(PermissionState::PermanentlyDenied),
+                _ => Ok(PermissionState::Denied),
+            })
+            .collect()
+    })
+}
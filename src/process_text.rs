@@ -0,0 +1,98 @@
+//! `Intent.ACTION_PROCESS_TEXT` (API 23+), the "process selected text" entries of the text
+//! selection toolbar, in both directions.
+//!
+//! To be such an entry, declare an activity with an intent filter for
+//! `android.intent.action.PROCESS_TEXT` and type `text/plain`, then answer the incoming request:
+//! ```no_run
+//! use android_intent::process_text;
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! # let mut intent = android_intent::Intent::new(intent_env.get_env(), android_intent::Action::View);
+//! // `intent` is the one the activity was started with.
+//! if let Some(request) = process_text::incoming(&mut intent).unwrap() {
+//!     if !request.readonly {
+//!         process_text::reply(&mut intent_env.get_env(), &request.text.to_uppercase()).unwrap();
+//!     }
+//! }
+//! ```
+
+use jni::{AttachGuard, JNIEnv};
+
+use crate::backend::INTENT_CLASS;
+use crate::trace::Traced;
+use crate::{context, mime, ApiLevel, CompletedIntent, Error, Intent, RESULT_OK};
+
+const ACTION_PROCESS_TEXT: &str = "android.intent.action.PROCESS_TEXT";
+const EXTRA_PROCESS_TEXT: &str = "android.intent.extra.PROCESS_TEXT";
+const EXTRA_PROCESS_TEXT_READONLY: &str = "android.intent.extra.PROCESS_TEXT_READONLY";
+
+/// Text another app asked this one to process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcessTextRequest {
+    pub text: String,
+    /// The text can't be replaced, so there's no point in [`reply`]ing.
+    pub readonly: bool,
+}
+
+/// Offer `text` to the apps that process text, e.g. translators. Unless `readonly`, the
+/// replacement arrives with `request_code`; read it with [`processed_text`].
+pub fn process<'env>(
+    mut env: AttachGuard<'env>,
+    text: &str,
+    readonly: bool,
+    request_code: i32,
+) -> Result<Intent<'env>, Error> {
+    ApiLevel(23).require(&mut env)?;
+
+    Intent::new(env, "ACTION_PROCESS_TEXT")
+        .with_type(mime::TEXT_PLAIN)
+        .with_extra_char_sequence(EXTRA_PROCESS_TEXT, text)
+        .with_extra_bool(EXTRA_PROCESS_TEXT_READONLY, readonly)
+        .start_activity_for_result(request_code)
+}
+
+/// The replacement text of a [`process`] result, or `None` if the user cancelled or the app
+/// returned nothing.
+pub fn processed_text(result: &mut CompletedIntent<'_>) -> Result<Option<String>, Error> {
+    if !result.is_ok() {
+        return Ok(None);
+    }
+
+    result.data.get_char_sequence_extra(EXTRA_PROCESS_TEXT)
+}
+
+/// The request in `intent`, or `None` if it isn't an `ACTION_PROCESS_TEXT` intent.
+pub fn incoming(intent: &mut Intent) -> Result<Option<ProcessTextRequest>, Error> {
+    if intent.to_spec()?.action.as_deref() != Some(ACTION_PROCESS_TEXT) {
+        return Ok(None);
+    }
+
+    let text = intent.get_char_sequence_extra(EXTRA_PROCESS_TEXT)?.unwrap_or_default();
+    let readonly = intent.get::<Option<bool>>(EXTRA_PROCESS_TEXT_READONLY)?.unwrap_or(false);
+
+    Ok(Some(ProcessTextRequest { text, readonly }))
+}
+
+/// Answer an [`incoming`] request with `replacement`, via `Activity.setResult`. The activity
+/// still has to finish for the caller to receive it.
+pub fn reply(env: &mut JNIEnv, replacement: &str) -> Result<(), Error> {
+    let data = env.new_object(INTENT_CLASS, "()V", &[])?;
+    let key = env.new_string(EXTRA_PROCESS_TEXT)?;
+    let replacement = env.new_string(replacement)?;
+    env.call(
+        &data,
+        "putExtra",
+        "(Ljava/lang/String;Ljava/lang/CharSequence;)Landroid/content/Intent;",
+        &[(&key).into(), (&replacement).into()],
+    )?;
+
+    let activity = context::activity(env)?;
+    env.call(
+        &activity,
+        "setResult",
+        "(ILandroid/content/Intent;)V",
+        &[RESULT_OK.into(), (&data).into()],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Launching this app in the user's other profiles (e.g. work and personal), via
+//! `CrossProfileApps` (API 28+).
+//!
+//! ```no_run
+//! use android_intent::profiles;
+//!
+//! # let mut intent_env = android_intent::IntentEnv::new();
+//! # android_intent::with_current_env(&mut intent_env, |mut env| {
+//! for profile in profiles::target_user_profiles(&mut env).unwrap() {
+//!     println!("{}", profile.label);
+//!     profiles::start_main_activity(&mut env, "com.example.app/.MainActivity", &profile).unwrap();
+//! }
+//! # })
+//! ```
+use jni::objects::{GlobalRef, JObject};
+use jni::JNIEnv;
+
+use crate::trace::Traced;
+use crate::{backend, bundle, context, ApiLevel, Error};
+
+/// Another profile of the user in which this app is installed.
+#[derive(Clone, Debug)]
+pub struct UserProfile {
+    /// Label for switching to the profile, e.g. "Switch to work app".
+    pub label: String,
+    handle: GlobalRef,
+}
+
+fn cross_profile_apps<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+    ApiLevel(28).require(env)?;
+    context::system_service(env, "CROSS_PROFILE_APPS_SERVICE")
+}
+
+/// The profiles this app can be launched in, via `CrossProfileApps.getTargetUserProfiles`.
+pub fn target_user_profiles(env: &mut JNIEnv) -> Result<Vec<UserProfile>, Error> {
+    let apps = cross_profile_apps(env)?;
+    let handles = env.call(&apps, "getTargetUserProfiles", "()Ljava/util/List;", &[])?.l()?;
+
+    let mut profiles = Vec::new();
+    for handle in bundle::collection(env, &handles)? {
+        let label = env
+            .call(
+                &apps,
+                "getProfileSwitchingLabel",
+                "(Landroid/os/UserHandle;)Ljava/lang/CharSequence;",
+                &[(&handle).into()],
+            )?
+            .l()?;
+
+        profiles.push(UserProfile {
+            label: bundle::to_string(env, &label)?,
+            handle: env.new_global_ref(handle)?,
+        });
+    }
+
+    Ok(profiles)
+}
+
+/// Start the launcher activity `component` (e.g. `com.example.app/.MainActivity`) of this app in
+/// `profile`, via `CrossProfileApps.startMainActivity`.
+pub fn start_main_activity(env: &mut JNIEnv, component: &str, profile: &UserProfile) -> Result<(), Error> {
+    let apps = cross_profile_apps(env)?;
+    let component = backend::component_name(env, component)?;
+
+    env.call(
+        &apps,
+        "startMainActivity",
+        "(Landroid/content/ComponentName;Landroid/os/UserHandle;)V",
+        &[(&component).into(), profile.handle.as_obj().into()],
+    )?;
+
+    Ok(())
+}
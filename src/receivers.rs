@@ -0,0 +1,103 @@
+//! Broadcast receivers registered at runtime, queueing the broadcasts they receive for Rust to
+//! read.
+//!
+//! Needs the [bridge] activity, which implements the `BroadcastReceiver`.
+//! Broadcasts are delivered on the UI thread, so don't block it on [`Receiver::recv`].
+//! ```no_run
+//! use android_intent::receivers;
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! # let mut env = intent_env.get_env();
+//! // Only apps holding the permission may send, and only this app on API 33+ without it.
+//! let receiver = receivers::register(
+//!     &mut env,
+//!     &["com.example.app.SYNC_DONE"],
+//!     false,
+//!     Some("com.example.app.permission.SYNC"),
+//! )
+//! .unwrap();
+//! let broadcast = receiver.recv(&mut env).unwrap();
+//! log::info!("sync done: {:?}", broadcast.extras);
+//! receiver.unregister(&mut env).unwrap();
+//! ```
+
+use jni::objects::{GlobalRef, JObject};
+use jni::JNIEnv;
+
+use crate::trace::{self, Traced};
+use crate::{api_level, backend, bridge, compat, context, Error, IntentSpec};
+
+/// A receiver from [`register`]. Unregister it before the activity is destroyed.
+pub struct Receiver {
+    receiver: GlobalRef,
+    received: GlobalRef,
+}
+
+/// Register a receiver for broadcasts with any of `actions`, via `Context.registerReceiver`.
+///
+/// Unless `exported`, only this app and the system can send to it from API 33 on; apps
+/// targeting API 34 must decide. With a `permission`, senders must also hold it.
+pub fn register(env: &mut JNIEnv, actions: &[&str], exported: bool, permission: Option<&str>) -> Result<Receiver, Error> {
+    trace::checked(env, |env| {
+        let filter = env.new_object("android/content/IntentFilter", "()V", &[])?;
+        for action in actions {
+            let action = env.new_string(action)?;
+            env.call(&filter, "addAction", "(Ljava/lang/String;)V", &[(&action).into()])?;
+        }
+        let permission = match permission {
+            Some(permission) => env.new_string(permission)?.into(),
+            None => JObject::null(),
+        };
+        let flags = compat::receiver_flags(api_level::sdk_int_with(env)?, exported);
+
+        let received = env.new_object("java/util/concurrent/LinkedBlockingQueue", "()V", &[])?;
+        let activity = context::activity(env)?;
+        let receiver = env
+            .call(
+                &activity,
+                bridge::REGISTER_QUEUE_RECEIVER,
+                bridge::REGISTER_QUEUE_RECEIVER_SIG,
+                &[(&filter).into(), (&permission).into(), flags.into(), (&received).into()],
+            )?
+            .l()?;
+
+        Ok(Receiver { receiver: env.new_global_ref(receiver)?, received: env.new_global_ref(received)? })
+    })
+}
+
+impl Receiver {
+    /// Block until the next broadcast arrives.
+    pub fn recv(&self, env: &mut JNIEnv) -> Result<IntentSpec, Error> {
+        trace::checked(env, |env| {
+            let intent = env.call(self.received.as_obj(), "take", "()Ljava/lang/Object;", &[])?.l()?;
+            backend::spec_of(env, &intent)
+        })
+    }
+
+    /// The next broadcast, if one has arrived.
+    pub fn try_recv(&self, env: &mut JNIEnv) -> Result<Option<IntentSpec>, Error> {
+        trace::checked(env, |env| {
+            let intent = env.call(self.received.as_obj(), "poll", "()Ljava/lang/Object;", &[])?.l()?;
+            if intent.is_null() {
+                return Ok(None);
+            }
+
+            backend::spec_of(env, &intent).map(Some)
+        })
+    }
+
+    /// Stop receiving, via `Context.unregisterReceiver`.
+    pub fn unregister(self, env: &mut JNIEnv) -> Result<(), Error> {
+        trace::checked(env, |env| {
+            let activity = context::activity(env)?;
+            env.call(
+                &activity,
+                "unregisterReceiver",
+                "(Landroid/content/BroadcastReceiver;)V",
+                &[self.receiver.as_obj().into()],
+            )?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,474 @@
+//! Ready-made intents for common tasks.
+
+use jni::{objects::{GlobalRef, JObject}, AttachGuard, JNIEnv};
+
+use crate::trace::Traced;
+
+use crate::{api_level, backend, compat, context, mime, settings, uri, Action, Category, CompletedIntent, Error, Extra, Intent, IntentSpec, Parcelable, Uri, RESULT_OK};
+
+const MEDIA_STORE_CLASS: &str = "android/provider/MediaStore";
+const BLUETOOTH_ADAPTER_CLASS: &str = "android/bluetooth/BluetoothAdapter";
+const EXTRA_DISCOVERABLE_DURATION: &str = "android.bluetooth.adapter.extra.DISCOVERABLE_DURATION";
+
+const EXTRA_RETURN_RESULT: &str = "android.intent.extra.RETURN_RESULT";
+
+const DEVICE_POLICY_MANAGER_CLASS: &str = "android/app/admin/DevicePolicyManager";
+const EXTRA_DEVICE_ADMIN: &str = "android.app.extra.DEVICE_ADMIN";
+const EXTRA_ADD_EXPLANATION: &str = "android.app.extra.ADD_EXPLANATION";
+
+const ACTION_APPLICATION_PREFERENCES: &str = "android.intent.action.APPLICATION_PREFERENCES";
+
+const FILE_PROVIDER_CLASS: &str = "androidx.core.content.FileProvider";
+
+const RINGTONE_MANAGER_CLASS: &str = "android/media/RingtoneManager";
+const EXTRA_RINGTONE_TYPE: &str = "android.intent.extra.ringtone.TYPE";
+const EXTRA_RINGTONE_EXISTING_URI: &str = "android.intent.extra.ringtone.EXISTING_URI";
+const EXTRA_RINGTONE_SHOW_SILENT: &str = "android.intent.extra.ringtone.SHOW_SILENT";
+const EXTRA_RINGTONE_PICKED_URI: &str = "android.intent.extra.ringtone.PICKED_URI";
+
+/// Package name of the Google Play Store app.
+pub const PLAY_STORE_PACKAGE: &str = "com.android.vending";
+
+/// Outcome of [`request_enable_bluetooth`] or [`request_discoverable`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BluetoothOutcome {
+    /// The user turned Bluetooth on.
+    Enabled,
+    /// The device is discoverable for this many seconds.
+    Discoverable(u32),
+    /// The user declined.
+    Denied,
+}
+
+impl From<&CompletedIntent<'_>> for BluetoothOutcome {
+    fn from(result: &CompletedIntent<'_>) -> Self {
+        match result.result_code {
+            RESULT_OK => Self::Enabled,
+            duration if duration > 0 => Self::Discoverable(duration as u32),
+            _ => Self::Denied,
+        }
+    }
+}
+
+/// Ask the user to turn Bluetooth on. The result arrives with `request_code` and maps to a
+/// [`BluetoothOutcome`].
+/// ```no_run
+/// use android_intent::recipes::{self, BluetoothOutcome};
+///
+/// use android_intent::CompletedIntent;
+///
+/// let intent_env = android_intent::IntentEnv::new();
+/// recipes::request_enable_bluetooth(intent_env.get_env(), 1).unwrap();
+///
+/// // Once the result with request code 1 has arrived:
+/// fn is_enabled(result: &CompletedIntent) -> bool {
+///     BluetoothOutcome::from(result) == BluetoothOutcome::Enabled
+/// }
+/// ```
+pub fn request_enable_bluetooth(env: AttachGuard<'_>, request_code: i32) -> Result<Intent<'_>, Error> {
+    Intent::new_with_class_action(env, BLUETOOTH_ADAPTER_CLASS, "ACTION_REQUEST_ENABLE", None::<&str>)
+        .start_activity_for_result(request_code)
+}
+
+/// Ask the user to make this device discoverable for `duration_secs` seconds, turning Bluetooth
+/// on if needed. Durations beyond `i32::MAX` are clamped to it.
+pub fn request_discoverable(env: AttachGuard<'_>, duration_secs: u32, request_code: i32) -> Result<Intent<'_>, Error> {
+    Intent::new_with_class_action(env, BLUETOOTH_ADAPTER_CLASS, "ACTION_REQUEST_DISCOVERABLE", None::<&str>)
+        .with_extra_int(EXTRA_DISCOVERABLE_DURATION, i32::try_from(duration_secs).unwrap_or(i32::MAX))
+        .start_activity_for_result(request_code)
+}
+
+/// Share `html` through a chooser, as `EXTRA_HTML_TEXT` for receivers that understand HTML and
+/// as styled `EXTRA_TEXT` (via `Html.fromHtml`) for all others.
+/// ```no_run
+/// use android_intent::recipes;
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// recipes::share_html(intent_env.get_env(), "<b>Hello</b> World!").unwrap();
+/// ```
+pub fn share_html<'env>(env: AttachGuard<'env>, html: &str) -> Result<Intent<'env>, Error> {
+    Intent::new(env, Action::Send)
+        .with_type(mime::TEXT_HTML)
+        .with_extra_html(Extra::Text, html)
+        .with_extra(Extra::HtmlText, html)
+        .into_chooser()
+        .start_activity()
+}
+
+/// Let the user pick an image, using the system photo picker on API 33+ and an
+/// `ACTION_GET_CONTENT` document picker on older devices. The picked image's URI is the data of
+/// the result intent.
+pub fn pick_image(mut env: AttachGuard<'_>, request_code: i32) -> Result<Intent<'_>, Error> {
+    let intent = if compat::supports_photo_picker(api_level::sdk_int_with(&mut env)?) {
+        Intent::new_with_class_action(env, MEDIA_STORE_CLASS, "ACTION_PICK_IMAGES", None::<&str>)
+    } else {
+        Intent::new(env, Action::GetContent)
+            .with_type(mime::IMAGE_ANY)
+            .add_category(Category::Openable)
+    };
+
+    intent.start_activity_for_result(request_code)
+}
+
+/// Open the store listing of `package`, e.g. for a "rate this app" button.
+///
+/// Opens a `market://details` URI, which any installed app store can handle; with
+/// `play_store_only` it is restricted to [`PLAY_STORE_PACKAGE`]. If no store handles it, the
+/// Play Store web page is opened instead.
+/// ```no_run
+/// use android_intent::recipes;
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// recipes::open_store_listing(intent_env.get_env(), "com.example.app", true).unwrap();
+/// ```
+pub fn open_store_listing<'env>(env: AttachGuard<'env>, package: &str, play_store_only: bool) -> Result<Intent<'env>, Error> {
+    let web_url = format!("https://play.google.com/store/apps/details?id={}", uri::encode(package, ""));
+    open_store_page(env, Uri::market(package), &web_url, play_store_only)
+}
+
+/// Open the Play Store page listing every app of the developer `dev_id`, falling back to the web
+/// page like [`open_store_listing`].
+pub fn open_developer_page<'env>(env: AttachGuard<'env>, dev_id: &str, play_store_only: bool) -> Result<Intent<'env>, Error> {
+    let dev_id = uri::encode(dev_id, "");
+    let market_uri = Uri::new(format!("market://dev?id={}", dev_id));
+    open_store_page(env, market_uri, &format!("https://play.google.com/store/apps/dev?id={}", dev_id), play_store_only)
+}
+
+fn open_store_page<'env>(env: AttachGuard<'env>, market_uri: Uri, web_url: &str, play_store_only: bool) -> Result<Intent<'env>, Error> {
+    let mut intent = Intent::new_with_uri(env, Action::View, market_uri);
+    if play_store_only {
+        intent = intent.with_package(PLAY_STORE_PACKAGE);
+    }
+
+    intent.start_activity_or_uri(web_url)
+}
+
+/// Open the preferences screen of `package`, or of this app if `None`, where it declares one
+/// with an `ACTION_APPLICATION_PREFERENCES` filter (API 24+). Otherwise, opens its app details
+/// settings.
+///
+/// From API 30 on, another app's screen is only found if it is visible to this one, e.g. through
+/// a `<queries>` entry for the action in the manifest.
+/// ```no_run
+/// use android_intent::recipes;
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// recipes::open_app_preferences(intent_env.get_env(), Some("com.example.keyboard")).unwrap();
+/// ```
+pub fn open_app_preferences<'env>(mut env: AttachGuard<'env>, package: Option<&str>) -> Result<Intent<'env>, Error> {
+    let package = match package {
+        Some(package) => package.to_owned(),
+        None => context::package_name(&mut env)?,
+    };
+    let preferences = IntentSpec {
+        action: Some(ACTION_APPLICATION_PREFERENCES.to_owned()),
+        package: Some(package.clone()),
+        ..Default::default()
+    };
+
+    let sdk = api_level::sdk_int_with(&mut env)?;
+    if compat::supports_app_preferences(sdk) && resolves_activity(&mut env, &preferences)? {
+        return preferences.realize(env).start_activity();
+    }
+    settings::app_details(env, Some(&package)).start_activity()
+}
+
+/// Whether some activity handles `spec`, via `PackageManager.resolveActivity`.
+fn resolves_activity(env: &mut JNIEnv, spec: &IntentSpec) -> Result<bool, Error> {
+    let intent = backend::new_intent(env, spec)?;
+    let package_manager = context::package_manager(env)?;
+
+    let resolved = env
+        .call(
+            &package_manager,
+            "resolveActivity",
+            "(Landroid/content/Intent;I)Landroid/content/pm/ResolveInfo;",
+            &[(&intent).into(), 0.into()],
+        )?
+        .l()?;
+
+    Ok(!resolved.is_null())
+}
+
+/// Ask the user to uninstall `package`. The result arrives with `request_code`;
+/// [`CompletedIntent::is_ok`] tells whether the package was removed.
+///
+/// From API 28 on, this needs the `REQUEST_DELETE_PACKAGES` permission in the manifest.
+pub fn request_uninstall<'env>(env: AttachGuard<'env>, package: &str, request_code: i32) -> Result<Intent<'env>, Error> {
+    Intent::new_with_uri(env, Action::UninstallPackage, format!("package:{}", package))
+        .with_extra_bool(EXTRA_RETURN_RESULT, true)
+        .start_activity_for_result(request_code)
+}
+
+/// Ask the user to activate this app's device admin receiver `component` (e.g.
+/// `com.example/.AdminReceiver`), showing `explanation` on the confirmation screen. The result
+/// arrives with `request_code`; [`CompletedIntent::is_ok`] tells whether it was activated.
+pub fn activate_device_admin<'env>(
+    env: AttachGuard<'env>,
+    component: &str,
+    explanation: &str,
+    request_code: i32,
+) -> Result<Intent<'env>, Error> {
+    Intent::new_with_class_action(env, DEVICE_POLICY_MANAGER_CLASS, "ACTION_ADD_DEVICE_ADMIN", None::<&str>)
+        .with_extra_parcelable(EXTRA_DEVICE_ADMIN, Parcelable::Component(component.to_owned()))
+        .with_extra_char_sequence(EXTRA_ADD_EXPLANATION, explanation)
+        .start_activity_for_result(request_code)
+}
+
+/// Make sure the user allowed this app to act as a VPN, via `VpnService.prepare`, which is needed
+/// before `VpnService.Builder.establish` works.
+///
+/// Returns `Ok(None)` if the app is already allowed. Otherwise the consent dialog is started and
+/// its result arrives with `request_code`; [`CompletedIntent::is_ok`] tells whether the user
+/// consented.
+pub fn prepare_vpn(mut env: AttachGuard<'_>, request_code: i32) -> Result<Option<Intent<'_>>, Error> {
+    let activity = context::activity(&mut env)?;
+    let intent = env
+        .call_static(
+            "android/net/VpnService",
+            "prepare",
+            "(Landroid/content/Context;)Landroid/content/Intent;",
+            &[(&activity).into()],
+        )?
+        .l()?;
+    if intent.is_null() {
+        return Ok(None);
+    }
+
+    Intent::from_local(env, intent).start_activity_for_result(request_code).map(Some)
+}
+
+/// Ask the user for permission to capture the screen, via
+/// `MediaProjectionManager.createScreenCaptureIntent`. Turn the result arriving with
+/// `request_code` into a [`ScreenCaptureGrant`].
+/// ```no_run
+/// use android_intent::recipes::{self, ScreenCaptureGrant};
+///
+/// use android_intent::{CompletedIntent, IntentEnv};
+///
+/// let intent_env = IntentEnv::new();
+/// recipes::request_screen_capture(intent_env.get_env(), 1).unwrap();
+///
+/// // Once the result with request code 1 has arrived:
+/// fn on_result(intent_env: &IntentEnv, result: &mut CompletedIntent) {
+///     if let Some(grant) = ScreenCaptureGrant::from_result(result).unwrap() {
+///         let projection = grant.media_projection(&mut intent_env.get_env()).unwrap();
+///     }
+/// }
+/// ```
+pub fn request_screen_capture(mut env: AttachGuard<'_>, request_code: i32) -> Result<Intent<'_>, Error> {
+    let manager = context::system_service(&mut env, "MEDIA_PROJECTION_SERVICE")?;
+    let intent = env
+        .call(&manager, "createScreenCaptureIntent", "()Landroid/content/Intent;", &[])?
+        .l()?;
+
+    Intent::from_local(env, intent).start_activity_for_result(request_code)
+}
+
+/// The user's consent to capture the screen: the result code and data intent of
+/// [`request_screen_capture`], as `MediaProjectionManager.getMediaProjection` expects them.
+#[derive(Clone, Debug)]
+pub struct ScreenCaptureGrant {
+    pub result_code: i32,
+    /// The result's `android.content.Intent`.
+    pub data: GlobalRef,
+}
+
+impl ScreenCaptureGrant {
+    /// The grant in `result`, or `None` if the user declined.
+    pub fn from_result(result: &mut CompletedIntent<'_>) -> Result<Option<Self>, Error> {
+        if !result.is_ok() {
+            return Ok(None);
+        }
+
+        let inner = result.data.jni("ScreenCaptureGrant::from_result")?;
+        Ok(Some(Self {
+            result_code: result.result_code,
+            data: inner.env.new_global_ref(&inner.object)?,
+        }))
+    }
+
+    /// Start the `android.media.projection.MediaProjection`. From API 29 on, the app needs a
+    /// running foreground service of type `mediaProjection` first.
+    pub fn media_projection<'a>(&self, env: &mut JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+        let manager = context::system_service(env, "MEDIA_PROJECTION_SERVICE")?;
+        let projection = env
+            .call(
+                &manager,
+                "getMediaProjection",
+                "(ILandroid/content/Intent;)Landroid/media/projection/MediaProjection;",
+                &[self.result_code.into(), self.data.as_obj().into()],
+            )?
+            .l()?;
+
+        Ok(projection)
+    }
+}
+
+/// Ask the user to install the APK at `path`, e.g. a downloaded update of a sideloaded app.
+///
+/// The file is shared through the app's `androidx.core.content.FileProvider` registered under
+/// `authority`, whose paths must cover `path`. The manifest also needs the
+/// `REQUEST_INSTALL_PACKAGES` permission.
+///
+/// Returns `Ok(None)` without launching anything on API 26+ if the user hasn't allowed this app
+/// to install packages; send them to [`settings::manage_unknown_app_sources`] and try again.
+/// ```no_run
+/// use android_intent::{recipes, settings};
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// let apk = "/data/data/com.example.app/cache/update.apk";
+/// if recipes::install_apk(intent_env.get_env(), apk, "com.example.app.fileprovider").unwrap().is_none() {
+///     settings::manage_unknown_app_sources(intent_env.get_env()).start_activity().unwrap();
+/// }
+/// ```
+pub fn install_apk<'env>(mut env: AttachGuard<'env>, path: &str, authority: &str) -> Result<Option<Intent<'env>>, Error> {
+    let sdk = api_level::sdk_int_with(&mut env)?;
+    if compat::restricts_package_installs(sdk) && !settings::can_request_package_installs(&mut env)? {
+        return Ok(None);
+    }
+
+    let uri = file_provider_uri(&mut env, path, authority)?;
+
+    Intent::new(env, Action::View)
+        .with_data_and_type(uri, mime::APPLICATION_APK)
+        .grant_read()
+        .start_activity()
+        .map(Some)
+}
+
+/// Open the local file at `path` with whichever app handles its type, e.g. a PDF viewer. The MIME
+/// type is guessed from the extension, falling back to `*/*`; if no app handles it, a chooser
+/// tells the user so instead of failing.
+///
+/// Like [`install_apk`], the file is shared through the app's `FileProvider` registered under
+/// `authority`, whose paths must cover `path`.
+/// ```no_run
+/// use android_intent::recipes;
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// let report = "/data/data/com.example.app/files/report.pdf";
+/// recipes::open_file(intent_env.get_env(), report, "com.example.app.fileprovider").unwrap();
+/// ```
+pub fn open_file<'env>(mut env: AttachGuard<'env>, path: &str, authority: &str) -> Result<Intent<'env>, Error> {
+    let mime_type = mime::from_path(&mut env, path)?.unwrap_or_else(|| mime::ANY.as_str().to_owned());
+    let uri = file_provider_uri(&mut env, path, authority)?;
+
+    Intent::new(env, Action::View)
+        .with_data_and_type(uri, mime_type)
+        .grant_read()
+        .start_activity_or_chooser()
+}
+
+/// A `content://` URI for `path` from `FileProvider.getUriForFile`.
+pub(crate) fn file_provider_uri(env: &mut JNIEnv, path: &str, authority: &str) -> Result<String, Error> {
+    let file_provider = context::load_class(env, FILE_PROVIDER_CLASS)?;
+
+    let jpath = env.new_string(path)?;
+    let file = env.new_object("java/io/File", "(Ljava/lang/String;)V", &[(&jpath).into()])?;
+    let authority = env.new_string(authority)?;
+
+    let activity = context::activity(env)?;
+    let uri = env
+        .call_static_on(
+            &file_provider,
+            FILE_PROVIDER_CLASS,
+            "getUriForFile",
+            "(Landroid/content/Context;Ljava/lang/String;Ljava/io/File;)Landroid/net/Uri;",
+            &[(&activity).into(), (&authority).into(), (&file).into()],
+        )?
+        .l()?;
+
+    Ok(backend::string_getter(env, &uri, "toString")?.unwrap_or_default())
+}
+
+/// Let the user crop the image at the `content://` `uri` and set it as wallpaper, via
+/// `WallpaperManager.getCropAndSetWallpaperIntent`. If that rejects `uri`, as it does for anything
+/// but content URIs of images, a chooser of wallpaper pickers is shown instead. The result
+/// arrives with `request_code`; [`CompletedIntent::is_ok`] tells whether a wallpaper was applied.
+///
+/// Without an app that crops wallpapers the launch fails, with an `ActivityNotFoundException` as
+/// the [root cause](Error::root_cause).
+pub fn set_wallpaper<'env>(mut env: AttachGuard<'env>, uri: &str, request_code: i32) -> Result<Intent<'env>, Error> {
+    let intent = match crop_and_set_wallpaper_intent(&mut env, uri) {
+        Ok(object) => Intent::from_local(env, object),
+        Err(Error::Jni(jni::errors::Error::JavaException)) => {
+            // IllegalArgumentException: `uri` is no content URI, or not of an image type.
+            env.exception_clear()?;
+            Intent::new(env, Action::SetWallpaper).into_chooser()
+        }
+        Err(err) => return Err(err),
+    };
+
+    intent.start_activity_for_result(request_code)
+}
+
+fn crop_and_set_wallpaper_intent<'a>(env: &mut JNIEnv<'a>, uri: &str) -> Result<JObject<'a>, Error> {
+    let activity = context::activity(env)?;
+    let manager = env
+        .call_static(
+            "android/app/WallpaperManager",
+            "getInstance",
+            "(Landroid/content/Context;)Landroid/app/WallpaperManager;",
+            &[(&activity).into()],
+        )?
+        .l()?;
+    let uri = backend::parse_uri(env, uri)?;
+
+    let intent = env
+        .call(
+            manager,
+            "getCropAndSetWallpaperIntent",
+            "(Landroid/net/Uri;)Landroid/content/Intent;",
+            &[(&uri).into()],
+        )?
+        .l()?;
+
+    Ok(intent)
+}
+
+/// Which sounds [`pick_ringtone`] offers, see `RingtoneManager.TYPE_*`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RingtoneType {
+    Ringtone,
+    Notification,
+    Alarm,
+    All,
+}
+
+impl RingtoneType {
+    fn value(self) -> i32 {
+        match self {
+            Self::Ringtone => 1,
+            Self::Notification => 2,
+            Self::Alarm => 4,
+            Self::All => 7,
+        }
+    }
+}
+
+/// Let the user pick a sound of `ringtone_type` with the system ringtone picker, preselecting
+/// `existing` and offering a "Silent" entry if `show_silent` is set. The result arrives with
+/// `request_code`; read the choice with [`picked_ringtone`].
+pub fn pick_ringtone<'env>(
+    env: AttachGuard<'env>,
+    ringtone_type: RingtoneType,
+    existing: Option<Uri>,
+    show_silent: bool,
+    request_code: i32,
+) -> Result<Intent<'env>, Error> {
+    let mut intent = Intent::new_with_class_action(env, RINGTONE_MANAGER_CLASS, "ACTION_RINGTONE_PICKER", None::<&str>)
+        .with_extra_int(EXTRA_RINGTONE_TYPE, ringtone_type.value())
+        .with_extra_bool(EXTRA_RINGTONE_SHOW_SILENT, show_silent);
+    if let Some(existing) = existing {
+        intent = intent.with_extra_uri(EXTRA_RINGTONE_EXISTING_URI, existing);
+    }
+
+    intent.start_activity_for_result(request_code)
+}
+
+/// The sound picked in a [`pick_ringtone`] result, or `None` for "Silent". Check
+/// [`CompletedIntent::is_ok`] first, since a cancelled picker returns no choice at all.
+pub fn picked_ringtone(result: &mut CompletedIntent<'_>) -> Result<Option<Uri>, Error> {
+    result.get(EXTRA_RINGTONE_PICKED_URI)
+}
@@ -0,0 +1,64 @@
+//! An in-memory log of every intent launch, enabled by the `record` feature.
+//!
+//! Instrumented tests can assert on what the app tried to launch without intercepting anything
+//! at the OS level.
+//! ```no_run
+//! use android_intent::record::{self, LaunchOutcome};
+//! use android_intent::ExtraValue;
+//!
+//! // ... exercise the app ...
+//!
+//! let shares: Vec<_> = record::launches()
+//!     .into_iter()
+//!     .filter_map(|launch| launch.spec)
+//!     .filter(|spec| spec.action.as_deref() == Some("android.intent.action.SEND"))
+//!     .collect();
+//! assert_eq!(shares[0].mime_type.as_deref(), Some("text/plain"));
+//! assert_eq!(
+//!     shares[0].extras.get("android.intent.extra.TEXT"),
+//!     Some(&ExtraValue::Str("Hello World!".into())),
+//! );
+//! ```
+
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::{Error, IntentSpec};
+
+pub use crate::observe::LaunchOutcome;
+
+static LOG: Mutex<Vec<LaunchRecord>> = Mutex::new(Vec::new());
+
+/// One launch attempt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LaunchRecord {
+    /// The launched intent, or `None` if it failed to build.
+    pub spec: Option<IntentSpec>,
+    /// The request code, for `start_activity_for_result`.
+    pub request_code: Option<i32>,
+    pub timestamp: SystemTime,
+    pub outcome: LaunchOutcome,
+}
+
+/// All launches recorded so far, oldest first.
+pub fn launches() -> Vec<LaunchRecord> {
+    LOG.lock().unwrap().clone()
+}
+
+/// All launches recorded so far, clearing the log.
+pub fn take() -> Vec<LaunchRecord> {
+    std::mem::take(&mut *LOG.lock().unwrap())
+}
+
+pub fn clear() {
+    LOG.lock().unwrap().clear();
+}
+
+pub(crate) fn record(spec: Option<IntentSpec>, request_code: Option<i32>, result: Result<(), &Error>) {
+    LOG.lock().unwrap().push(LaunchRecord {
+        spec,
+        request_code,
+        timestamp: SystemTime::now(),
+        outcome: LaunchOutcome::from_result(result),
+    });
+}
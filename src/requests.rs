@@ -0,0 +1,331 @@
+//! In-memory tracking of launches waiting for their activity result, so several can be in
+//! flight at once (say a picker and a permission dialog) without their request codes colliding.
+//!
+//! Every [`Intent::start_activity_for_result`](crate::Intent::start_activity_for_result) on a
+//! real intent registers its request code here until
+//! [`Intent::get_result`](crate::Intent::get_result) returns its result. Starting another launch
+//! with a code still in flight fails with [`Error::RequestCodeInUse`]. Unlike
+//! [`pending_results`](crate::pending_results), nothing survives a process restart.
+//! ```no_run
+//! use android_intent::{recipes, requests};
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! recipes::pick_image(intent_env.get_env(), 1).unwrap();
+//! requests::on_result(1, |result| log::info!("picked: {}", result.is_ok()));
+//!
+//! for request in requests::outstanding() {
+//!     log::debug!("waiting on {}: {:?}", request.request_code, request.spec.and_then(|spec| spec.action));
+//! }
+//! ```
+//!
+//! # Keyed callbacks
+//!
+//! A callback passed to [`on_result`] belongs to one launch and usually captures state of the
+//! activity that started it, which a rotation replaces. Like androidx's
+//! `registerForActivityResult`, [`register`] instead binds a long-lived callback to a stable
+//! string key, and [`Intent::start_activity_for_key`](crate::Intent::start_activity_for_key)
+//! launches under the request code derived from it. The recreated activity registers the same
+//! key again, replacing the old callback, and the result goes to the new one.
+//!
+//! The request code depends on the key alone, so this also covers the process being killed
+//! while the launched activity is in front: nothing in memory survives, but once the restarted
+//! app registers its keys, the result that Android delivers to the new activity is matched by
+//! its request code. Register keys before polling
+//! [`Intent::get_result`](crate::Intent::get_result) at startup; a result whose key has no
+//! callback yet is only returned from `get_result`.
+//! ```no_run
+//! use android_intent::{requests, Action, Intent};
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! // On every onCreate, including after rotation or a process restart:
+//! requests::register("pick-avatar", |result| log::info!("avatar picked: {}", result.is_ok())).unwrap();
+//!
+//! // Later, from a button:
+//! Intent::new(intent_env.get_env(), Action::GetContent)
+//!     .with_type("image/*")
+//!     .start_activity_for_key("pick-avatar")
+//!     .unwrap();
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::trace::debug;
+use crate::{CompletedIntent, Error, IntentSpec};
+
+type ResultCallback = Box<dyn FnOnce(&mut CompletedIntent<'_>) + Send>;
+type KeyedCallback = Box<dyn FnMut(&mut CompletedIntent<'_>) + Send>;
+
+struct Entry {
+    spec: Option<IntentSpec>,
+    started_at: SystemTime,
+    on_result: Option<ResultCallback>,
+}
+
+static IN_FLIGHT: Mutex<BTreeMap<i32, Entry>> = Mutex::new(BTreeMap::new());
+
+/// Keyed callbacks by request code, with their key. `None` while the callback runs.
+static KEYED: Mutex<BTreeMap<i32, (String, Option<KeyedCallback>)>> = Mutex::new(BTreeMap::new());
+
+/// A launch waiting for its result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutstandingRequest {
+    pub request_code: i32,
+    /// The launched intent, `None` if it couldn't be read back.
+    pub spec: Option<IntentSpec>,
+    pub started_at: SystemTime,
+    /// Whether an [`on_result`] callback waits for it.
+    pub has_callback: bool,
+}
+
+/// All launches waiting for their result, by request code.
+pub fn outstanding() -> Vec<OutstandingRequest> {
+    IN_FLIGHT
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(&request_code, entry)| OutstandingRequest {
+            request_code,
+            spec: entry.spec.clone(),
+            started_at: entry.started_at,
+            has_callback: entry.on_result.is_some(),
+        })
+        .collect()
+}
+
+/// Whether a launch with `request_code` waits for its result.
+pub fn is_outstanding(request_code: i32) -> bool {
+    IN_FLIGHT.lock().unwrap().contains_key(&request_code)
+}
+
+/// Run `callback` with the result of the launch with `request_code` when
+/// [`Intent::get_result`](crate::Intent::get_result) returns it, before returning it. Returns
+/// `false` if no such launch is in flight.
+pub fn on_result(request_code: i32, callback: impl FnOnce(&mut CompletedIntent<'_>) + Send + 'static) -> bool {
+    match IN_FLIGHT.lock().unwrap().get_mut(&request_code) {
+        Some(entry) => {
+            entry.on_result = Some(Box::new(callback));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Stop tracking the launch with `request_code`, e.g. when its result was delivered some other
+/// way, so the code can be reused. Returns whether it was in flight.
+pub fn forget(request_code: i32) -> bool {
+    IN_FLIGHT.lock().unwrap().remove(&request_code).is_some()
+}
+
+/// The request code launches for `key` use: a 32-bit FNV-1a hash folded into `0x8000..=0xFFFF`,
+/// so it stays the same across activity and process restarts and fits the 16 bits androidx
+/// allows.
+pub const fn request_code_for(key: &str) -> i32 {
+    let bytes = key.as_bytes();
+    let mut hash: u32 = 0x811c_9dc5;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+
+    0x8000 | ((hash ^ (hash >> 15)) & 0x7fff) as i32
+}
+
+/// Run `callback` with every result for launches under `key`, replacing the callback an earlier
+/// activity instance registered for it. Returns the key's request code.
+///
+/// Fails with [`Error::RequestCodeInUse`] if a different registered key maps to the same code.
+pub fn register(
+    key: &str,
+    callback: impl FnMut(&mut CompletedIntent<'_>) + Send + 'static,
+) -> Result<i32, Error> {
+    let request_code = request_code_for(key);
+    let mut keyed = KEYED.lock().unwrap();
+    if let Some((other, _)) = keyed.get(&request_code) {
+        if other != key {
+            return Err(Error::RequestCodeInUse(request_code));
+        }
+    }
+    keyed.insert(request_code, (key.to_owned(), Some(Box::new(callback))));
+
+    Ok(request_code)
+}
+
+/// Drop the callback registered for `key`. Returns whether there was one.
+pub fn unregister(key: &str) -> bool {
+    let mut keyed = KEYED.lock().unwrap();
+    match keyed.get(&request_code_for(key)) {
+        Some((registered, _)) if registered == key => keyed.remove(&request_code_for(key)).is_some(),
+        _ => false,
+    }
+}
+
+/// Request codes whose results a callback waits for.
+pub(crate) fn callback_codes() -> Vec<i32> {
+    let mut codes: Vec<i32> = IN_FLIGHT
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, entry)| entry.on_result.is_some())
+        .map(|(&request_code, _)| request_code)
+        .collect();
+    codes.extend(KEYED.lock().unwrap().keys());
+
+    codes
+}
+
+/// Claim `request_code` for a launch about to start.
+pub(crate) fn reserve(request_code: i32) -> Result<(), Error> {
+    let mut in_flight = IN_FLIGHT.lock().unwrap();
+    if in_flight.contains_key(&request_code) {
+        return Err(Error::RequestCodeInUse(request_code));
+    }
+    in_flight.insert(request_code, Entry { spec: None, started_at: SystemTime::now(), on_result: None });
+
+    Ok(())
+}
+
+/// Record what the launch with the reserved `request_code` started.
+pub(crate) fn started(request_code: i32, spec: Option<IntentSpec>) {
+    if let Some(entry) = IN_FLIGHT.lock().unwrap().get_mut(&request_code) {
+        entry.spec = spec;
+    }
+}
+
+/// Stop tracking the launch `result` answers, running its callback.
+pub(crate) fn complete(result: &mut CompletedIntent<'_>) {
+    let entry = IN_FLIGHT.lock().unwrap().remove(&result.request_code);
+    match entry {
+        Some(Entry { on_result: Some(callback), .. }) => callback(result),
+        Some(_) => {
+            run_keyed(result);
+        }
+        // After a process restart nothing is in flight, but a keyed callback may wait for it.
+        None if !run_keyed(result) => debug!("result for untracked request {}", result.request_code),
+        None => {}
+    }
+}
+
+/// Run the keyed callback for `result`'s request code, if one is registered. The callback runs
+/// without the lock held, so it may register or unregister keys itself.
+fn run_keyed(result: &mut CompletedIntent<'_>) -> bool {
+    let mut callback = match KEYED.lock().unwrap().get_mut(&result.request_code) {
+        Some((_, callback)) => match callback.take() {
+            Some(callback) => callback,
+            None => return false,
+        },
+        None => return false,
+    };
+
+    callback(result);
+
+    // Put it back unless it was replaced or unregistered meanwhile.
+    if let Some((_, slot @ None)) = KEYED.lock().unwrap().get_mut(&result.request_code) {
+        *slot = Some(callback);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn request_codes_are_stable_and_fit_in_16_bits() {
+        assert_eq!(request_code_for("pick-avatar"), request_code_for("pick-avatar"));
+        for key in ["", "pick-avatar", "a much longer key with spaces and ünïcode"] {
+            assert!((0x8000..=0xffff).contains(&request_code_for(key)), "{key}");
+        }
+    }
+
+    #[test]
+    fn register_rejects_colliding_keys() {
+        // 15 bits of hash collide after a few hundred keys.
+        let mut seen = HashMap::new();
+        let (first, second) = (0..)
+            .map(|i| format!("test-collision-{i}"))
+            .find_map(|key| seen.insert(request_code_for(&key), key.clone()).map(|other| (other, key)))
+            .unwrap();
+
+        let code = register(&first, |_| {}).unwrap();
+        assert!(matches!(register(&second, |_| {}), Err(Error::RequestCodeInUse(c)) if c == code));
+        // The same key may register again.
+        assert_eq!(register(&first, |_| {}).unwrap(), code);
+
+        assert!(!unregister(&second));
+        assert!(unregister(&first));
+        assert!(!unregister(&first));
+    }
+
+    #[test]
+    fn reserve_rejects_codes_in_flight() {
+        reserve(0x6101).unwrap();
+        assert!(is_outstanding(0x6101));
+        assert!(matches!(reserve(0x6101), Err(Error::RequestCodeInUse(0x6101))));
+
+        assert!(forget(0x6101));
+        reserve(0x6101).unwrap();
+        forget(0x6101);
+    }
+
+    #[cfg(feature = "mock")]
+    fn completed(request_code: i32) -> CompletedIntent<'static> {
+        let data = crate::Intent::mock("ACTION_VIEW");
+        CompletedIntent { request_code, result_code: crate::RESULT_OK, data }
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn results_go_to_the_latest_keyed_callback() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let old_calls = Arc::new(AtomicUsize::new(0));
+        let new_calls = Arc::new(AtomicUsize::new(0));
+
+        let calls = old_calls.clone();
+        let code = register("test-reregistered", move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+        // As the recreated activity would.
+        let calls = new_calls.clone();
+        register("test-reregistered", move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+
+        // Nothing in flight, as after a process restart.
+        complete(&mut completed(code));
+        complete(&mut completed(code));
+        assert_eq!(old_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(new_calls.load(Ordering::SeqCst), 2);
+
+        unregister("test-reregistered");
+        complete(&mut completed(code));
+        assert_eq!(new_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn on_result_callback_runs_once_and_releases_the_code() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let ran = Arc::new(AtomicBool::new(false));
+        reserve(0x6102).unwrap();
+        started(0x6102, None);
+        let flag = ran.clone();
+        assert!(on_result(0x6102, move |result| flag.store(result.is_ok(), Ordering::SeqCst)));
+
+        complete(&mut completed(0x6102));
+        assert!(ran.load(Ordering::SeqCst));
+        assert!(!is_outstanding(0x6102));
+        assert!(!on_result(0x6102, |_| {}));
+    }
+}
@@ -0,0 +1,407 @@
+//! Intents that send the user to system settings screens.
+//!
+//! ```no_run
+//! use android_intent::settings;
+//!
+//! # let mut intent_env = android_intent::IntentEnv::new();
+//! # android_intent::with_current_env(&mut intent_env, |mut env| {
+//! if !settings::is_ignoring_battery_optimizations(&mut env).unwrap() {
+//!     settings::request_ignore_battery_optimizations(env)
+//!         .start_activity()
+//!         .unwrap();
+//! }
+//! # })
+//! ```
+
+use std::collections::BTreeMap;
+
+use jni::{AttachGuard, JNIEnv};
+
+use crate::{api_level, backend, bundle, compat, context, ApiLevel, Error, Intent};
+use crate::trace::{self, Traced};
+
+const SETTINGS_CLASS: &str = "android/provider/Settings";
+const SETTINGS_PANEL_CLASS: &str = "android/provider/Settings$Panel";
+const APP_OPS_MANAGER_CLASS: &str = "android/app/AppOpsManager";
+const EXTRA_APP_PACKAGE: &str = "android.provider.extra.APP_PACKAGE";
+
+/// A settings panel that floats over the app, see [`panel`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Panel {
+    Internet,
+    Wifi,
+    Volume,
+    Nfc,
+}
+
+impl AsRef<str> for Panel {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Internet => "ACTION_INTERNET_CONNECTIVITY",
+            Self::Wifi => "ACTION_WIFI",
+            Self::Volume => "ACTION_VOLUME",
+            Self::Nfc => "ACTION_NFC",
+        }
+    }
+}
+
+impl Panel {
+    /// The full-screen `Settings` action shown instead of the panel before API 29.
+    fn fallback(&self) -> &str {
+        match self {
+            Self::Internet => "ACTION_WIRELESS_SETTINGS",
+            Self::Wifi => "ACTION_WIFI_SETTINGS",
+            Self::Volume => "ACTION_SOUND_SETTINGS",
+            Self::Nfc => "ACTION_NFC_SETTINGS",
+        }
+    }
+}
+
+fn package_settings_intent<'env>(mut env: AttachGuard<'env>, action: &str) -> Intent<'env> {
+    match context::package_uri(&mut env) {
+        Ok(uri) => Intent::new_with_class_action(env, SETTINGS_CLASS, action, Some(uri)),
+        Err(err) => Intent::from_error(err),
+    }
+}
+
+/// The app details screen of `package` (permissions, storage, "Force stop"), or of this app
+/// if `None`.
+pub fn app_details<'env>(env: AttachGuard<'env>, package: Option<&str>) -> Intent<'env> {
+    match package {
+        Some(package) => Intent::new_with_class_action(
+            env,
+            SETTINGS_CLASS,
+            "ACTION_APPLICATION_DETAILS_SETTINGS",
+            Some(format!("package:{}", package)),
+        ),
+        None => package_settings_intent(env, "ACTION_APPLICATION_DETAILS_SETTINGS"),
+    }
+}
+
+/// Ask the user to exempt this app from battery optimizations (Doze and App Standby, API 23+).
+///
+/// Requires the `REQUEST_IGNORE_BATTERY_OPTIMIZATIONS` permission. Check
+/// [`is_ignoring_battery_optimizations`] first to avoid prompting needlessly.
+pub fn request_ignore_battery_optimizations(mut env: AttachGuard<'_>) -> Intent<'_> {
+    if let Err(err) = ApiLevel(23).require(&mut env) {
+        return Intent::from_error(err);
+    }
+
+    package_settings_intent(env, "ACTION_REQUEST_IGNORE_BATTERY_OPTIMIZATIONS")
+}
+
+/// Whether this app is already exempt from battery optimizations, via
+/// `PowerManager.isIgnoringBatteryOptimizations` (API 23+).
+pub fn is_ignoring_battery_optimizations(env: &mut JNIEnv) -> Result<bool, Error> {
+    trace::checked(env, |env| {
+        ApiLevel(23).require(env)?;
+        let power_manager = context::system_service(env, "POWER_SERVICE")?;
+        let package = context::package_name(env)?;
+        let package = env.new_string(package)?;
+
+        let ignoring = env
+            .call(
+                power_manager,
+                "isIgnoringBatteryOptimizations",
+                "(Ljava/lang/String;)Z",
+                &[(&package).into()],
+            )?
+            .z()?;
+
+        Ok(ignoring)
+    })
+}
+
+/// Let the user allow this app to install other apps (API 26+).
+///
+/// Check [`can_request_package_installs`] before launching an APK install intent.
+pub fn manage_unknown_app_sources(mut env: AttachGuard<'_>) -> Intent<'_> {
+    if let Err(err) = ApiLevel(26).require(&mut env) {
+        return Intent::from_error(err);
+    }
+
+    package_settings_intent(env, "ACTION_MANAGE_UNKNOWN_APP_SOURCES")
+}
+
+/// Whether the user has allowed this app to install other apps, via
+/// `PackageManager.canRequestPackageInstalls`.
+pub fn can_request_package_installs(env: &mut JNIEnv) -> Result<bool, Error> {
+    trace::checked(env, |env| {
+        ApiLevel(26).require(env)?;
+        let package_manager = context::package_manager(env)?;
+
+        let allowed = env
+            .call(package_manager, "canRequestPackageInstalls", "()Z", &[])?
+            .z()?;
+
+        Ok(allowed)
+    })
+}
+
+/// Let the user connect this app across their work and personal profiles (API 30+), see
+/// [`profiles`](crate::profiles).
+pub fn manage_cross_profile_access(mut env: AttachGuard<'_>) -> Intent<'_> {
+    if let Err(err) = ApiLevel(30).require(&mut env) {
+        return Intent::from_error(err);
+    }
+
+    package_settings_intent(env, "ACTION_MANAGE_CROSS_PROFILE_ACCESS")
+}
+
+/// Let the user grant this app usage access (`PACKAGE_USAGE_STATS`), for `UsageStatsManager`.
+///
+/// Check [`has_usage_access`] first, since the screen lists every app.
+pub fn usage_access(env: AttachGuard<'_>) -> Intent<'_> {
+    Intent::new_with_class_action(env, SETTINGS_CLASS, "ACTION_USAGE_ACCESS_SETTINGS", None::<&str>)
+}
+
+/// Whether the user granted this app usage access, via `AppOpsManager`.
+pub fn has_usage_access(env: &mut JNIEnv) -> Result<bool, Error> {
+    trace::checked(env, |env| {
+        const MODE_ALLOWED: i32 = 0;
+        const MODE_DEFAULT: i32 = 3;
+
+        let app_ops = context::system_service(env, "APP_OPS_SERVICE")?;
+        let op = backend::static_field(env, APP_OPS_MANAGER_CLASS, "OPSTR_GET_USAGE_STATS", "Ljava/lang/String;")?;
+        let uid = env.call_static("android/os/Process", "myUid", "()I", &[])?.i()?;
+        let package = context::package_name(env)?;
+        let package = env.new_string(package)?;
+
+        let method = compat::check_op_method(api_level::sdk_int_with(env)?);
+        let mode = env
+            .call(
+                app_ops,
+                method,
+                "(Ljava/lang/String;ILjava/lang/String;)I",
+                &[(&op).into(), uid.into(), (&package).into()],
+            )?
+            .i()?;
+
+        match mode {
+            MODE_ALLOWED => Ok(true),
+            // Without an explicit choice, the permission itself decides.
+            MODE_DEFAULT => {
+                let permission = env.new_string("android.permission.PACKAGE_USAGE_STATS")?;
+                let activity = context::activity(env)?;
+                let granted = env
+                    .call(
+                        &activity,
+                        "checkCallingOrSelfPermission",
+                        "(Ljava/lang/String;)I",
+                        &[(&permission).into()],
+                    )?
+                    .i()?;
+                Ok(granted == 0)
+            }
+            _ => Ok(false),
+        }
+    })
+}
+
+/// Let the user turn on accessibility services, e.g. the one this app provides.
+///
+/// Check [`is_accessibility_service_enabled`] first to only route users there when needed.
+pub fn accessibility(env: AttachGuard<'_>) -> Intent<'_> {
+    Intent::new_with_class_action(env, SETTINGS_CLASS, "ACTION_ACCESSIBILITY_SETTINGS", None::<&str>)
+}
+
+/// Whether the accessibility service `component` (e.g. `com.example/.TalkService`) is enabled,
+/// via `AccessibilityManager.getEnabledAccessibilityServiceList`.
+pub fn is_accessibility_service_enabled(env: &mut JNIEnv, component: &str) -> Result<bool, Error> {
+    trace::checked(env, |env| {
+        const FEEDBACK_ALL_MASK: i32 = -1;
+
+        let component = backend::component_name(env, component)?;
+        let accessibility_manager = context::system_service(env, "ACCESSIBILITY_SERVICE")?;
+        let services = env
+            .call(
+                accessibility_manager,
+                "getEnabledAccessibilityServiceList",
+                "(I)Ljava/util/List;",
+                &[FEEDBACK_ALL_MASK.into()],
+            )?
+            .l()?;
+
+        for service in bundle::collection(env, &services)? {
+            // The id is the service's flattened component name.
+            let Some(id) = backend::string_getter(env, &service, "getId")? else {
+                continue;
+            };
+            let enabled = backend::component_name(env, &id)?;
+            if env.call(&enabled, "equals", "(Ljava/lang/Object;)Z", &[(&component).into()])?.z()? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    })
+}
+
+/// Show a settings panel on top of the app (API 29+), or the matching full settings screen on
+/// older versions.
+/// ```no_run
+/// use android_intent::settings::{self, Panel};
+///
+/// # let mut intent_env = android_intent::IntentEnv::new();
+/// # android_intent::with_current_env(&mut intent_env, |env| {
+/// settings::panel(env, Panel::Internet).start_activity().unwrap();
+/// # })
+/// ```
+pub fn panel(mut env: AttachGuard<'_>, panel: Panel) -> Intent<'_> {
+    match api_level::sdk_int_with(&mut env) {
+        Ok(sdk) if compat::supports_settings_panels(sdk) => {
+            Intent::new_with_class_action(env, SETTINGS_PANEL_CLASS, panel, None::<&str>)
+        }
+        Ok(_) => Intent::new_with_class_action(env, SETTINGS_CLASS, panel.fallback(), None::<&str>),
+        Err(err) => Intent::from_error(err),
+    }
+}
+
+/// Let the user allow this app to schedule exact alarms (API 31+), see
+/// [`alarms`](crate::alarms). Check [`can_schedule_exact_alarms`] first.
+pub fn request_exact_alarm_permission(mut env: AttachGuard<'_>) -> Intent<'_> {
+    if let Err(err) = ApiLevel(31).require(&mut env) {
+        return Intent::from_error(err);
+    }
+
+    package_settings_intent(env, "ACTION_REQUEST_SCHEDULE_EXACT_ALARM")
+}
+
+/// Whether this app may schedule exact alarms, via `AlarmManager.canScheduleExactAlarms`.
+/// Always `true` before API 31, which introduced the restriction.
+pub fn can_schedule_exact_alarms(env: &mut JNIEnv) -> Result<bool, Error> {
+    trace::checked(env, |env| {
+        if !ApiLevel(31).is_supported(env)? {
+            return Ok(true);
+        }
+        let alarm_manager = context::system_service(env, "ALARM_SERVICE")?;
+
+        let allowed = env.call(alarm_manager, "canScheduleExactAlarms", "()Z", &[])?.z()?;
+
+        Ok(allowed)
+    })
+}
+
+/// Let the user allow this app to show full-screen notifications (API 34+), as alarm and
+/// calling apps need. Check [`can_use_full_screen_intent`] first.
+pub fn manage_full_screen_intent(mut env: AttachGuard<'_>) -> Intent<'_> {
+    if let Err(err) = ApiLevel(34).require(&mut env) {
+        return Intent::from_error(err);
+    }
+
+    package_settings_intent(env, "ACTION_MANAGE_APP_USE_FULL_SCREEN_INTENT")
+}
+
+/// Whether this app may show full-screen notifications, via
+/// `NotificationManager.canUseFullScreenIntent`. Always `true` before API 34, which introduced
+/// the restriction.
+pub fn can_use_full_screen_intent(env: &mut JNIEnv) -> Result<bool, Error> {
+    trace::checked(env, |env| {
+        if !ApiLevel(34).is_supported(env)? {
+            return Ok(true);
+        }
+        let notification_manager = context::system_service(env, "NOTIFICATION_SERVICE")?;
+
+        let allowed = env.call(notification_manager, "canUseFullScreenIntent", "()Z", &[])?.z()?;
+
+        Ok(allowed)
+    })
+}
+
+/// This app's notification settings, via `ACTION_APP_NOTIFICATION_SETTINGS` (API 26+), e.g. to
+/// turn notifications back on after [`notify::ensure_permission`](crate::notify::ensure_permission)
+/// found them permanently denied. Opens the app details screen before API 26.
+pub fn app_notification_settings(mut env: AttachGuard<'_>) -> Intent<'_> {
+    match api_level::sdk_int_with(&mut env) {
+        Ok(sdk) if compat::supports_app_notification_settings(sdk) => match context::package_name(&mut env) {
+            Ok(package) => {
+                Intent::new_with_class_action(env, SETTINGS_CLASS, "ACTION_APP_NOTIFICATION_SETTINGS", None::<&str>)
+                    .with_extra(EXTRA_APP_PACKAGE, package)
+            }
+            Err(err) => Intent::from_error(err),
+        },
+        Ok(_) => app_details(env, None),
+        Err(err) => Intent::from_error(err),
+    }
+}
+
+/// Let the user choose which links this app opens by default (API 31+), e.g. when
+/// [`domain_verification`] shows domains that are neither verified nor selected.
+pub fn open_by_default(mut env: AttachGuard<'_>) -> Intent<'_> {
+    if let Err(err) = ApiLevel(31).require(&mut env) {
+        return Intent::from_error(err);
+    }
+
+    package_settings_intent(env, "ACTION_APP_OPEN_BY_DEFAULT_SETTINGS")
+}
+
+/// How this app handles links to one of its domains, see [`DomainVerification`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DomainState {
+    /// Neither verified nor selected by the user; links open elsewhere.
+    None,
+    /// The user chose this app for the domain.
+    Selected,
+    /// The domain passed app link verification.
+    Verified,
+}
+
+/// This app's app link status, from `DomainVerificationManager`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainVerification {
+    /// Whether the user lets the app open links at all.
+    pub link_handling_allowed: bool,
+    /// State of each web domain in the app's intent filters.
+    pub domains: BTreeMap<String, DomainState>,
+}
+
+/// This app's app link status, via `DomainVerificationManager.getDomainVerificationUserState`
+/// (API 31+).
+/// ```no_run
+/// use android_intent::settings::{self, DomainState};
+///
+/// # let intent_env = android_intent::IntentEnv::new();
+/// let status = settings::domain_verification(&mut intent_env.get_env()).unwrap();
+/// if !status.link_handling_allowed || status.domains.values().any(|state| *state == DomainState::None) {
+///     settings::open_by_default(intent_env.get_env()).start_activity().unwrap();
+/// }
+/// ```
+pub fn domain_verification(env: &mut JNIEnv) -> Result<DomainVerification, Error> {
+    trace::checked(env, |env| {
+        const DOMAIN_STATE_SELECTED: i32 = 1;
+        const DOMAIN_STATE_VERIFIED: i32 = 2;
+
+        ApiLevel(31).require(env)?;
+        let manager = context::system_service(env, "DOMAIN_VERIFICATION_SERVICE")?;
+        let package = context::package_name(env)?;
+        let package = env.new_string(package)?;
+
+        let state = env
+            .call(
+                &manager,
+                "getDomainVerificationUserState",
+                "(Ljava/lang/String;)Landroid/content/pm/verify/domain/DomainVerificationUserState;",
+                &[(&package).into()],
+            )?
+            .l()?;
+        let link_handling_allowed = env.call(&state, "isLinkHandlingAllowed", "()Z", &[])?.z()?;
+
+        let host_states = env.call(&state, "getHostToStateMap", "()Ljava/util/Map;", &[])?.l()?;
+        let hosts = env.call(&host_states, "keySet", "()Ljava/util/Set;", &[])?.l()?;
+        let mut domains = BTreeMap::new();
+        for host in bundle::string_collection(env, &hosts)? {
+            let jhost = env.new_string(&host)?;
+            let state = env
+                .call(&host_states, "get", "(Ljava/lang/Object;)Ljava/lang/Object;", &[(&jhost).into()])?
+                .l()?;
+            let state = match env.call(&state, "intValue", "()I", &[])?.i()? {
+                DOMAIN_STATE_SELECTED => DomainState::Selected,
+                DOMAIN_STATE_VERIFIED => DomainState::Verified,
+                _ => DomainState::None,
+            };
+            domains.insert(host, state);
+        }
+
+        Ok(DomainVerification { link_handling_allowed, domains })
+    })
+}
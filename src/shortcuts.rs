@@ -0,0 +1,287 @@
+//! Home screen and Direct Share shortcuts, via `ShortcutManager`.
+//!
+//! ```no_run
+//! use android_intent::shortcuts::{self, Shortcut};
+//! use android_intent::IntentSpec;
+//!
+//! # let mut intent_env = android_intent::IntentEnv::new();
+//! # android_intent::with_current_env(&mut intent_env, |mut env| {
+//! let target = IntentSpec {
+//!     action: Some("android.intent.action.VIEW".to_owned()),
+//!     data: Some("https://example.com/inbox".to_owned()),
+//!     ..Default::default()
+//! };
+//! let icon = std::fs::read("inbox.png").unwrap();
+//! let shortcut = Shortcut::new("inbox", "Inbox", target).with_icon(icon);
+//!
+//! if shortcuts::is_pin_supported(&mut env).unwrap() {
+//!     shortcuts::request_pin(&mut env, &shortcut, None).unwrap();
+//! }
+//! # })
+//! ```
+use jni::{objects::JObject, JNIEnv};
+
+use crate::trace::Traced;
+use crate::{api_level, backend, context, pending, ApiLevel, Error, Intent, IntentSpec};
+
+const SHORTCUT_INFO_BUILDER_CLASS: &str = "android/content/pm/ShortcutInfo$Builder";
+const EXTRA_SHORTCUT_ID: &str = "android.intent.extra.shortcut.ID";
+
+/// A shortcut to pin or publish.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shortcut {
+    /// Identifies the shortcut within this app.
+    pub id: String,
+    /// Label shown under the icon.
+    pub short_label: String,
+    /// Longer label, shown where there is room for it.
+    pub long_label: Option<String>,
+    /// Encoded image (e.g. PNG) to use as the icon, see `Icon.createWithData`. The app icon is
+    /// used if this is `None`.
+    pub icon: Option<Vec<u8>>,
+    /// Intent launched when the shortcut is tapped. It needs an action.
+    pub intent: IntentSpec,
+    /// Categories, e.g. a `share-target` category from the app's `shortcuts.xml` to show the
+    /// shortcut as a Direct Share target.
+    pub categories: Vec<String>,
+    /// People the shortcut is about, to rank it in the share sheet (API 29+, ignored before).
+    pub persons: Vec<Person>,
+    /// Keep the shortcut cached by the system after it is removed, as recommended for sharing
+    /// shortcuts (API 30+, ignored before).
+    pub long_lived: bool,
+}
+
+/// An `android.app.Person` hint for a [`Shortcut`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Person {
+    pub name: String,
+    /// Stable identifier of the person within the app.
+    pub key: Option<String>,
+}
+
+impl Shortcut {
+    pub fn new(id: impl Into<String>, short_label: impl Into<String>, intent: IntentSpec) -> Self {
+        Self {
+            id: id.into(),
+            short_label: short_label.into(),
+            long_label: None,
+            icon: None,
+            intent,
+            categories: Vec::new(),
+            persons: Vec::new(),
+            long_lived: false,
+        }
+    }
+
+    pub fn with_long_label(self, long_label: impl Into<String>) -> Self {
+        Self {
+            long_label: Some(long_label.into()),
+            ..self
+        }
+    }
+
+    pub fn with_icon(self, icon: Vec<u8>) -> Self {
+        Self { icon: Some(icon), ..self }
+    }
+
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.categories.push(category.into());
+        self
+    }
+
+    pub fn with_person(mut self, person: Person) -> Self {
+        self.persons.push(person);
+        self
+    }
+
+    pub fn long_lived(self) -> Self {
+        Self { long_lived: true, ..self }
+    }
+}
+
+/// Whether the launcher supports pinning shortcuts. Always `false` below API 26.
+pub fn is_pin_supported(env: &mut JNIEnv) -> Result<bool, Error> {
+    if !ApiLevel(26).is_supported(env)? {
+        return Ok(false);
+    }
+
+    let shortcut_manager = context::system_service(env, "SHORTCUT_SERVICE")?;
+    let supported = env
+        .call(shortcut_manager, "isRequestPinShortcutSupported", "()Z", &[])?
+        .z()?;
+
+    Ok(supported)
+}
+
+/// Ask the launcher to pin `shortcut` to the home screen (API 26+). The user confirms in a
+/// system dialog.
+///
+/// If given, `callback` is sent as a broadcast once the shortcut was pinned. Returns whether the
+/// request was passed on to the launcher.
+pub fn request_pin(env: &mut JNIEnv, shortcut: &Shortcut, callback: Option<&IntentSpec>) -> Result<bool, Error> {
+    ApiLevel(26).require(env)?;
+
+    let info = shortcut_info(env, shortcut)?;
+    let sender = match callback {
+        Some(callback) => pending::broadcast_sender(env, callback, 0)?,
+        None => JObject::null(),
+    };
+
+    let shortcut_manager = context::system_service(env, "SHORTCUT_SERVICE")?;
+    let requested = env
+        .call(
+            shortcut_manager,
+            "requestPinShortcut",
+            "(Landroid/content/pm/ShortcutInfo;Landroid/content/IntentSender;)Z",
+            &[(&info).into(), (&sender).into()],
+        )?
+        .z()?;
+
+    Ok(requested)
+}
+
+/// Publish `shortcuts` as dynamic shortcuts, shown when long-pressing the app icon and, with a
+/// share target category, in the share sheet's Direct Share row (API 25+). Returns `false` if
+/// the app is being rate-limited.
+///
+/// A share target also needs a `<share-target>` for the category in the app's `shortcuts.xml`.
+/// When the user picks one, the share intent carries its id, see [`shortcut_id`].
+/// ```no_run
+/// use android_intent::shortcuts::{self, Person, Shortcut};
+/// use android_intent::IntentSpec;
+///
+/// # let mut intent_env = android_intent::IntentEnv::new();
+/// # android_intent::with_current_env(&mut intent_env, |mut env| {
+/// let intent = IntentSpec {
+///     action: Some("android.intent.action.VIEW".to_owned()),
+///     ..Default::default()
+/// };
+/// let alice = Shortcut::new("contact-alice", "Alice", intent)
+///     .with_category("com.example.app.category.TEXT_SHARE_TARGET")
+///     .with_person(Person { name: "Alice".to_owned(), key: Some("alice".to_owned()) })
+///     .long_lived();
+///
+/// shortcuts::add_dynamic_shortcuts(&mut env, &[alice]).unwrap();
+/// # })
+/// ```
+pub fn add_dynamic_shortcuts(env: &mut JNIEnv, shortcuts: &[Shortcut]) -> Result<bool, Error> {
+    ApiLevel(25).require(env)?;
+
+    let list = env.new_object("java/util/ArrayList", "()V", &[])?;
+    for shortcut in shortcuts {
+        let info = shortcut_info(env, shortcut)?;
+        env.call(&list, "add", "(Ljava/lang/Object;)Z", &[(&info).into()])?;
+    }
+
+    let shortcut_manager = context::system_service(env, "SHORTCUT_SERVICE")?;
+    let added = env
+        .call(shortcut_manager, "addDynamicShortcuts", "(Ljava/util/List;)Z", &[(&list).into()])?
+        .z()?;
+
+    Ok(added)
+}
+
+/// Remove the dynamic shortcuts with the given ids.
+pub fn remove_dynamic_shortcuts(env: &mut JNIEnv, ids: &[&str]) -> Result<(), Error> {
+    ApiLevel(25).require(env)?;
+
+    let list = env.new_object("java/util/ArrayList", "()V", &[])?;
+    for id in ids {
+        let id = env.new_string(id)?;
+        env.call(&list, "add", "(Ljava/lang/Object;)Z", &[(&id).into()])?;
+    }
+
+    let shortcut_manager = context::system_service(env, "SHORTCUT_SERVICE")?;
+    env.call(shortcut_manager, "removeDynamicShortcuts", "(Ljava/util/List;)V", &[(&list).into()])?;
+
+    Ok(())
+}
+
+/// The id of the Direct Share shortcut an incoming share intent was sent to, from
+/// `Intent.EXTRA_SHORTCUT_ID`. `None` if the user picked the app itself.
+pub fn shortcut_id(intent: &mut Intent) -> Result<Option<String>, Error> {
+    intent.get(EXTRA_SHORTCUT_ID)
+}
+
+/// Build the `ShortcutInfo` for `shortcut`.
+pub(crate) fn shortcut_info<'a>(env: &mut JNIEnv<'a>, shortcut: &Shortcut) -> Result<JObject<'a>, Error> {
+    const BUILDER: &str = "Landroid/content/pm/ShortcutInfo$Builder;";
+
+    let id = env.new_string(&shortcut.id)?;
+    let activity = context::activity(env)?;
+    let builder = env.new_object(
+        SHORTCUT_INFO_BUILDER_CLASS,
+        "(Landroid/content/Context;Ljava/lang/String;)V",
+        &[(&activity).into(), (&id).into()],
+    )?;
+
+    let short_label = env.new_string(&shortcut.short_label)?;
+    env.call(&builder, "setShortLabel", format!("(Ljava/lang/CharSequence;){}", BUILDER), &[(&short_label).into()])?;
+
+    if let Some(long_label) = &shortcut.long_label {
+        let long_label = env.new_string(long_label)?;
+        env.call(&builder, "setLongLabel", format!("(Ljava/lang/CharSequence;){}", BUILDER), &[(&long_label).into()])?;
+    }
+
+    if let Some(icon) = &shortcut.icon {
+        let data = env.byte_array_from_slice(icon)?;
+        let icon = env
+            .call_static(
+                "android/graphics/drawable/Icon",
+                "createWithData",
+                "([BII)Landroid/graphics/drawable/Icon;",
+                &[(&data).into(), 0.into(), (icon.len() as i32).into()],
+            )?
+            .l()?;
+        env.call(&builder, "setIcon", format!("(Landroid/graphics/drawable/Icon;){}", BUILDER), &[(&icon).into()])?;
+    }
+
+    let intent = backend::new_intent(env, &shortcut.intent)?;
+    env.call(&builder, "setIntent", format!("(Landroid/content/Intent;){}", BUILDER), &[(&intent).into()])?;
+
+    if !shortcut.categories.is_empty() {
+        let categories = env.new_object("java/util/HashSet", "()V", &[])?;
+        for category in &shortcut.categories {
+            let category = env.new_string(category)?;
+            env.call(&categories, "add", "(Ljava/lang/Object;)Z", &[(&category).into()])?;
+        }
+        env.call(&builder, "setCategories", format!("(Ljava/util/Set;){}", BUILDER), &[(&categories).into()])?;
+    }
+
+    let sdk = api_level::sdk_int_with(env)?;
+    if !shortcut.persons.is_empty() && sdk >= 29 {
+        let persons = env.new_object_array(shortcut.persons.len() as i32, "android/app/Person", JObject::null())?;
+        for (i, person) in shortcut.persons.iter().enumerate() {
+            let person = new_person(env, person)?;
+            env.set_object_array_element(&persons, i as i32, person)?;
+        }
+        env.call(&builder, "setPersons", format!("([Landroid/app/Person;){}", BUILDER), &[(&persons).into()])?;
+    }
+
+    if shortcut.long_lived && sdk >= 30 {
+        env.call(&builder, "setLongLived", format!("(Z){}", BUILDER), &[true.into()])?;
+    }
+
+    let info = env
+        .call(&builder, "build", "()Landroid/content/pm/ShortcutInfo;", &[])?
+        .l()?;
+
+    Ok(info)
+}
+
+fn new_person<'a>(env: &mut JNIEnv<'a>, person: &Person) -> Result<JObject<'a>, Error> {
+    const BUILDER: &str = "Landroid/app/Person$Builder;";
+
+    let builder = env.new_object("android/app/Person$Builder", "()V", &[])?;
+
+    let name = env.new_string(&person.name)?;
+    env.call(&builder, "setName", format!("(Ljava/lang/CharSequence;){}", BUILDER), &[(&name).into()])?;
+
+    if let Some(key) = &person.key {
+        let key = env.new_string(key)?;
+        env.call(&builder, "setKey", format!("(Ljava/lang/String;){}", BUILDER), &[(&key).into()])?;
+    }
+
+    let person = env.call(&builder, "build", "()Landroid/app/Person;", &[])?.l()?;
+    Ok(person)
+}
@@ -0,0 +1,54 @@
+//! JNI method descriptors spelled as Rust-like signatures, so they can't come out malformed.
+//!
+//! `sig!((String, Uri) -> Intent)` expands to the `&'static str`
+//! `"(Ljava/lang/String;Landroid/net/Uri;)Landroid/content/Intent;"`. Types are the primitives
+//! (`int`, `boolean`, ...), `()` for `void`, `[T]` for arrays, the short names of the framework
+//! classes below, or a class literal such as `"android/app/Person"`.
+
+macro_rules! sig {
+    (($($arg:tt),*) -> $ret:tt) => {
+        concat!("(", $($crate::sig::jni_type!($arg),)* ")", $crate::sig::jni_type!($ret))
+    };
+}
+
+pub(crate) use sig;
+
+macro_rules! jni_type {
+    (()) => { "V" };
+    (boolean) => { "Z" };
+    (byte) => { "B" };
+    (char) => { "C" };
+    (short) => { "S" };
+    (int) => { "I" };
+    (long) => { "J" };
+    (float) => { "F" };
+    (double) => { "D" };
+    ([$element:tt]) => { concat!("[", $crate::sig::jni_type!($element)) };
+    (Object) => { "Ljava/lang/Object;" };
+    (String) => { "Ljava/lang/String;" };
+    (CharSequence) => { "Ljava/lang/CharSequence;" };
+    (Class) => { "Ljava/lang/Class;" };
+    (Serializable) => { "Ljava/io/Serializable;" };
+    (ArrayList) => { "Ljava/util/ArrayList;" };
+    (BlockingQueue) => { "Ljava/util/concurrent/BlockingQueue;" };
+    (Intent) => { "Landroid/content/Intent;" };
+    (ComponentName) => { "Landroid/content/ComponentName;" };
+    (Uri) => { "Landroid/net/Uri;" };
+    (Bundle) => { "Landroid/os/Bundle;" };
+    (Parcelable) => { "Landroid/os/Parcelable;" };
+    ($class:literal) => { concat!("L", $class, ";") };
+}
+
+pub(crate) use jni_type;
+
+const _: () = {
+    use crate::constants::str_eq;
+
+    assert!(str_eq(sig!((String, Uri) -> Intent), "(Ljava/lang/String;Landroid/net/Uri;)Landroid/content/Intent;"));
+    assert!(str_eq(sig!(() -> ()), "()V"));
+    assert!(str_eq(
+        sig!((String, [Parcelable]) -> Intent),
+        "(Ljava/lang/String;[Landroid/os/Parcelable;)Landroid/content/Intent;"
+    ));
+    assert!(str_eq(sig!((int, "android/app/Person") -> [[long]]), "(ILandroid/app/Person;)[[J"));
+};
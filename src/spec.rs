@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use jni::AttachGuard;
+
+use crate::{ExtraValue, Intent};
+
+/// A plain Rust description of an intent, independent of any JNI environment.
+///
+/// Use it to persist intents, load them from configuration or compare them in tests. With the
+/// `serde` feature it implements `Serialize` and `Deserialize`.
+/// ```no_run
+/// use android_intent::{ExtraValue, IntentSpec};
+///
+/// let spec = IntentSpec {
+///     action: Some("android.intent.action.SEND".into()),
+///     mime_type: Some("text/plain".into()),
+///     extras: [("android.intent.extra.TEXT".into(), ExtraValue::Str("Hello World!".into()))].into(),
+///     ..Default::default()
+/// };
+///
+/// # let mut intent_env = android_intent::IntentEnv::new();
+/// # android_intent::with_current_env(&mut intent_env, |env| {
+/// spec.realize(env).start_activity().unwrap();
+/// # })
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct IntentSpec {
+    /// The action string, e.g. `android.intent.action.VIEW`.
+    pub action: Option<String>,
+    pub data: Option<String>,
+    pub mime_type: Option<String>,
+    pub package: Option<String>,
+    /// Flattened component name, e.g. `com.example/.MainActivity`.
+    pub component: Option<String>,
+    pub categories: Vec<String>,
+    /// Screen rectangle the intent was launched from as `[left, top, right, bottom]`, see
+    /// `Intent.setSourceBounds`.
+    pub source_bounds: Option<[i32; 4]>,
+    /// Intent used instead of this one to find the target, see `Intent.setSelector`.
+    pub selector: Option<Box<IntentSpec>>,
+    /// Identifier from `Intent.setIdentifier` (API 29+, ignored before).
+    pub identifier: Option<String>,
+    /// Raw `Intent.FLAG_*` bits.
+    pub flags: i32,
+    pub extras: BTreeMap<String, ExtraValue>,
+}
+
+impl IntentSpec {
+    /// Build the described intent.
+    pub fn realize(self, env: AttachGuard<'_>) -> Intent<'_> {
+        Intent::from_spec(env, self)
+    }
+}
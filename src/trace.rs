@@ -0,0 +1,208 @@
+//! Diagnostics for builder steps and JNI calls.
+//!
+//! With the `tracing` feature, every builder step and JNI method call runs in a span and Java
+//! exceptions are reported as events. Without it, the crate logs through [`log`] as before.
+
+use std::cell::RefCell;
+
+use jni::errors::Error;
+use jni::objects::{JClass, JObject, JValue, JValueOwned};
+use jni::JNIEnv;
+
+/// `log::debug!`, or `tracing::debug!` with the `tracing` feature.
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+        #[cfg(not(feature = "tracing"))]
+        log::debug!($($arg)*);
+    }};
+}
+pub(crate) use debug;
+
+/// Keeps the span of a builder step entered until dropped.
+pub(crate) struct StepGuard {
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+/// Enter a span for the builder step `step`.
+pub(crate) fn step(step: &'static str) -> StepGuard {
+    #[cfg(feature = "tracing")]
+    return StepGuard {
+        _span: tracing::debug_span!("intent_step", step).entered(),
+    };
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = step;
+        StepGuard {}
+    }
+}
+
+/// Traced counterparts of [`JNIEnv::call_method`] and [`JNIEnv::call_static_method`].
+pub(crate) trait Traced<'local> {
+    fn call<'other_local>(
+        &mut self,
+        object: impl AsRef<JObject<'other_local>>,
+        method: &str,
+        signature: impl AsRef<str>,
+        args: &[JValue],
+    ) -> Result<JValueOwned<'local>, Error>;
+
+    fn call_static(
+        &mut self,
+        class: &str,
+        method: &str,
+        signature: impl AsRef<str>,
+        args: &[JValue],
+    ) -> Result<JValueOwned<'local>, Error>;
+
+    /// [`Self::call_static`] on a class that was already loaded, e.g. through
+    /// the app's class loader. `class_name` is only used for tracing.
+    fn call_static_on(
+        &mut self,
+        class: &JClass,
+        class_name: &str,
+        method: &str,
+        signature: impl AsRef<str>,
+        args: &[JValue],
+    ) -> Result<JValueOwned<'local>, Error>;
+}
+
+impl<'local> Traced<'local> for JNIEnv<'local> {
+    fn call<'other_local>(
+        &mut self,
+        object: impl AsRef<JObject<'other_local>>,
+        method: &str,
+        signature: impl AsRef<str>,
+        args: &[JValue],
+    ) -> Result<JValueOwned<'local>, Error> {
+        let signature = signature.as_ref();
+        traced(None, method, signature, || self.call_method(object, method, signature, args))
+    }
+
+    fn call_static(
+        &mut self,
+        class: &str,
+        method: &str,
+        signature: impl AsRef<str>,
+        args: &[JValue],
+    ) -> Result<JValueOwned<'local>, Error> {
+        let signature = signature.as_ref();
+        traced(Some(class), method, signature, || self.call_static_method(class, method, signature, args))
+    }
+
+    fn call_static_on(
+        &mut self,
+        class: &JClass,
+        class_name: &str,
+        method: &str,
+        signature: impl AsRef<str>,
+        args: &[JValue],
+    ) -> Result<JValueOwned<'local>, Error> {
+        let signature = signature.as_ref();
+        traced(Some(class_name), method, signature, || self.call_static_method(class, method, signature, args))
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn traced<T>(class: Option<&str>, method: &str, signature: &str, call: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    let span = tracing::trace_span!("jni_call", class, method, signature, duration_us = tracing::field::Empty);
+    let _entered = span.enter();
+
+    let start = std::time::Instant::now();
+    let result = call();
+    span.record("duration_us", start.elapsed().as_micros() as u64);
+
+    if let Err(Error::JavaException) = &result {
+        tracing::warn!(class, method, signature, "Java exception thrown");
+        note_throw(method);
+    }
+
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+fn traced<T>(_class: Option<&str>, method: &str, _signature: &str, call: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    let result = call();
+    if let Err(Error::JavaException) = &result {
+        note_throw(method);
+    }
+
+    result
+}
+
+thread_local! {
+    /// The JNI method that last threw on this thread, for [`take_exception`].
+    static LAST_THROW: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn note_throw(method: &str) {
+    LAST_THROW.with(|last| *last.borrow_mut() = Some(method.to_owned()));
+}
+
+/// Clear the pending Java exception and describe it as [`crate::Error::JavaException`], naming
+/// the method that threw it. `None` if no exception is pending.
+pub(crate) fn take_exception(env: &mut JNIEnv) -> Option<crate::Error> {
+    let throwable = env.exception_occurred().ok()?;
+    env.exception_clear().ok()?;
+    if throwable.is_null() {
+        return None;
+    }
+
+    let method = LAST_THROW.with(|last| last.borrow_mut().take()).unwrap_or_else(|| "a JNI call".to_owned());
+    let exception = crate::bundle::to_string(env, &throwable).ok()?;
+
+    Some(crate::Error::JavaException { method, exception })
+}
+
+/// Run `f`, clearing a Java exception it fails with so the caller's next JNI call works, see
+/// [`clear_exception`].
+pub(crate) fn checked<'a, T>(
+    env: &mut JNIEnv<'a>,
+    f: impl FnOnce(&mut JNIEnv<'a>) -> Result<T, crate::Error>,
+) -> Result<T, crate::Error> {
+    f(env).map_err(|err| clear_exception(env, err))
+}
+
+/// `err`, or, if it is a pending Java exception, that exception described and cleared.
+pub(crate) fn clear_exception(env: &mut JNIEnv, err: crate::Error) -> crate::Error {
+    describe_exception(err, || take_exception(env))
+}
+
+/// Replace a bare [`Error::JavaException`] with what `take` describes, if anything.
+fn describe_exception(err: crate::Error, take: impl FnOnce() -> Option<crate::Error>) -> crate::Error {
+    match err {
+        crate::Error::Jni(Error::JavaException) => take().unwrap_or(crate::Error::Jni(Error::JavaException)),
+        err => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_java_exceptions() {
+        let described = describe_exception(crate::Error::Jni(Error::JavaException), || {
+            Some(crate::Error::JavaException { method: "getType".into(), exception: "java.lang.Boom".into() })
+        });
+        assert!(matches!(described, crate::Error::JavaException { method, .. } if method == "getType"));
+    }
+
+    #[test]
+    fn keeps_the_error_without_a_pending_exception() {
+        let described = describe_exception(crate::Error::Jni(Error::JavaException), || None);
+        assert!(matches!(described, crate::Error::Jni(Error::JavaException)));
+    }
+
+    #[test]
+    fn leaves_other_errors_alone() {
+        let described = describe_exception(crate::Error::NullContext, || panic!("nothing to take"));
+        assert!(matches!(described, crate::Error::NullContext));
+
+        let described = describe_exception(crate::Error::Jni(Error::NullPtr("env")), || panic!("nothing to take"));
+        assert!(matches!(described, crate::Error::Jni(Error::NullPtr(_))));
+    }
+}
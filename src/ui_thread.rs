@@ -0,0 +1,137 @@
+//! Launching from threads other than the UI thread.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use jni::objects::{GlobalRef, JObject};
+use jni::JNIEnv;
+
+use crate::trace::{debug, Traced};
+use crate::{bridge, bundle, context, observe, Error, IntentSpec};
+
+const LOOPER_CLASS: &str = "android/os/Looper";
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+/// Opt into strict threading checks. Launches from threads without a prepared `Looper` then
+/// fail with [`Error::WrongThread`] instead of misbehaving, as does [`UiLaunch::wait`] on the UI
+/// thread itself.
+pub fn set_strict_mode(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+/// Whether this is the app's main (UI) thread, i.e. `Looper.myLooper()` is the main looper.
+pub fn is_main_thread(env: &mut JNIEnv) -> Result<bool, Error> {
+    let mine = my_looper(env)?;
+    let main = env
+        .call_static(LOOPER_CLASS, "getMainLooper", "()Landroid/os/Looper;", &[])?
+        .l()?;
+
+    Ok(!mine.is_null() && env.is_same_object(&mine, &main)?)
+}
+
+fn my_looper<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+    let looper = env
+        .call_static(LOOPER_CLASS, "myLooper", "()Landroid/os/Looper;", &[])?
+        .l()?;
+
+    Ok(looper)
+}
+
+/// In strict mode, fail unless this thread has a prepared `Looper` to launch from.
+pub(crate) fn check_launch_thread(env: &mut JNIEnv) -> Result<(), Error> {
+    if STRICT.load(Ordering::Relaxed) && my_looper(env)?.is_null() {
+        return Err(Error::WrongThread("launching from a thread without a Looper; use start_activity_on_ui_thread"));
+    }
+
+    Ok(())
+}
+
+/// A launch posted to the UI thread by
+/// [`Intent::start_activity_on_ui_thread`](crate::Intent::start_activity_on_ui_thread).
+#[must_use]
+pub struct UiLaunch {
+    receiver: mpsc::Receiver<Result<(), Error>>,
+}
+
+impl UiLaunch {
+    pub(crate) fn done(outcome: Result<(), Error>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let _ = sender.send(outcome);
+        Self { receiver }
+    }
+
+    /// Block until the UI thread ran the launch.
+    pub fn wait(self) -> Result<(), Error> {
+        if STRICT.load(Ordering::Relaxed) {
+            let vm = context::java_vm()?;
+            let mut env = vm.attach_current_thread()?;
+            if is_main_thread(&mut env)? {
+                return Err(Error::WrongThread("blocking on the UI thread"));
+            }
+        }
+
+        self.receiver.recv().unwrap_or(Err(Error::LaunchFailed("UI thread never reported back".to_owned())))
+    }
+
+    /// The outcome, if the UI thread already ran the launch.
+    pub fn try_wait(&self) -> Option<Result<(), Error>> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// The channel the outcome is sent on, e.g. to `select` over it.
+    pub fn into_receiver(self) -> mpsc::Receiver<Result<(), Error>> {
+        self.receiver
+    }
+}
+
+/// Ask the activity to start `intent` on its UI thread, through the app-side
+/// `startActivityOnUiThread` [bridge](crate::bridge) method.
+///
+/// `spec`, if given, is reported to the [launch observer](crate::observe) once the UI thread
+/// ran the launch.
+pub(crate) fn post_start_activity(
+    env: &mut JNIEnv,
+    intent: &JObject,
+    request_code: Option<i32>,
+    spec: Option<IntentSpec>,
+) -> Result<UiLaunch, Error> {
+    let done = env.new_object("java/util/concurrent/LinkedBlockingQueue", "()V", &[])?;
+
+    let activity = context::activity(env)?;
+    env.call(
+        &activity,
+        bridge::START_ON_UI_THREAD,
+        bridge::START_ON_UI_THREAD_SIG,
+        &[intent.into(), request_code.unwrap_or(-1).into(), (&done).into()],
+    )?;
+
+    let done = env.new_global_ref(done)?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let outcome = wait(&done);
+        if let Some(spec) = &spec {
+            observe::notify(spec, outcome.as_ref().copied());
+        }
+        let _ = sender.send(outcome);
+    });
+
+    Ok(UiLaunch { receiver })
+}
+
+/// Block until the bridge reports back on `done`: `Boolean.TRUE` on success, or the `Throwable`
+/// `startActivity` threw.
+fn wait(done: &GlobalRef) -> Result<(), Error> {
+    let vm = context::java_vm()?;
+    let mut env = vm.attach_current_thread_as_daemon()?;
+
+    let outcome = env.call(done.as_obj(), "take", "()Ljava/lang/Object;", &[])?.l()?;
+    if env.is_instance_of(&outcome, "java/lang/Throwable")? {
+        let message = bundle::to_string(&mut env, &outcome)?;
+        debug!("UI thread launch failed: {}", message);
+        return Err(Error::LaunchFailed(message));
+    }
+
+    Ok(())
+}
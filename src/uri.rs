@@ -0,0 +1,154 @@
+//! URIs, and percent-encoding that matches `android.net.Uri`.
+
+use std::fmt;
+
+/// A URI, passed to Java as an `android.net.Uri`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Uri(String);
+
+impl Uri {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self(uri.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// A `tel:` URI dialing `number`, escaped like `Uri.fromParts("tel", number, null)`.
+    /// ```
+    /// use android_intent::Uri;
+    ///
+    /// assert_eq!(Uri::tel("*100#").as_str(), "tel:*100%23");
+    /// ```
+    pub fn tel(number: &str) -> Self {
+        Self(format!("tel:{}", encode(number, "")))
+    }
+
+    /// An `smsto:` URI for a text message to `number`.
+    pub fn smsto(number: &str) -> Self {
+        Self(format!("smsto:{}", encode(number, "")))
+    }
+
+    /// A `mailto:` URI for an email to `address`.
+    pub fn mailto(address: &str) -> Self {
+        Self(format!("mailto:{}", encode(address, "@")))
+    }
+
+    /// A `geo:` URI for the point at `latitude`,`longitude`, shown with a pin named `label` by
+    /// map apps that support the `q=lat,lng(label)` query.
+    /// ```
+    /// use android_intent::Uri;
+    ///
+    /// assert_eq!(Uri::geo(59.91, 10.75, Some("Oslo S")).as_str(), "geo:59.91,10.75?q=59.91,10.75(Oslo%20S)");
+    /// ```
+    pub fn geo(latitude: f64, longitude: f64, label: Option<&str>) -> Self {
+        match label {
+            Some(label) => Self(format!("geo:{0},{1}?q={0},{1}({2})", latitude, longitude, encode(label, ""))),
+            None => Self(format!("geo:{},{}", latitude, longitude)),
+        }
+    }
+
+    /// A `market://details` URI for the store listing of `package`.
+    pub fn market(package: &str) -> Self {
+        Self(format!("market://details?id={}", encode(package, "")))
+    }
+}
+
+impl AsRef<str> for Uri {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Uri {
+    fn from(uri: String) -> Self {
+        Self(uri)
+    }
+}
+
+impl From<&str> for Uri {
+    fn from(uri: &str) -> Self {
+        Self(uri.to_owned())
+    }
+}
+
+/// Characters `Uri.encode` never escapes.
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_-!.~'()*".contains(c)
+}
+
+/// Percent-encode `s` as `Uri.encode(s, allow)` does: UTF-8, with uppercase hex digits, leaving
+/// letters, digits, `_-!.~'()*` and the characters in `allow` as they are.
+/// ```
+/// use android_intent::uri;
+///
+/// assert_eq!(uri::encode("Hi there, 50% off?", ""), "Hi%20there%2C%2050%25%20off%3F");
+/// assert_eq!(uri::encode("a/b c", "/"), "a/b%20c");
+/// ```
+pub fn encode(s: &str, allow: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for c in s.chars() {
+        if is_unreserved(c) || allow.contains(c) {
+            encoded.push(c);
+        } else {
+            let mut utf8 = [0; 4];
+            for byte in c.encode_utf8(&mut utf8).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Decode the `%XX` escapes in `s` as `Uri.decode` does. `+` stays as it is, and invalid
+/// escapes or UTF-8 become U+FFFD instead of failing.
+/// ```
+/// use android_intent::uri;
+///
+/// assert_eq!(uri::decode("caf%C3%A9+bar"), "café+bar");
+/// assert_eq!(uri::decode("100%"), "100\u{fffd}");
+/// ```
+pub fn decode(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut bytes = Vec::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            decoded.push_str(&String::from_utf8_lossy(&bytes));
+            bytes.clear();
+            decoded.push(c);
+            continue;
+        }
+
+        let high = chars.next().map(|c| c.to_digit(16));
+        let low = chars.next().map(|c| c.to_digit(16));
+        match (high, low) {
+            (Some(Some(high)), Some(Some(low))) => bytes.push((high * 16 + low) as u8),
+            (Some(_), Some(_)) => {
+                decoded.push_str(&String::from_utf8_lossy(&bytes));
+                bytes.clear();
+                decoded.push(char::REPLACEMENT_CHARACTER);
+            }
+            // The input ends inside the escape.
+            _ => {
+                decoded.push_str(&String::from_utf8_lossy(&bytes));
+                decoded.push(char::REPLACEMENT_CHARACTER);
+                return decoded;
+            }
+        }
+    }
+    decoded.push_str(&String::from_utf8_lossy(&bytes));
+
+    decoded
+}
@@ -0,0 +1,115 @@
+//! Checks for common intent mistakes, before launching.
+
+use std::fmt;
+
+use crate::{Flags, IntentSpec};
+
+const ACTION_SEND: &str = "android.intent.action.SEND";
+const ACTION_SEND_MULTIPLE: &str = "android.intent.action.SEND_MULTIPLE";
+const ACTION_GET_CONTENT: &str = "android.intent.action.GET_CONTENT";
+pub(crate) const ACTION_CALL: &str = "android.intent.action.CALL";
+const CATEGORY_OPENABLE: &str = "android.intent.category.OPENABLE";
+const EXTRA_STREAM: &str = "android.intent.extra.STREAM";
+
+/// A likely mistake found by [`IntentSpec::validate`] or [`Intent::validate`](crate::Intent::validate).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Diagnostic {
+    /// `ACTION_SEND`/`ACTION_SEND_MULTIPLE` without a MIME type, so no app matches.
+    SendWithoutType,
+    /// `EXTRA_STREAM` without `FLAG_GRANT_READ_URI_PERMISSION`, so the receiver can't read a
+    /// `content:` URI.
+    StreamWithoutReadGrant,
+    /// `ACTION_GET_CONTENT` without `CATEGORY_OPENABLE`, so pickers may return URIs that can't be
+    /// opened as streams.
+    GetContentWithoutOpenable,
+    /// `ACTION_CALL` needs the `CALL_PHONE` permission, or it throws `SecurityException`.
+    CallWithoutPermission,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SendWithoutType => "SEND without a MIME type",
+            Self::StreamWithoutReadGrant => "EXTRA_STREAM without FLAG_GRANT_READ_URI_PERMISSION",
+            Self::GetContentWithoutOpenable => "GET_CONTENT without CATEGORY_OPENABLE",
+            Self::CallWithoutPermission => "CALL without the CALL_PHONE permission",
+        })
+    }
+}
+
+impl IntentSpec {
+    /// Check for common mistakes, without touching the device. [`Diagnostic::CallWithoutPermission`]
+    /// is reported for every `ACTION_CALL`; [`Intent::validate`](crate::Intent::validate) checks
+    /// the permission.
+    /// ```
+    /// use android_intent::{validate::Diagnostic, IntentSpec};
+    ///
+    /// let spec = IntentSpec { action: Some("android.intent.action.SEND".to_owned()), ..Default::default() };
+    /// assert_eq!(spec.validate(), [Diagnostic::SendWithoutType]);
+    /// ```
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let action = self.action.as_deref();
+
+        if matches!(action, Some(ACTION_SEND | ACTION_SEND_MULTIPLE)) && self.mime_type.is_none() {
+            diagnostics.push(Diagnostic::SendWithoutType);
+        }
+        let read_granted = Flags::from_bits_retain(self.flags as u32).contains(Flags::GRANT_READ_URI_PERMISSION);
+        if self.extras.contains_key(EXTRA_STREAM) && !read_granted {
+            diagnostics.push(Diagnostic::StreamWithoutReadGrant);
+        }
+        if action == Some(ACTION_GET_CONTENT) && !self.categories.iter().any(|category| category == CATEGORY_OPENABLE) {
+            diagnostics.push(Diagnostic::GetContentWithoutOpenable);
+        }
+        if action == Some(ACTION_CALL) {
+            diagnostics.push(Diagnostic::CallWithoutPermission);
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(action: &str) -> IntentSpec {
+        IntentSpec { action: Some(action.to_owned()), ..Default::default() }
+    }
+
+    #[test]
+    fn flags_each_mistake() {
+        let mut send = spec(ACTION_SEND_MULTIPLE);
+        send.extras.insert(EXTRA_STREAM.to_owned(), crate::ExtraValue::Str("content://a/b".to_owned()));
+        assert_eq!(send.validate(), [Diagnostic::SendWithoutType, Diagnostic::StreamWithoutReadGrant]);
+
+        assert_eq!(spec(ACTION_GET_CONTENT).validate(), [Diagnostic::GetContentWithoutOpenable]);
+        assert_eq!(spec(ACTION_CALL).validate(), [Diagnostic::CallWithoutPermission]);
+    }
+
+    #[test]
+    fn accepts_well_formed_intents() {
+        let mut send = spec(ACTION_SEND);
+        send.mime_type = Some("image/png".to_owned());
+        send.flags = Flags::GRANT_READ_URI_PERMISSION.bits() as i32;
+        send.extras.insert(EXTRA_STREAM.to_owned(), crate::ExtraValue::Str("content://a/b".to_owned()));
+        assert_eq!(send.validate(), []);
+
+        let mut get_content = spec(ACTION_GET_CONTENT);
+        get_content.categories.push(CATEGORY_OPENABLE.to_owned());
+        assert_eq!(get_content.validate(), []);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn validates_mock_intents() {
+        use crate::{Action, Intent};
+
+        let mut intent = Intent::mock(Action::Send);
+        assert_eq!(intent.validate().unwrap(), [Diagnostic::SendWithoutType]);
+
+        // Without a device, the permission can't be checked.
+        let mut intent = Intent::mock("ACTION_CALL");
+        assert_eq!(intent.validate().unwrap(), [Diagnostic::CallWithoutPermission]);
+    }
+}
@@ -0,0 +1,87 @@
+//! Home screen widgets, via `AppWidgetManager`.
+//!
+//! ```no_run
+//! use android_intent::widgets;
+//!
+//! # let mut intent_env = android_intent::IntentEnv::new();
+//! # android_intent::with_current_env(&mut intent_env, |mut env| {
+//! if widgets::is_pin_supported(&mut env).unwrap() {
+//!     widgets::request_pin(&mut env, "com.example.app/.ClockWidget", None, None).unwrap();
+//! }
+//! # })
+//! ```
+use std::collections::BTreeMap;
+
+use jni::{objects::JObject, JNIEnv};
+
+use crate::trace::Traced;
+use crate::{backend, context, pending, ApiLevel, Error, ExtraValue, IntentSpec};
+
+const APP_WIDGET_MANAGER_CLASS: &str = "android/appwidget/AppWidgetManager";
+
+fn app_widget_manager<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+    let activity = context::activity(env)?;
+    let manager = env
+        .call_static(
+            APP_WIDGET_MANAGER_CLASS,
+            "getInstance",
+            "(Landroid/content/Context;)Landroid/appwidget/AppWidgetManager;",
+            &[(&activity).into()],
+        )?
+        .l()?;
+
+    Ok(manager)
+}
+
+/// Whether the launcher supports pinning widgets. Always `false` below API 26.
+pub fn is_pin_supported(env: &mut JNIEnv) -> Result<bool, Error> {
+    if !ApiLevel(26).is_supported(env)? {
+        return Ok(false);
+    }
+
+    let manager = app_widget_manager(env)?;
+    let supported = env
+        .call(manager, "isRequestPinAppWidgetSupported", "()Z", &[])?
+        .z()?;
+
+    Ok(supported)
+}
+
+/// Ask the launcher to place the widget of `provider`, the flattened component name of its
+/// `AppWidgetProvider` (e.g. `com.example.app/.ClockWidget`). The user confirms in a system
+/// dialog.
+///
+/// `preview_options` is passed to the launcher as the extras `Bundle`. If given, `on_success` is
+/// sent as a broadcast once the widget was placed; the system adds
+/// `AppWidgetManager.EXTRA_APPWIDGET_ID` to it. Returns whether the request was passed on to the
+/// launcher.
+pub fn request_pin(
+    env: &mut JNIEnv,
+    provider: &str,
+    preview_options: Option<&BTreeMap<String, ExtraValue>>,
+    on_success: Option<&IntentSpec>,
+) -> Result<bool, Error> {
+    ApiLevel(26).require(env)?;
+
+    let provider = backend::component_name(env, provider)?;
+    let options = match preview_options {
+        Some(options) => backend::new_bundle(env, options)?,
+        None => JObject::null(),
+    };
+    let callback = match on_success {
+        Some(on_success) => pending::broadcast(env, on_success, 0, true)?,
+        None => JObject::null(),
+    };
+
+    let manager = app_widget_manager(env)?;
+    let requested = env
+        .call(
+            manager,
+            "requestPinAppWidget",
+            "(Landroid/content/ComponentName;Landroid/os/Bundle;Landroid/app/PendingIntent;)Z",
+            &[(&provider).into(), (&options).into(), (&callback).into()],
+        )?
+        .z()?;
+
+    Ok(requested)
+}
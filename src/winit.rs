@@ -0,0 +1,133 @@
+//! Forwarding activity results and broadcasts into a winit event loop as user events, so GUI
+//! apps handle them in their normal event handling instead of polling.
+//!
+//! Needs the `winit` feature and the [bridge](crate::bridge) activity. The event loop's user
+//! event type converts from [`IntentEvent`]:
+//! ```no_run
+//! use android_intent::winit::{forward_results, IntentEvent};
+//! use winit::event_loop::EventLoop;
+//!
+//! #[derive(Debug)]
+//! enum UserEvent {
+//!     Intent(IntentEvent),
+//! }
+//!
+//! impl From<IntentEvent> for UserEvent {
+//!     fn from(event: IntentEvent) -> Self {
+//!         Self::Intent(event)
+//!     }
+//! }
+//!
+//! # let intent_env = android_intent::IntentEnv::new();
+//! let event_loop = EventLoop::<UserEvent>::with_user_event().build().unwrap();
+//! forward_results(&mut intent_env.get_env(), event_loop.create_proxy()).unwrap();
+//! // Results now arrive in `ApplicationHandler::user_event`.
+//! ```
+
+use std::thread::{self, JoinHandle};
+
+use ::winit::event_loop::EventLoopProxy;
+use jni::objects::GlobalRef;
+use jni::JNIEnv;
+
+use crate::receivers::Receiver;
+use crate::trace::{debug, Traced};
+use crate::{context, host, CompletedIntent, Error, IntentSpec};
+
+/// A result or broadcast forwarded to the event loop.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IntentEvent {
+    /// An activity launched for its result finished. `data` is `None` if it returned none.
+    ActivityResult { request_code: i32, result_code: i32, data: Option<IntentSpec> },
+    /// A broadcast arrived at a [`Receiver`] passed to [`forward_broadcasts`].
+    Broadcast(IntentSpec),
+}
+
+/// Send every activity result to `proxy` as it arrives, until the activity is destroyed or the
+/// event loop exits. [`requests`](crate::requests) callbacks still run first, but until then the
+/// results don't reach [`Intent::get_result`](crate::Intent::get_result).
+pub fn forward_results<T>(env: &mut JNIEnv, proxy: EventLoopProxy<T>) -> Result<(), Error>
+where
+    T: From<IntentEvent> + Send + 'static,
+{
+    let results = env.new_object("java/util/concurrent/LinkedBlockingQueue", "()V", &[])?;
+    let activity = context::activity(env)?;
+    host::with_host(env, &activity, |host, env| host.register_result_queue(env, &activity, &results))?;
+
+    let results = env.new_global_ref(results)?;
+    thread::spawn(move || {
+        if let Err(err) = forward(&results, &proxy) {
+            debug!("forwarding activity results failed: {}", err);
+        }
+        // Results arriving from now on go to `Intent::get_result` again.
+        if let Err(err) = unregister(&results) {
+            debug!("unregistering the result queue failed: {}", err);
+        }
+    });
+
+    Ok(())
+}
+
+/// Send every broadcast `receiver` gets to `proxy`, until the event loop exits. The returned
+/// thread then hands the receiver back to be unregistered; it only notices the exit with the
+/// next broadcast.
+pub fn forward_broadcasts<T>(receiver: Receiver, proxy: EventLoopProxy<T>) -> JoinHandle<Result<Receiver, Error>>
+where
+    T: From<IntentEvent> + Send + 'static,
+{
+    thread::spawn(move || {
+        let vm = context::java_vm()?;
+        let mut env = vm.attach_current_thread_as_daemon()?;
+
+        loop {
+            let broadcast = receiver.recv(&mut env)?;
+            if proxy.send_event(IntentEvent::Broadcast(broadcast).into()).is_err() {
+                return Ok(receiver);
+            }
+        }
+    })
+}
+
+/// Take results the bridge posts on `results` until it reports the activity destroyed.
+fn forward<T>(results: &GlobalRef, proxy: &EventLoopProxy<T>) -> Result<(), Error>
+where
+    T: From<IntentEvent> + Send + 'static,
+{
+    let vm = context::java_vm()?;
+    let mut env = vm.attach_current_thread_as_daemon()?;
+
+    loop {
+        let result = env.call(results.as_obj(), "take", "()Ljava/lang/Object;", &[])?.l()?;
+        if env.is_instance_of(&result, "java/lang/String")? {
+            debug!("activity destroyed, no longer forwarding results");
+            return Ok(());
+        }
+
+        let mut completed = CompletedIntent::from_result(vm.attach_current_thread()?, &result)?;
+        env.delete_local_ref(result)?;
+        crate::requests::complete(&mut completed);
+
+        let event = IntentEvent::ActivityResult {
+            request_code: completed.request_code,
+            result_code: completed.result_code,
+            data: completed.data.to_spec().ok(),
+        };
+        if proxy.send_event(event.into()).is_err() {
+            debug!("event loop exited, no longer forwarding results");
+            return Ok(());
+        }
+    }
+}
+
+/// Stop the bridge posting results on `results`, unless the activity is already gone.
+fn unregister(results: &GlobalRef) -> Result<(), Error> {
+    let vm = context::java_vm()?;
+    let mut env = vm.attach_current_thread()?;
+    let activity = match context::activity(&mut env) {
+        Ok(activity) => activity,
+        Err(Error::NullContext) => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    host::with_host(&mut env, &activity, |host, env| host.unregister_result_queue(env, &activity, results.as_obj()))
+}